@@ -0,0 +1,166 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use inquire::Confirm;
+use log::{error, info};
+
+use crate::{
+    require_interactive,
+    utils::{get_dir_size, remove_dir, BACKUP_DIR, BACKUP_MANIFEST},
+    Profile, THEME,
+};
+
+/// A backup created by the tool.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub age: Duration,
+}
+
+/// Lists the tool-created backups and removes those older than the given age.
+///
+/// The backups are the mod snapshots of `mod backup` in the `.dayz-backups` folder of
+/// the profile's workdir, see `find_backups`. All backups are listed with their age and
+/// size. The ones older than `older_than_days` are removed after a confirmation, or
+/// right away if `yes` is set.
+pub fn clean_backups(profile: &Profile, older_than_days: u64, yes: bool) -> io::Result<()> {
+    let backups = find_backups(Path::new(&profile.workdir_path));
+    if backups.is_empty() {
+        println!("{}", THEME.value_italic("No backups found."));
+        return Ok(());
+    }
+
+    let max_age = Duration::from_secs(older_than_days * 24 * 60 * 60);
+    for backup in &backups {
+        let marker = if backup.age > max_age { "*" } else { " " };
+        println!(
+            "{} {} ({} days, {} bytes)",
+            marker,
+            THEME.value(backup.path.display().to_string()),
+            backup.age.as_secs() / (24 * 60 * 60),
+            backup.size
+        );
+    }
+
+    let old_count = backups.iter().filter(|b| b.age > max_age).count();
+    if old_count == 0 {
+        info!("No backups older than {} days", older_than_days);
+        return Ok(());
+    }
+
+    let confirmed = yes || {
+        require_interactive("--yes to confirm the removal").map_err(io::Error::other)?;
+        Confirm::new(&format!(
+            "Remove the {} backup(s) older than {} days (marked with *)?",
+            old_count, older_than_days
+        ))
+        .with_default(false)
+        .prompt()
+        .unwrap_or(false)
+    };
+
+    if confirmed {
+        let removed = remove_old_backups(&backups, max_age)?;
+        info!("Removed {} backup(s)", removed.len());
+    }
+
+    Ok(())
+}
+
+/// Finds the mod snapshots in the `.dayz-backups` folder of the given workdir.
+///
+/// Only folders with the `manifest.json` of `backup_mods` are backups, other files in
+/// the workdir are never touched. The age of a snapshot is the age of its manifest,
+/// which is written last. The snapshots are sorted by name, i.e. oldest first.
+pub fn find_backups(workdir: &Path) -> Vec<BackupFile> {
+    let now = SystemTime::now();
+    let Ok(entries) = workdir.join(BACKUP_DIR).read_dir() else {
+        return Vec::new();
+    };
+
+    let mut backups: Vec<BackupFile> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let metadata = path.join(BACKUP_MANIFEST).metadata().ok()?;
+            let age = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .unwrap_or_default();
+            Some(BackupFile {
+                size: get_dir_size(&path),
+                path,
+                age,
+            })
+        })
+        .collect();
+    backups.sort_by(|a, b| a.path.cmp(&b.path));
+
+    backups
+}
+
+/// Removes the backups older than `max_age` and returns their paths.
+pub fn remove_old_backups(backups: &[BackupFile], max_age: Duration) -> io::Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+
+    for backup in backups.iter().filter(|b| b.age > max_age) {
+        if let Err(e) = remove_dir(&backup.path) {
+            error!("Failed to remove {}: {}", backup.path.display(), e);
+            return Err(e);
+        }
+        removed.push(backup.path.clone());
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{self, File};
+
+    #[test]
+    fn test_remove_old_backups() {
+        let workdir = std::env::temp_dir().join("dayz_tool_clean_backups");
+        let _ = fs::remove_dir_all(&workdir);
+        let backup_dir = workdir.join(BACKUP_DIR);
+        let old_backup = backup_dir.join("2024-01-01_12-00-00");
+        let new_backup = backup_dir.join("2024-03-01_12-00-00");
+        for snapshot in [&old_backup, &new_backup] {
+            fs::create_dir_all(snapshot.join("@CF")).unwrap();
+            fs::write(snapshot.join("@CF/mod.pbo"), "pbo").unwrap();
+            fs::write(snapshot.join(BACKUP_MANIFEST), "{}").unwrap();
+        }
+        fs::create_dir_all(backup_dir.join("unrelated")).unwrap();
+        fs::write(workdir.join("serverDZ.cfg.bak"), "user backup").unwrap();
+
+        let sixty_days = Duration::from_secs(60 * 24 * 60 * 60);
+        File::options()
+            .write(true)
+            .open(old_backup.join(BACKUP_MANIFEST))
+            .unwrap()
+            .set_modified(SystemTime::now() - sixty_days)
+            .unwrap();
+
+        let backups = find_backups(&workdir);
+        assert_eq!(
+            backups.iter().map(|b| &b.path).collect::<Vec<_>>(),
+            vec![&old_backup, &new_backup]
+        );
+        assert_eq!(backups[0].size, 5);
+
+        let removed = remove_old_backups(&backups, Duration::from_secs(30 * 24 * 60 * 60)).unwrap();
+
+        assert_eq!(removed, vec![old_backup.clone()]);
+        assert!(!old_backup.exists());
+        assert!(new_backup.exists());
+        assert!(workdir.join("serverDZ.cfg.bak").exists());
+
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+}
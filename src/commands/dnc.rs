@@ -1,5 +1,11 @@
-use crate::DncError;
+use crate::{is_dry_run, DncChange, DncError};
+use log::info;
 use regex::Regex;
+use std::{
+    fs::{read_to_string, write},
+    io,
+    path::Path,
+};
 
 /// Calculates DayZ server settings for Day/Night cycle acceleration.
 ///
@@ -29,10 +35,217 @@ pub fn calculate_dnc(day: &str, night: &str) -> Result<(f32, f32), DncError> {
     validate_dnc(time_acceleration, night_time_acceleration)
 }
 
+/// Calculates the effective day and night lengths of the given accelerations.
+///
+/// This is the reverse of `calculate_dnc` and is used to verify that the calculated
+/// values actually produce the requested lengths.
+///
+/// # Arguments
+///
+/// * `time_acceleration`: The `serverTimeAcceleration` value.
+/// * `night_time_acceleration`: The `serverNightTimeAcceleration` value.
+///
+/// # Returns
+///
+/// A tuple with the effective day and night lengths in minutes.
+pub fn reverse_dnc(time_acceleration: f32, night_time_acceleration: f32) -> (f32, f32) {
+    let full_day_duration = 720.0;
+
+    let day_time = full_day_duration / time_acceleration;
+    let night_time = full_day_duration / (night_time_acceleration * time_acceleration);
+
+    (day_time, night_time)
+}
+
+/// Calculates the day and night lengths of existing acceleration values.
+///
+/// The values are checked against the same range of 0.1 to 64.0 as `calculate_dnc`.
+/// The lengths are formatted like the input of `calculate_dnc` (e.g. "8h", "1h30min"),
+/// with a seconds component if they are not whole minutes.
+///
+/// # Arguments
+///
+/// * `time_accel`: The `serverTimeAcceleration` value.
+/// * `night_accel`: The `serverNightTimeAcceleration` value.
+///
+/// # Returns
+///
+/// A `Result` object containing the day and night lengths.
+/// If a value is out of range, an `Err` result with a `DncError` is returned.
+pub fn calculate_dnc_reverse(
+    time_accel: f32,
+    night_accel: f32,
+) -> Result<(String, String), DncError> {
+    let (time_accel, night_accel) = validate_dnc(time_accel, night_accel)?;
+    let (day_time, night_time) = reverse_dnc(time_accel, night_accel);
+
+    Ok((
+        format_duration(day_time).replace(' ', ""),
+        format_duration(night_time).replace(' ', ""),
+    ))
+}
+
+/// Formats a number of minutes as a human-readable duration (e.g. "7h 58min").
+pub fn format_duration(minutes: f32) -> String {
+    let total_seconds = (minutes * 60.0).round() as u64;
+    let hours = total_seconds / 3600;
+    let mins = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+
+    let mut parts = Vec::new();
+    if hours > 0 {
+        parts.push(format!("{}h", hours));
+    }
+    if mins > 0 || (hours == 0 && seconds == 0) {
+        parts.push(format!("{}min", mins));
+    }
+    if seconds > 0 {
+        parts.push(format!("{}s", seconds));
+    }
+
+    parts.join(" ")
+}
+
+/// Builds a reference chart of common day/night length combinations.
+///
+/// Iterates `calculate_dnc` over day lengths from 1h to 12h and night lengths
+/// from 10min to 2h. Each cell shows `serverTimeAcceleration/serverNightTimeAcceleration`,
+/// combinations outside the valid range of 0.1 to 64.0 are flagged with `out`.
+///
+/// # Returns
+///
+/// The formatted table as a `String`, ready to be printed.
+pub fn dnc_table() -> String {
+    let nights = [
+        "10min", "20min", "30min", "45min", "60min", "90min", "120min",
+    ];
+    let mut table = format!("{:<6}", "day");
+
+    for night in nights {
+        table.push_str(&format!("{:>11}", night));
+    }
+    table.push('\n');
+
+    for hours in 1..=12 {
+        let day = format!("{}h", hours);
+        table.push_str(&format!("{:<6}", day));
+
+        for night in nights {
+            let cell = match calculate_dnc(&day, night) {
+                Ok((time_acceleration, night_time_acceleration)) => {
+                    format!("{:.1}/{:.1}", time_acceleration, night_time_acceleration)
+                }
+                Err(_) => "out".to_string(),
+            };
+            table.push_str(&format!("{:>11}", cell));
+        }
+        table.push('\n');
+    }
+
+    table
+}
+
+/// Writes the day/night accelerations into a `serverDZ.cfg` file.
+///
+/// Existing `serverTimeAcceleration` and `serverNightTimeAcceleration` lines are replaced
+/// in place, keeping their indentation and trailing comments; missing keys are appended.
+/// In dry-run mode the before/after lines are logged and the file is left untouched.
+///
+/// # Arguments
+///
+/// * `cfg_path`: The path to the `serverDZ.cfg` file.
+/// * `time_acceleration`: The `serverTimeAcceleration` value.
+/// * `night_time_acceleration`: The `serverNightTimeAcceleration` value.
+///
+/// # Returns
+///
+/// The changed lines, keys that already have the value are left out.
+pub fn write_dnc(
+    cfg_path: &Path,
+    time_acceleration: f32,
+    night_time_acceleration: f32,
+) -> io::Result<Vec<DncChange>> {
+    let content = read_to_string(cfg_path)?;
+    let (updated, changes) = apply_dnc(&content, time_acceleration, night_time_acceleration);
+
+    if is_dry_run() {
+        info!("[dry-run] Would change {}:", cfg_path.display());
+        for change in &changes {
+            if let Some(before) = &change.before {
+                info!("[dry-run] - {}", before);
+            }
+            info!("[dry-run] + {}", change.after);
+        }
+        return Ok(changes);
+    }
+
+    if !changes.is_empty() {
+        write(cfg_path, updated)?;
+    }
+    Ok(changes)
+}
+
+/// Replaces or appends the acceleration lines in the content of a `serverDZ.cfg` file,
+/// see `write_dnc`.
+fn apply_dnc(
+    content: &str,
+    time_acceleration: f32,
+    night_time_acceleration: f32,
+) -> (String, Vec<DncChange>) {
+    let values = [
+        ("serverTimeAcceleration", time_acceleration),
+        ("serverNightTimeAcceleration", night_time_acceleration),
+    ];
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut changes = Vec::new();
+
+    for (key, value) in values {
+        let position = lines.iter().position(|line| {
+            line.trim_start()
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        });
+
+        match position {
+            Some(index) => {
+                let line = &lines[index];
+                let indent = &line[..line.len() - line.trim_start().len()];
+                let comment = line.find(';').map_or("", |end| &line[end + 1..]);
+                let after = format!("{}{} = {};{}", indent, key, value, comment);
+                if *line != after {
+                    changes.push(DncChange {
+                        key,
+                        before: Some(line.clone()),
+                        after: after.clone(),
+                    });
+                    lines[index] = after;
+                }
+            }
+            None => {
+                let after = format!("{} = {};", key, value);
+                changes.push(DncChange {
+                    key,
+                    before: None,
+                    after: after.clone(),
+                });
+                lines.push(after);
+            }
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') || content.is_empty() {
+        updated.push('\n');
+    }
+    (updated, changes)
+}
+
 /// Parses a time string into a number of minutes.
 ///
-/// The function expects a time string in the format "<number>h" or "<number>min",
-/// where <number> is a valid floating-point number.
+/// The function expects a time string of one or more "<number>h" or "<number>min"
+/// components, e.g. "90min", "1.5h" or "1h30min", where <number> is a non-negative
+/// decimal number. The components are added up. Every component needs a unit, so
+/// "1h30" is rejected.
 ///
 /// # Arguments
 ///
@@ -43,22 +256,24 @@ pub fn calculate_dnc(day: &str, night: &str) -> Result<(f32, f32), DncError> {
 /// A `Result` object containing the parsed time in minutes as a `f32`.
 /// If an error occurs, an `Err` result with a `DncError` is returned.
 fn parse_time(time: &str) -> Result<f32, DncError> {
-    let re = Regex::new(r"(\d+)").unwrap();
-    let captures = re.captures(time).ok_or(DncError::InvalidTimeFormat)?;
-    let number = captures
-        .get(1)
-        .ok_or(DncError::InvalidNumber)?
-        .as_str()
-        .parse::<f32>()
-        .map_err(|_| DncError::InvalidNumber)?;
-
-    if time.ends_with('h') {
-        Ok(number * 60.0)
-    } else if time.ends_with("min") {
-        Ok(number)
-    } else {
-        Err(DncError::InvalidTimeFormat)
+    let format = Regex::new(r"^(?:\d+(?:\.\d+)?(?:h|min))+$").unwrap();
+    if !format.is_match(time) {
+        return Err(DncError::InvalidTimeFormat);
+    }
+
+    let component = Regex::new(r"(\d+(?:\.\d+)?)(h|min)").unwrap();
+    let mut minutes = 0.0;
+    for captures in component.captures_iter(time) {
+        let number = captures[1]
+            .parse::<f32>()
+            .map_err(|_| DncError::InvalidNumber)?;
+        minutes += match &captures[2] {
+            "h" => number * 60.0,
+            _ => number,
+        };
     }
+
+    Ok(minutes)
 }
 
 /// Validates the calculated time acceleration values.
@@ -109,7 +324,7 @@ mod tests {
 
     #[test]
     fn test_calculate_dnc_invalid_time_acceleration() {
-        let result = calculate_dnc("0.5h", "10min");
+        let result = calculate_dnc("10min", "10min");
         assert!(result.is_err());
         assert_eq!(result.unwrap_err(), DncError::InvalidTimeAcceleration);
     }
@@ -121,6 +336,91 @@ mod tests {
         assert_eq!(result.unwrap_err(), DncError::InvalidNightTimeAcceleration);
     }
 
+    #[test]
+    fn test_dnc_table_contains_known_cell() {
+        let table = dnc_table();
+        let row = table.lines().find(|line| line.starts_with("8h ")).unwrap();
+        assert_eq!(row.split_whitespace().nth(1), Some("1.5/48.0"));
+        assert!(table.contains("out"));
+    }
+
+    #[test]
+    fn test_reverse_dnc_matches_requested_lengths() {
+        for (day, night, day_minutes, night_minutes) in [
+            ("8h", "10min", 480.0, 10.0),
+            ("5h", "45min", 300.0, 45.0),
+            ("7h", "30min", 420.0, 30.0),
+        ] {
+            let (time_acceleration, night_time_acceleration) = calculate_dnc(day, night).unwrap();
+            let (day_time, night_time) = reverse_dnc(time_acceleration, night_time_acceleration);
+            assert!((day_time - day_minutes).abs() < 0.01);
+            assert!((night_time - night_minutes).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_calculate_dnc_reverse() {
+        assert_eq!(
+            calculate_dnc_reverse(1.5, 48.0).unwrap(),
+            ("8h".to_string(), "10min".to_string())
+        );
+        assert_eq!(
+            calculate_dnc_reverse(8.0, 12.0).unwrap(),
+            ("1h30min".to_string(), "7min30s".to_string())
+        );
+        assert_eq!(
+            calculate_dnc_reverse(0.05, 1.0).unwrap_err(),
+            DncError::InvalidTimeAcceleration
+        );
+        assert_eq!(
+            calculate_dnc_reverse(1.0, 65.0).unwrap_err(),
+            DncError::InvalidNightTimeAcceleration
+        );
+    }
+
+    #[test]
+    fn test_calculate_dnc_reverse_round_trips() {
+        for (day, night) in [("8h", "10min"), ("1h30min", "20min"), ("5h", "45min")] {
+            let (time_accel, night_accel) = calculate_dnc(day, night).unwrap();
+            let (day_length, night_length) =
+                calculate_dnc_reverse(time_accel, night_accel).unwrap();
+            assert_eq!((day_length.as_str(), night_length.as_str()), (day, night));
+        }
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(480.0), "8h");
+        assert_eq!(format_duration(478.0), "7h 58min");
+        assert_eq!(format_duration(10.5), "10min 30s");
+        assert_eq!(format_duration(0.0), "0min");
+    }
+
+    #[test]
+    fn test_apply_dnc_replaces_and_appends() {
+        let content = "hostname = \"Server\";\n  serverTimeAcceleration = 4; // fast days\n";
+        let (updated, changes) = apply_dnc(content, 1.5, 48.0);
+
+        assert_eq!(
+            updated,
+            "hostname = \"Server\";\n  serverTimeAcceleration = 1.5; // fast days\nserverNightTimeAcceleration = 48;\n"
+        );
+        assert_eq!(
+            changes[0].before.as_deref(),
+            Some("  serverTimeAcceleration = 4; // fast days")
+        );
+        assert_eq!(changes[1].before, None);
+        assert_eq!(changes[1].after, "serverNightTimeAcceleration = 48;");
+    }
+
+    #[test]
+    fn test_apply_dnc_skips_unchanged_values() {
+        let content = "serverTimeAcceleration = 1.5;\nserverNightTimeAcceleration = 48;\n";
+        let (updated, changes) = apply_dnc(content, 1.5, 48.0);
+        assert_eq!(updated, content);
+        assert!(changes.is_empty());
+    }
+
     #[test]
     fn test_parse_time_valid_hours() {
         assert_eq!(parse_time("8h").unwrap(), 480.0);
@@ -131,6 +431,23 @@ mod tests {
         assert_eq!(parse_time("10min").unwrap(), 10.0);
     }
 
+    #[test]
+    fn test_parse_time_combined_units() {
+        assert_eq!(parse_time("1h30min").unwrap(), 90.0);
+        assert_eq!(parse_time("90min").unwrap(), 90.0);
+    }
+
+    #[test]
+    fn test_parse_time_fractional_hours() {
+        assert_eq!(parse_time("1.5h").unwrap(), 90.0);
+        assert_eq!(parse_time("0.25h").unwrap(), 15.0);
+    }
+
+    #[test]
+    fn test_parse_time_missing_unit_in_component() {
+        assert_eq!(parse_time("1h30").unwrap_err(), DncError::InvalidTimeFormat);
+    }
+
     #[test]
     fn test_parse_time_invalid_number() {
         let result = parse_time("abc");
@@ -1,9 +1,10 @@
 use base64::{engine::general_purpose, Engine as _};
-use log::error;
+use log::info;
 use regex::Regex;
 use sha2::{Digest, Sha256};
+use std::{fs, path::Path};
 
-use crate::GuidError;
+use crate::{is_dry_run, GuidError};
 
 /// Generates a GUID from a given Steam64 ID.
 ///
@@ -17,7 +18,8 @@ use crate::GuidError;
 ///
 /// # Returns
 ///
-/// A String representing the generated GUID.
+/// A Result containing the generated GUID, or the `GuidError` of the invalid Steam64 ID
+/// (see `validate_id`).
 ///
 /// # Example
 ///
@@ -25,30 +27,138 @@ use crate::GuidError;
 /// use dayz_tool_cli::commands::generate_guid;
 ///
 /// let steam64id = "76561198039479171";
-/// let guid = generate_guid(steam64id);
+/// let guid = generate_guid(steam64id).unwrap();
 ///
 /// assert_eq!(guid, "Bf_539q_w3ILhdEg8_kBACd4lKj-_ipXV8TiKEPj-og=");
 /// ```
-pub fn generate_guid(id: &str) -> String {
+pub fn generate_guid(id: &str) -> Result<String, GuidError> {
+    validate_id(id).map(|id| hash_id(&id))
+}
+
+/// Hashes a validated Steam64 ID into its GUID, see `generate_guid`.
+fn hash_id(id: &str) -> String {
     let mut hasher = Sha256::new();
+    hasher.update(id);
+    let hash_result = hasher.finalize();
+
+    let hash_to_base64 = general_purpose::URL_SAFE.encode(hash_result);
 
-    match validate_id(id) {
-        Ok(validated_id) => {
-            hasher.update(validated_id);
-            let hash_result = hasher.finalize();
+    let base64_regex = Regex::new(r"/").unwrap();
+    let guid = base64_regex.replace_all(&hash_to_base64, "_");
 
-            let hash_to_base64 = general_purpose::URL_SAFE.encode(hash_result);
+    guid.to_string()
+}
 
-            let base64_regex = Regex::new(r"/").unwrap();
-            let guid = base64_regex.replace_all(&hash_to_base64, "_");
+/// Generates the GUIDs of newline-separated Steam64 IDs.
+///
+/// Empty lines, lines that only hold a comment and trailing `//` comments are skipped,
+/// see `generate_admin_list` for the format. Unlike `generate_guid`, an invalid ID does not stop the
+/// batch: it is returned as a `GuidError::InvalidLine` with its line number instead.
+/// Returns the `(id, guid)` pairs of the valid IDs in input order and the errors.
+pub fn generate_guid_pairs(content: &str) -> (Vec<(String, String)>, Vec<GuidError>) {
+    let mut pairs = Vec::new();
+    let mut errors = Vec::new();
 
-            guid.to_string()
+    for (index, line) in content.lines().enumerate() {
+        let (id, _) = split_id_line(line);
+        if id.is_empty() {
+            continue;
         }
-        Err(e) => {
-            error!("{}", e);
-            std::process::exit(1);
+        match validate_id(id) {
+            Ok(id) => {
+                let guid = hash_id(&id);
+                pairs.push((id, guid));
+            }
+            Err(e) => errors.push(GuidError::InvalidLine(index + 1, e.to_string())),
         }
     }
+
+    (pairs, errors)
+}
+
+/// Writes `(id, guid)` pairs as a two-column, tab-separated file, one pair per line.
+pub fn write_guid_pairs(output: &Path, pairs: &[(String, String)]) -> Result<(), GuidError> {
+    if is_dry_run() {
+        info!(
+            "[dry-run] Would write {} GUIDs to {}",
+            pairs.len(),
+            output.display()
+        );
+        return Ok(());
+    }
+
+    let content: String = pairs
+        .iter()
+        .map(|(id, guid)| format!("{}\t{}\n", id, guid))
+        .collect();
+    fs::write(output, content).map_err(|_| GuidError::WriteError)?;
+
+    info!("Wrote {} GUIDs to {}", pairs.len(), output.display());
+    Ok(())
+}
+
+/// Generates the GUIDs of a list of Steam64 IDs and writes them to a file.
+///
+/// `file` holds one Steam64 ID per line, see `generate_admin_list` for the format. The
+/// GUIDs are written to `output` in the same order. Returns the number of GUIDs.
+pub fn generate_admins(file: &Path, output: &Path) -> Result<usize, GuidError> {
+    let content = fs::read_to_string(file).map_err(|_| GuidError::ReadError)?;
+    let (list, count) = generate_admin_list(&content)?;
+    if is_dry_run() {
+        info!(
+            "[dry-run] Would write {} GUIDs to {}",
+            count,
+            output.display()
+        );
+        return Ok(count);
+    }
+    fs::write(output, list).map_err(|_| GuidError::WriteError)?;
+
+    info!("Wrote {} GUIDs to {}", count, output.display());
+    Ok(count)
+}
+
+/// Replaces the Steam64 IDs of an admin list with their GUIDs.
+///
+/// A trailing `//` comment (e.g. `76561198039479171 //Karnes`) is kept after the GUID.
+/// Empty lines and lines that only hold a comment are copied unchanged. Returns the new
+/// list and the number of GUIDs in it, or the first invalid ID with its line number.
+pub fn generate_admin_list(content: &str) -> Result<(String, usize), GuidError> {
+    let mut lines = Vec::new();
+    let mut count = 0;
+
+    for (index, line) in content.lines().enumerate() {
+        let (id, comment) = split_id_line(line);
+
+        if id.is_empty() {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let id = validate_id(id).map_err(|e| GuidError::InvalidLine(index + 1, e.to_string()))?;
+        let guid = hash_id(&id);
+        count += 1;
+        match comment {
+            Some(comment) => lines.push(format!("{} //{}", guid, comment)),
+            None => lines.push(guid),
+        }
+    }
+
+    let mut list = lines.join("\n");
+    if !list.is_empty() {
+        list.push('\n');
+    }
+    Ok((list, count))
+}
+
+/// Splits a line of an ID list into its trimmed Steam64 ID and its trailing `//` comment.
+///
+/// The ID is empty for blank lines and lines that only hold a comment.
+fn split_id_line(line: &str) -> (&str, Option<&str>) {
+    match line.split_once("//") {
+        Some((id, comment)) => (id.trim(), Some(comment)),
+        None => (line.trim(), None),
+    }
 }
 
 /// Validates a Steam64 ID.
@@ -89,7 +199,76 @@ mod tests {
         let steam64id: &str = "76561198039479171";
         let expected_guid = "Bf_539q_w3ILhdEg8_kBACd4lKj-_ipXV8TiKEPj-og=";
         let generated_guid = generate_guid(steam64id);
-        assert_eq!(generated_guid, expected_guid);
+        assert_eq!(generated_guid, Ok(expected_guid.to_string()));
+        assert_eq!(generate_guid("123"), Err(GuidError::InvalidLength));
+    }
+
+    #[test]
+    fn test_generate_admin_list_keeps_comments() {
+        let content = "// Server admins\n76561198039479171 //Karnes\n\n76561198000000000\n";
+
+        let (list, count) = generate_admin_list(content).unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            list,
+            "// Server admins\nBf_539q_w3ILhdEg8_kBACd4lKj-_ipXV8TiKEPj-og= //Karnes\n\n\
+             mF_Wg3Kb36jaNjTWf9B_iQQzAsqyHEXsppQOVEXBBWE=\n"
+        );
+        assert_eq!(
+            generate_admin_list("76561198039479171\n123 //typo"),
+            Err(GuidError::InvalidLine(
+                2,
+                GuidError::InvalidLength.to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_generate_guid_pairs_skips_invalid_ids() {
+        let content =
+            "// Admins\n76561198039479171 //Karnes\n\n  76561198000000000  \n123\n86561198000000000\n";
+
+        let (pairs, errors) = generate_guid_pairs(content);
+
+        assert_eq!(
+            pairs,
+            vec![
+                (
+                    "76561198039479171".to_string(),
+                    "Bf_539q_w3ILhdEg8_kBACd4lKj-_ipXV8TiKEPj-og=".to_string()
+                ),
+                (
+                    "76561198000000000".to_string(),
+                    "mF_Wg3Kb36jaNjTWf9B_iQQzAsqyHEXsppQOVEXBBWE=".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            errors,
+            vec![
+                GuidError::InvalidLine(5, GuidError::InvalidLength.to_string()),
+                GuidError::InvalidLine(6, GuidError::InvalidPrefix.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_guid_pairs() {
+        let dir = std::env::temp_dir().join("dayz_tool_guid_pairs");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("whitelist.txt");
+
+        let (pairs, _) = generate_guid_pairs("76561198039479171\n");
+        write_guid_pairs(&output, &pairs).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&output).unwrap(),
+            "76561198039479171\tBf_539q_w3ILhdEg8_kBACd4lKj-_ipXV8TiKEPj-og=\n"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
     }
 
     #[test]
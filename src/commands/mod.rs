@@ -1,13 +1,33 @@
+mod backups;
 mod dnc;
 mod guid;
 mod mods;
 mod profile;
 mod startup;
 
-pub use dnc::calculate_dnc;
-pub use guid::generate_guid;
-pub use mods::{install_mods, list_installed_mods, uninstall_mods, update_mods};
+pub use backups::{clean_backups, find_backups, remove_old_backups, BackupFile};
+pub use dnc::{
+    calculate_dnc, calculate_dnc_reverse, dnc_table, format_duration, reverse_dnc, write_dnc,
+};
+pub use guid::{
+    generate_admin_list, generate_admins, generate_guid, generate_guid_pairs, write_guid_pairs,
+};
+pub use mods::{
+    apply_loadout, apply_swap, backup_mods, check_ce, check_drift, check_signatures, check_updates,
+    economy_preview, fetch_mods, for_each_mod_status, format_dependency_graph,
+    format_mods_markdown, freeze_mods, get_mod_statuses, get_workshop_statuses, install_keys,
+    install_loadout, install_mods, list_backups, list_installed_mods, mods_with_tag,
+    move_load_order, rebuild_ce, recompute_short_names, restore_backup, restore_mods,
+    scale_mod_types, show_mod_status, summarize_install, swap_mod, tag_mod, uninstall_mod,
+    uninstall_mods, update_mods, validate_xml, verify_installed_copies, verify_mods,
+    write_mod_params, MOD_PARAMS_FILE,
+};
 pub use profile::{
-    create_profile, delete_profile, list_profiles, show_profile, switch_profile, update_profile,
+    create_profile, current_profile, delete_profile, format_config, get_config_value,
+    import_profiles, list_profiles, restore_profile, set_steamcmd_password, show_profile,
+    store_steamcmd_password, switch_profile, update_profile,
+};
+pub use startup::{
+    default_mission_value, generate_startup_script, script_hash, stored_template_parameters,
+    validate_parameters, verify_startup_script, write_script_hash, write_startup_script,
 };
-pub use startup::generate_startup_script;
@@ -1,36 +1,106 @@
-use inquire::MultiSelect;
+use chrono::Local;
+
+use inquire::{Confirm, MultiSelect, Select};
 
 use log::{debug, error, info, warn};
 
+use serde_json::{json, Value};
+
 use std::{
-    fs::remove_dir_all,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::{create_dir_all, read_to_string, write},
+    io::ErrorKind,
     path::{Path, PathBuf},
-    sync::Arc,
+    process::Command,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Instant,
 };
 
 use crate::{
+    is_cancelled, is_dry_run, is_interactive, require_interactive,
     utils::{
-        add_mods_to_profile, analyze_types_folder, compare_mod_versions, copy_dir, copy_keys,
-        find_keys_folder, find_types_folder, get_installed_mod_list, get_map_name,
-        parse_startup_parameter, remove_ce_entries, remove_keys_for_mod, remove_mods_from_profile,
-        save_extracted_data, save_profile, update_cfgeconomy,
+        add_mods_to_profile, analyze_types_folder, build_steamcmd_command, ce_base_path,
+        ce_entry_names, ce_folder, check_distinct_paths, check_mod_branch, compare_mod_versions,
+        copy_dir, copy_keys, count_ce_entries, diff_checksums, diff_mod_files,
+        find_dangling_ce_files, find_keys_folder, find_types_folder, find_workshop_mods,
+        forget_mods, get_config_path, get_dir_size, get_downloaded_item_path,
+        get_installed_mod_list, get_profile, list_mission_folders, merge_custom_types,
+        mod_file_checksums, normalize_tags, order_by_load_order, parse_server_cfg,
+        parse_startup_parameter, read_mod_name, read_published_id, read_types_file,
+        rebuild_cfgeconomy, remove_ce_entries, remove_dir, remove_keys_for_mod,
+        remove_mods_from_profile, rename_ce_short_name, resolve_map_name, save_extracted_data,
+        save_profile, scale_types, steamcmd_password, steamcmd_runscript, update_cfgeconomy,
+        validate_ce_files, write_types_file, SystemKeyring, BACKUP_DIR, BACKUP_MANIFEST,
+        SUMMARY_TARGET,
     },
-    Mod, ModError, Profile, ProgressBar, ThreadPool, THEME, THREAD_POOL,
+    BackedUpMod, BackupManifest, EconomyPreview, InstallOptions, InstallSummary,
+    InstalledModSummary, Loadout, LoadoutMod, Mod, ModBaseline, ModDiff, ModError, ModStatus,
+    Profile, ProgressBar, ThreadPool, TimingReport, Type, TypeOverride, UpdateSummary,
+    COPY_LIMITER, THEME, THREAD_POOL,
 };
 
+/// The baseline file written by `freeze_mods` into the workdir. It is hidden, so it is
+/// not part of the mod comparison.
+const BASELINE_FILE: &str = ".dayz-baseline.json";
+
+/// The Steam Workshop page of an item, without its ID.
+const WORKSHOP_URL: &str = "https://steamcommunity.com/sharedfiles/filedetails/?id=";
+
 /// Installs selected mods from the workshop directory to the workdir directory.
 ///
 /// This function prompts the user to select filtered, not installed mods from the workshop directory and then
-/// copies the selected mods to the workdir directory. It also updates the profile
+/// copies the selected mods to the workdir directory. If `options.mods` names the workshop
+/// folders, they are installed without asking. It also updates the profile
 /// with the installed mods and returns a startup parameter string for launching the game
 /// with the installed mods.
-pub fn install_mods(pool: &ThreadPool, profile: Profile) -> Result<String, ModError> {
+///
+/// If `options.rename` is given, only a single mod can be selected and it is installed under the
+/// given folder name instead. The new name is used for the workdir folder, the CE files,
+/// the profile entry and the startup string, while the original workshop folder is
+/// remembered in the profile so that later updates still find the mod.
+///
+/// After the installation the user can mark installed mods as server-only, unless running
+/// non-interactively. These are emitted in a separate `-serverMod=` parameter instead of `-mod=`.
+///
+/// If `options.map` is given, it is used as the mission folder for the CE files instead
+/// of the map detected from `serverDZ.cfg`.
+///
+/// If `options.register_ce` is false, the CE files are extracted but not registered, and
+/// the mods are recorded in the profile's `manual_ce_mods`. If `options.extract_types` is
+/// false, only the mod files and keys are installed and no CE data is extracted or
+/// registered.
+///
+/// If `options.write_params` is set, the startup parameters are also written to the
+/// workdir, see `write_mod_params`.
+///
+/// If `options.verify` is set, the installed copies are compared with their workshop source once
+/// all copies finished (see `verify_installed_copies`). The profile is still updated, but
+/// `ModError::VerifyFailed` is returned if any copy differs.
+///
+/// The mods are searched up to `options.scan_depth` levels deep in the workshop path, see
+/// `find_workshop_mods`. Once all copies finished, a summary is logged with the detail
+/// given by `options.summary`, see `summarize_install`.
+pub fn install_mods(
+    pool: &ThreadPool,
+    profile: Profile,
+    options: &InstallOptions,
+) -> Result<String, ModError> {
+    check_distinct_paths(&profile)?;
+    let rename = options.rename.as_deref();
+    let map = options.map.as_deref();
+
     let workshop_path = profile.workshop_path.clone();
     let path = Path::new(&workshop_path);
 
     let mut mods: Vec<String> = vec![];
     let mut mods_paths: Vec<String> = vec![];
     let mut mods_to_install: Vec<String> = vec![];
+    let mut mod_sources: HashMap<String, String> = HashMap::new();
+    let mut server_mods: Vec<String> = vec![];
+    let mut installed_copies: Vec<(String, PathBuf)> = vec![];
 
     let installed_mods = get_installed_mod_list(profile.clone()).unwrap();
     let installed_mods_names: Vec<String> = installed_mods
@@ -38,23 +108,50 @@ pub fn install_mods(pool: &ThreadPool, profile: Profile) -> Result<String, ModEr
         .map(|v| v.as_str().unwrap().to_string())
         .collect();
 
-    for entry in path.read_dir().unwrap() {
-        let entry = entry.unwrap();
-        let path = entry.path();
+    for path in find_workshop_mods(path, options.scan_depth) {
         let path_str = path.to_str().unwrap();
         let folder_name = path.file_name().unwrap().to_str().unwrap();
 
-        if !installed_mods_names.contains(&folder_name.to_string()) {
+        let installed = installed_mods_names.contains(&folder_name.to_string())
+            || profile.mod_sources.values().any(|s| s == folder_name);
+        if !installed {
             mods.push(folder_name.to_string());
             mods_paths.push(path_str.to_string());
         }
     }
 
-    let ans = MultiSelect::new("Select the mods to intsall:", mods.clone()).prompt();
+    if let Some(new_name) = rename {
+        if installed_mods_names.contains(&new_name.to_string()) {
+            error!("A mod named {} is already installed", new_name);
+            return Err(ModError::InstallError);
+        }
+    }
+
+    let ans = if !options.mods.is_empty() {
+        if let Some(missing) = options.mods.iter().find(|name| !mods.contains(name)) {
+            error!(
+                "{} was not found in the workshop or is already installed",
+                missing
+            );
+            return Err(ModError::NotFound);
+        }
+        if rename.is_some() && options.mods.len() > 1 {
+            error!("Only a single mod can be installed under a different name");
+            return Err(ModError::SelectError);
+        }
+        Ok(options.mods.clone())
+    } else {
+        require_interactive("the mod names or --loadout <file>")?;
+        match rename {
+            Some(_) => Select::new("Select the mod to install:", mods.clone())
+                .prompt()
+                .map(|selected_mod| vec![selected_mod]),
+            None => MultiSelect::new("Select the mods to intsall:", mods.clone()).prompt(),
+        }
+    };
 
     match ans {
         Ok(selected_mods) => {
-            mods_to_install.clone_from(&selected_mods);
             let selected_mods_paths: Vec<String> = mods_paths
                 .into_iter()
                 .enumerate()
@@ -67,448 +164,4703 @@ pub fn install_mods(pool: &ThreadPool, profile: Profile) -> Result<String, ModEr
                 })
                 .collect();
 
-            let progress = Arc::new(ProgressBar::new(
-                selected_mods_paths.len() as u64,
-                30,
-                "Installing mods",
-                Arc::new(THEME.clone()),
-            ));
+            // Dispatch small mods first, so they are not queued behind large copies
+            // waiting for the copy limiter.
+            let mut selected_mods: Vec<(String, u64)> = selected_mods_paths
+                .into_iter()
+                .map(|path| {
+                    let size = get_dir_size(Path::new(&path));
+                    (path, size)
+                })
+                .collect();
+            selected_mods.sort_by_key(|(_, size)| *size);
+
+            // The bar is weighted by size, so a large mod advances it more than a small one.
+            let total_size = selected_mods.iter().map(|(_, size)| size).sum();
+            let sources: Vec<PathBuf> = selected_mods
+                .iter()
+                .map(|(path, _)| PathBuf::from(path))
+                .collect();
+            let run = InstallRun::new(
+                ProgressBar::new(total_size, 30, "Installing mods", Arc::new(THEME.clone())),
+                resolve_install_map(&profile, options, &sources)?,
+            );
 
-            for selected_mod_path in selected_mods_paths {
+            for (selected_mod_path, size) in selected_mods {
                 let source_path = PathBuf::from(selected_mod_path);
-                let workdir_path = profile.workdir_path.clone();
-                let target_path = Path::new(&workdir_path).join(source_path.file_name().unwrap());
-                pool.execute({
-                    let source_path = source_path.clone();
-                    let target_path = target_path.clone();
-                    move || {
-                        copy_dir(&source_path, &target_path).unwrap();
-                    }
-                });
-
-                // Copy bikey files in the keys folder
-                if let Some(key_source_path) = find_keys_folder(&source_path) {
-                    let key_target_path = Path::new(&workdir_path).join("keys");
-                    pool.execute({
-                        let key_source_path = key_source_path.clone();
-                        let key_target_path = key_target_path.clone();
-                        move || {
-                            copy_keys(&key_source_path, &key_target_path).unwrap();
-                        }
-                    });
+                let source_name = source_path
+                    .file_name()
+                    .ok_or(ModError::PathError)?
+                    .to_string_lossy()
+                    .to_string();
+                let target_name = rename.unwrap_or(&source_name).to_string();
+
+                warn_on_branch_mismatch(&profile, &source_path, &target_name);
+                if let Err(e) = install_mod(
+                    pool,
+                    &source_path,
+                    size,
+                    &target_name,
+                    &profile,
+                    options,
+                    &run,
+                ) {
+                    // Let the copies of the previous mods finish before bailing out.
+                    pool.wait();
+                    return Err(e);
                 }
 
-                // Copy types, spawnable_types and events to the mpmissions/<map_name> folder
-                if let Some(types_folder_path) = find_types_folder(&source_path) {
-                    let map_name = get_map_name(&workdir_path).unwrap();
-
-                    match analyze_types_folder(&types_folder_path) {
-                        Ok((Some(types), Some(spawnable_types), Some(events))) => {
-                            if !types.is_empty()
-                                || !spawnable_types.is_empty()
-                                || !events.is_empty()
-                            {
-                                let mod_short_name = Mod {
-                                    name: source_path
-                                        .file_name()
-                                        .ok_or(ModError::PathError)?
-                                        .to_str()
-                                        .ok_or(ModError::PathError)?
-                                        .to_string(),
-                                }
-                                .short_name();
-                                pool.execute({
-                                    let mod_short_name = mod_short_name.clone();
-                                    let map_name = map_name.clone();
-                                    let types = types.clone();
-                                    let spawnable_types = spawnable_types.clone();
-                                    let events = events.clone();
-                                    move || {
-                                        if let Err(e) = save_extracted_data(
-                                            &workdir_path,
-                                            &mod_short_name,
-                                            &map_name,
-                                            types.clone(),
-                                            spawnable_types.clone(),
-                                            events.clone(),
-                                        ) {
-                                            error!(
-                                                "Error while saving data for {}: {}",
-                                                mod_short_name, e
-                                            );
-                                        }
-
-                                        if let Err(e) = update_cfgeconomy(
-                                            &workdir_path,
-                                            &mod_short_name,
-                                            types,
-                                            spawnable_types,
-                                            events,
-                                        ) {
-                                            error!(
-                                                "Error updating cfgeconomy.xml for {}: {}",
-                                                mod_short_name, e
-                                            )
-                                        }
-                                    }
-                                });
-                            } else {
-                                warn!(
-                                    "No types, spawnable_types or events found in mod: {}",
-                                    source_path.display()
-                                );
-                            }
-                        }
-                        Ok(_) => {
-                            error!(
-                                "Incomplete data in types directory for mod: {}",
-                                source_path.display()
-                            );
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing types directory for mod {}: {}",
-                                source_path.display(),
-                                e
-                            );
-                        }
-                    }
-                } else {
-                    error!(
-                        "No types directory found for mod: {}",
-                        source_path.display()
-                    );
+                if target_name != source_name {
+                    mod_sources.insert(target_name.clone(), source_name);
                 }
+                installed_copies.push((target_name.clone(), source_path));
+                mods_to_install.push(target_name);
             }
 
-            progress.inc(1);
+            if !mods_to_install.is_empty() && is_interactive() {
+                server_mods = MultiSelect::new(
+                    "Select the server-only mods (-serverMod=):",
+                    mods_to_install.clone(),
+                )
+                .with_help_message("Server-only mods are not required on the clients")
+                .prompt()
+                .unwrap_or_default();
+            }
 
-            add_mods_to_profile(mods_to_install.clone()).unwrap();
             pool.wait();
+            log_timings(&run.timings);
+
+            // Only record the mods whose jobs all succeeded, a failed copy is not installed.
+            let selected_count = mods_to_install.len();
+            let installed = run.installed.lock().unwrap().clone();
+            mods_to_install.retain(|name| installed.contains(name));
+            server_mods.retain(|name| installed.contains(name));
+            mod_sources.retain(|name, _| installed.contains(name));
+            installed_copies.retain(|(name, _)| installed.contains(name));
+            info!(
+                "Installed {} of {} mods",
+                mods_to_install.len(),
+                selected_count
+            );
+            add_mods_to_profile(mods_to_install.clone()).map_err(|_| ModError::InstallError)?;
+            let map_name = run
+                .map_name
+                .clone()
+                .or_else(|| resolve_map_name(&profile.workdir_path, map).ok());
+            info!(
+                target: SUMMARY_TARGET,
+                "{}",
+                summarize_install(&profile, map_name.as_deref(), &installed_copies)
+                    .format(options.summary)
+            );
         }
         Err(_) => {
             return Err(ModError::SelectError);
         }
     }
 
-    match parse_startup_parameter() {
-        Ok(startup_parameter) => {
-            profile.clone().start_parameters = Some(startup_parameter.clone());
-            save_profile(&profile).unwrap();
+    let mut updated_profile = get_config_path()
+        .and_then(|path| get_profile(&path))
+        .map_err(|_| ModError::InstallError)?;
+    if is_dry_run() {
+        // The profile was not saved, add the selected mods to preview the startup parameter.
+        for name in &mods_to_install {
+            if !updated_profile
+                .installed_mods
+                .iter()
+                .any(|v| v.as_str() == Some(name))
+            {
+                updated_profile.installed_mods.push(json!(name));
+            }
+        }
+    }
+    updated_profile.mod_sources.extend(mod_sources);
+    updated_profile.server_mods.extend(server_mods);
+    if !options.register_ce {
+        updated_profile.manual_ce_mods.extend(mods_to_install);
+    }
+
+    match parse_startup_parameter(&updated_profile) {
+        Ok(startup_parameters) => {
+            let startup_parameter = startup_parameters.to_string();
+            updated_profile.start_parameters = Some(startup_parameter.clone());
+            save_profile(&updated_profile).map_err(|_| ModError::InstallError)?;
+            if options.write_params {
+                write_mod_params(&profile.workdir_path, &updated_profile)?;
+            }
+            if options.verify && !is_dry_run() {
+                let mismatched =
+                    verify_installed_copies(pool, &profile.workdir_path, &installed_copies);
+                if !mismatched.is_empty() {
+                    return Err(ModError::VerifyFailed(mismatched.join(", ")));
+                }
+                info!("Verified {} installed mods", installed_copies.len());
+            }
             Ok(startup_parameter)
         }
         Err(_) => Err(ModError::ParseError),
     }
 }
 
-/// Lists all installed mods for a given DayZ profile.
+/// Compares installed mod copies with their workshop source.
 ///
-/// This function retrieves a list of all installed mods from the specified profile
-/// and displays them in the console. The mods are displayed one per line using
-/// the info log level. The function handles the conversion from the internal
-/// JSON representation to readable mod names.
+/// `copies` holds the workdir name and the workshop path of each mod. The copies are
+/// compared with `compare_mod_versions`; a missing copy, a difference or a failed
+/// comparison is logged. Returns the names of the mods whose copy does not match, e.g.
+/// after an interrupted or faulty copy.
+pub fn verify_installed_copies(
+    pool: &ThreadPool,
+    workdir_path: &str,
+    copies: &[(String, PathBuf)],
+) -> Vec<String> {
+    let mut mismatched = Vec::new();
+
+    for (mod_name, source_path) in copies {
+        let target_path = Path::new(workdir_path).join(mod_name);
+        let matches = if target_path.is_dir() {
+            match compare_mod_versions(source_path, &target_path, pool) {
+                Ok(true) => true,
+                Ok(false) => {
+                    error!("Installed copy of {} differs from the workshop", mod_name);
+                    false
+                }
+                Err(e) => {
+                    error!("Failed to verify {}: {}", mod_name, e);
+                    false
+                }
+            }
+        } else {
+            error!("Installed copy of {} is missing", mod_name);
+            false
+        };
+
+        if !matches {
+            mismatched.push(mod_name.clone());
+        }
+    }
+
+    mismatched
+}
+
+/// The file in the workdir that `write_mod_params` writes to.
+pub const MOD_PARAMS_FILE: &str = "mod_params.txt";
+
+/// Writes the startup parameters of a profile's mods to `<workdir>/mod_params.txt`.
 ///
-/// The displayed mod names include their '@' prefix as they appear in the
-/// DayZ server directory structure.
-pub fn list_installed_mods(profile: Profile) -> Result<(), ModError> {
-    let installed_mods = get_installed_mod_list(profile.clone()).unwrap();
-    let installed_mods_names: Vec<String> = installed_mods
+/// The `-mod=` and, if any mods are server-only, the `-serverMod=` parameter are written
+/// on separate lines, quoted like the printed startup parameter. A wrapper script can
+/// read them instead of the parameters being pasted into the launch command by hand.
+///
+/// Returns the path of the written file.
+pub fn write_mod_params(workdir_path: &str, profile: &Profile) -> Result<PathBuf, ModError> {
+    let startup_parameters = parse_startup_parameter(profile)?;
+    let params_path = Path::new(workdir_path).join(MOD_PARAMS_FILE);
+    let content: String = [startup_parameters.mods, startup_parameters.server_mods]
         .into_iter()
-        .map(|v| v.as_str().unwrap().to_string())
+        .filter(|p| !p.is_empty())
+        .map(|p| format!("{}\n", p))
         .collect();
 
-    if installed_mods_names.is_empty() {
-        info!("No mods installed.");
-        return Ok(());
+    if is_dry_run() {
+        info!("[dry-run] Would write {}", params_path.display());
+        return Ok(params_path);
     }
 
-    for mod_name in installed_mods_names {
-        info!("{}", mod_name);
-    }
+    std::fs::write(&params_path, content).map_err(|_| ModError::WriteError)?;
+    info!("Startup parameters written to {}", params_path.display());
 
-    Ok(())
+    Ok(params_path)
 }
 
-/// Updates installed mods by replacing their directories and types configurations.
+/// Downloads workshop items with SteamCMD and installs them.
 ///
-/// This function performs the following operations for each installed mod:
-/// 1. Removes the existing mod directory from the workdir
-/// 2. Copies the latest version from the workshop directory
-/// 3. Updates types configurations if changes are detected
+/// The SteamCMD command is built from the profile's `steamcmd` settings (see
+/// `build_steamcmd_command`) and downloads the items below the workshop path. If the
+/// settings reference a keyring, the password is read from it at this point and masked
+/// in the dry-run output; SteamCMD then gets its commands from a private `+runscript`
+/// file, so the password never shows up in the process list. Each downloaded item is then installed under the name from its
+/// `meta.cpp`, falling back to `@<id>`, and added to the profile.
 ///
-/// The function uses a thread pool for parallel processing of mods to improve performance.
-/// All operations are logged for tracking and debugging purposes.
-pub fn update_mods(profile: Profile, pool: &ThreadPool) -> Result<(), ModError> {
-    let installed_mods = get_installed_mod_list(profile.clone()).unwrap();
-    let workdir_path = profile.workdir_path.clone();
-    let workshop_path = profile.workshop_path.clone();
+/// Returns the resulting startup parameter string.
+pub fn fetch_mods(
+    pool: &ThreadPool,
+    profile: Profile,
+    ids: &[String],
+    map: Option<&str>,
+) -> Result<String, ModError> {
+    check_distinct_paths(&profile)?;
 
-    if installed_mods.is_empty() {
-        info!("No mods installed.");
-        return Ok(());
+    let config = profile
+        .steamcmd
+        .as_ref()
+        .ok_or(ModError::MissingSteamCmdConfig)?;
+    if is_dry_run() {
+        let masked = config.keyring.as_ref().map(|_| "********");
+        let command = build_steamcmd_command(config, &profile.workshop_path, ids, masked)?;
+        info!("[dry-run] Would run {}", command.join(" "));
+        return Ok(profile.start_parameters.unwrap_or_default());
     }
 
-    info!("Starting mod updates...");
+    let password = steamcmd_password(config, &SystemKeyring)?;
+    let command = build_steamcmd_command(config, &profile.workshop_path, ids, password.as_deref())?;
+    if command.is_empty() {
+        return Err(ModError::MissingSteamCmdConfig);
+    }
 
-    let progress = Arc::new(ProgressBar::new(
-        installed_mods.len() as u64,
-        30,
-        "Updating mods",
-        Arc::new(THEME.clone()),
-    ));
+    info!("Downloading {} workshop item(s) with SteamCMD", ids.len());
+    let status = if password.is_some() {
+        run_steamcmd_script(&command)
+    } else {
+        Command::new(&command[0]).args(&command[1..]).status()
+    }
+    .map_err(|e| {
+        error!("Failed to run SteamCMD: {}", e);
+        ModError::DownloadError
+    })?;
+    if !status.success() {
+        error!("SteamCMD exited with {}", status);
+        return Err(ModError::DownloadError);
+    }
 
-    for mod_entry in installed_mods {
-        let mod_name = mod_entry.as_str().unwrap().to_string();
-        let mod_workdir_path = Path::new(&workdir_path).join(&mod_name);
-        let mod_workshop_path = Path::new(&workshop_path).join(&mod_name);
-        let progress = Arc::clone(&progress);
+    let installed_mods_names: Vec<String> = get_installed_mod_list(profile.clone())?
+        .into_iter()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
 
-        if !mod_workshop_path.exists() {
-            error!(
-                "Workshop path does not exist for {}: {}",
-                mod_name,
-                mod_workshop_path.display()
-            );
-            continue;
+    let mut downloaded = Vec::new();
+    for id in ids {
+        let source_name = get_downloaded_item_path(id);
+        let source_path = Path::new(&profile.workshop_path).join(&source_name);
+        if source_path.is_dir() {
+            downloaded.push((id, source_name, source_path));
+        } else {
+            error!("Workshop item {} was not downloaded", id);
         }
+    }
 
-        if mod_workdir_path.exists() {
-            info!("Checking if update is needed for {}", mod_name);
-            match compare_mod_versions(&mod_workshop_path, &mod_workdir_path, &THREAD_POOL) {
-                Ok(true) => {
-                    info!("Mod {} is up to date, skipping", mod_name);
-                    continue;
-                }
-                Ok(false) => info!("Update needed for {}", mod_name),
-                Err(e) => {
-                    error!("Failed to compare versions for {}: {}", mod_name, e);
-                    continue;
-                }
-            }
+    let options = InstallOptions {
+        map: map.map(String::from),
+        ..Default::default()
+    };
+    let sources: Vec<PathBuf> = downloaded.iter().map(|(_, _, path)| path.clone()).collect();
+    let run = InstallRun::new(
+        ProgressBar::new(
+            ids.len() as u64,
+            30,
+            "Installing mods",
+            Arc::new(THEME.clone()),
+        )
+        .with_item_count(),
+        resolve_install_map(&profile, &options, &sources)?,
+    );
+    let mut mods_to_install = Vec::new();
+    let mut mod_sources = HashMap::new();
 
-            info!("Removing {} from workdir", mod_name);
-            if let Err(e) = std::fs::remove_dir_all(&mod_workdir_path) {
-                error!(
-                    "Failed to remove {} from workdir at {}: {}",
-                    mod_name,
-                    mod_workdir_path.display(),
-                    e
-                );
-                continue;
-            }
+    for (id, source_name, source_path) in downloaded {
+        let mod_name = read_mod_name(&source_path).unwrap_or_else(|| format!("@{}", id));
+        warn_on_branch_mismatch(&profile, &source_path, &mod_name);
+        if let Err(e) = install_mod(
+            pool,
+            &source_path,
+            get_dir_size(&source_path),
+            &mod_name,
+            &profile,
+            &options,
+            &run,
+        ) {
+            pool.wait();
+            return Err(e);
         }
 
-        info!("Updating {} from workshop", mod_name);
-        pool.execute({
-            let mod_name = mod_name.clone();
-            let mod_workshop_path = mod_workshop_path.clone();
-            let mod_workdir_path = mod_workdir_path.clone();
-            let workdir_path = workdir_path.clone();
-            move || match copy_dir(&mod_workshop_path, &mod_workdir_path) {
-                Ok(_) => {
-                    info!("Successfully copied {} to workdir", mod_name);
+        if !installed_mods_names.contains(&mod_name) {
+            mods_to_install.push(mod_name.clone());
+        }
+        mod_sources.insert(mod_name, source_name.to_string_lossy().to_string());
+    }
 
-                    if let Some(types_folder_path) = find_types_folder(&mod_workshop_path) {
-                        info!(
-                            "Found types folder for {}: {}",
-                            mod_name,
-                            types_folder_path.display()
-                        );
+    pool.wait();
+    log_timings(&run.timings);
 
-                        match analyze_types_folder(&types_folder_path) {
-                            Ok((Some(types), Some(spawnable_types), Some(events))) => {
-                                if !types.is_empty()
-                                    || !spawnable_types.is_empty()
-                                    || !events.is_empty()
-                                {
-                                    let mod_short_name = Mod {
-                                        name: mod_name.clone(),
-                                    }
-                                    .short_name();
-
-                                    match get_map_name(&workdir_path) {
-                                        Ok(map_name) => {
-                                            info!(
-                                                "Updating types data for {} ({})",
-                                                mod_name, mod_short_name
-                                            );
-
-                                            if let Err(e) = save_extracted_data(
-                                                &workdir_path,
-                                                &mod_short_name,
-                                                &map_name,
-                                                types.clone(),
-                                                spawnable_types.clone(),
-                                                events.clone(),
-                                            ) {
-                                                error!(
-                                                    "Error updating types data for {}: {}",
-                                                    mod_name, e
-                                                );
-                                            }
-                                        }
-                                        Err(e) => {
-                                            error!(
-                                                "Failed to get map name for {}: {:?}",
-                                                mod_name, e
-                                            );
-                                        }
-                                    }
-                                } else {
-                                    info!("No types data found for {}", mod_name);
-                                }
-                            }
-                            Ok(_) => {
-                                error!("Incomplete types data for mod: {}", mod_name);
-                            }
-                            Err(e) => {
-                                error!("Error analyzing types for mod {}: {}", mod_name, e);
-                            }
-                        }
-                    } else {
-                        info!("No types folder found for {}", mod_name);
-                    }
-                    progress.inc(1);
-                    info!("Successfully updated {}", mod_name);
-                }
-                Err(e) => {
-                    error!(
-                        "Failed to update {} to workdir.\nSource: {}\nTarget: {}\nError: {:?}",
-                        mod_name,
-                        mod_workshop_path.display(),
-                        mod_workdir_path.display(),
-                        e
-                    );
-                }
-            }
-        });
+    let installed = run.installed.lock().unwrap().clone();
+    mods_to_install.retain(|name| installed.contains(name));
+    mod_sources.retain(|name, _| installed.contains(name));
+    add_mods_to_profile(mods_to_install).map_err(|_| ModError::InstallError)?;
+
+    let mut updated_profile = get_config_path()
+        .and_then(|path| get_profile(&path))
+        .map_err(|_| ModError::InstallError)?;
+    updated_profile.mod_sources.extend(mod_sources);
+
+    let startup_parameter = parse_startup_parameter(&updated_profile)?.to_string();
+    updated_profile.start_parameters = Some(startup_parameter.clone());
+    save_profile(&updated_profile).map_err(|_| ModError::InstallError)?;
+
+    Ok(startup_parameter)
+}
+
+/// Runs a SteamCMD command line through a `+runscript` file readable only by the user.
+///
+/// The script is removed once SteamCMD exits, whether or not it succeeded.
+fn run_steamcmd_script(command: &[String]) -> std::io::Result<std::process::ExitStatus> {
+    let (program, script) = steamcmd_runscript(command);
+    if program.is_empty() {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidInput,
+            "the SteamCMD command starts with a +command instead of the executable",
+        ));
     }
+    let script_path =
+        std::env::temp_dir().join(format!("dayz-tool-steamcmd-{}.txt", std::process::id()));
+    let _ = std::fs::remove_file(&script_path);
 
-    pool.wait();
-    info!("All mod updates completed.");
-    Ok(())
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options
+        .open(&script_path)
+        .and_then(|mut file| std::io::Write::write_all(&mut file, script.as_bytes()))?;
+
+    let status = Command::new(&program[0])
+        .args(&program[1..])
+        .arg("+runscript")
+        .arg(&script_path)
+        .status();
+    let _ = std::fs::remove_file(&script_path);
+    status
 }
 
-/// Uninstalls selected mods from the DayZ server directory.
+/// Warns if the mod at `source_path` was built for a different branch than the server
+/// of the profile.
+fn warn_on_branch_mismatch(profile: &Profile, source_path: &Path, mod_name: &str) {
+    if let Some(mod_branch) = check_mod_branch(profile.branch.as_deref(), source_path) {
+        warn!(
+            "{} appears to be built for the {} branch, but the server runs {}. It may crash the server.",
+            mod_name,
+            mod_branch,
+            profile.branch.as_deref().unwrap_or_default()
+        );
+    }
+}
+
+/// Installs a single mod from the workshop directory into the workdir.
 ///
-/// This function performs a complete uninstallation of selected mods by:
-/// 1. Removing bikey files from the keys directory
-/// 2. Deleting mod-specific types folders from the mpmissions directory
-/// 3. Removing the mod directory from the workdir
-/// 4. Cleaning up CE entries from cfgeconomycore.xml
-/// 5. Updating the config.json to remove the mods from installed_mods
+/// If the mod folder already exists in the workdir and matches the workshop version
+/// (checked via `compare_mod_versions`), the copy is skipped and only the keys and
+/// CE entries are reconciled. This turns re-running an interrupted install into a
+/// fast reconciliation instead of a full recopy.
 ///
-/// The function uses parallel processing through a thread pool to handle multiple
-/// mod uninstallations simultaneously.
-pub fn uninstall_mods(profile: Profile, pool: &ThreadPool) -> Result<(), ModError> {
-    let installed_mods = get_installed_mod_list(profile.clone())?;
-    let installed_mods_names: Vec<String> = installed_mods
-        .into_iter()
-        .map(|v| v.as_str().unwrap().to_string())
-        .collect();
+/// The mod is installed as `<workdir>/<mod_name>`, which allows installing a workshop
+/// mod under a different folder name. The CE files are named after the short name of
+/// `mod_name` as well.
+///
+/// The file copy, key copy and CE update of the mod run as separate pool jobs. They are
+/// tracked as one unit and counted in the `run` once all of them succeeded. A progress
+/// bar of the run counting items is advanced by one once they have completed. A bar
+/// counting bytes is passed to `copy_dir`, which advances it per copied file; if the copy
+/// is skipped, it is advanced by `size`, the size of the mod. Copies of large mods are
+/// limited by `COPY_LIMITER`, so they do not run concurrently. The time the jobs spend on
+/// copying and extracting is recorded in the run's timings.
+///
+/// The CE files go to the mission folder `options.map`, or the one detected from
+/// `serverDZ.cfg`. If `options.register_ce` is false, the `_ce` files are still extracted,
+/// but not registered in `cfgeconomycore.xml`, leaving that to the admin. If the
+/// profile's `ce_dir` is set, the `_ce` folder is placed in that subfolder of the mission
+/// (see `ce_folder`). If `options.extract_types` is false, the types folder is ignored
+/// entirely and only the mod files and keys are installed.
+///
+/// Returns `true` if the mod files were copied, or `false` if an up-to-date copy
+/// was already present in the workdir.
+fn install_mod(
+    pool: &ThreadPool,
+    source_path: &Path,
+    size: u64,
+    mod_name: &str,
+    profile: &Profile,
+    options: &InstallOptions,
+    run: &Arc<InstallRun>,
+) -> Result<bool, ModError> {
+    let workdir_path = profile.workdir_path.as_str();
+    let ce_dir = profile.ce_dir.as_deref();
+    let register_ce = options.register_ce;
+    let extract_types = options.extract_types;
+    let mod_name = mod_name.to_string();
+    let target_path = Path::new(workdir_path).join(&mod_name);
 
-    if installed_mods_names.is_empty() {
-        info!("No mods installed.");
-        return Ok(());
-    }
+    let up_to_date = target_path.exists()
+        && match compare_mod_versions(source_path, &target_path, pool) {
+            Ok(same) => same,
+            Err(e) => {
+                warn!("Failed to compare existing copy of {}: {}", mod_name, e);
+                false
+            }
+        };
 
-    let ans = MultiSelect::new("Select mods to uninstall:", installed_mods_names.clone()).prompt();
+    let weight = if run.progress.counts_items() {
+        1
+    } else if up_to_date || is_dry_run() {
+        size
+    } else {
+        0
+    };
+    let jobs = ModJobs::new(&mod_name, Arc::clone(run), weight);
 
-    match ans {
-        Ok(selected_mods) => {
-            let map_name = get_map_name(&profile.workdir_path)?;
+    if up_to_date {
+        info!(
+            "{} is already installed and up to date, skipping copy",
+            mod_name
+        );
+    } else {
+        if target_path.exists() {
+            debug!("Removing incomplete copy of {}", mod_name);
+            remove_dir(&target_path).map_err(|_| ModError::RemoveFileError)?;
+        }
 
-            debug!("Starting mod uninstalls...");
+        jobs.add();
+        pool.execute({
+            let source_path = source_path.to_path_buf();
+            let target_path = target_path.clone();
+            let mod_name = mod_name.clone();
+            let jobs = Arc::clone(&jobs);
+            move || {
+                let permit = COPY_LIMITER.acquire(size);
+                if permit.is_some() {
+                    debug!("Copying large mod {} ({} bytes)", mod_name, size);
+                }
+                let result =
+                    jobs.timed(|| copy_dir(&source_path, &target_path, jobs.byte_progress()));
+                drop(permit);
+                if let Err(e) = &result {
+                    error!("Failed to copy {} to workdir: {}", mod_name, e);
+                }
+                jobs.done(result.is_ok());
+            }
+        });
+    }
 
-            for mod_name in &selected_mods {
-                pool.execute({
-                    let mod_name = mod_name.clone();
-                    let workdir_path = profile.workdir_path.clone();
-                    let map_name = map_name.clone();
-
-                    move || {
-                        let mod_path = Path::new(&workdir_path).join(&mod_name);
-
-                        if let Err(e) = remove_keys_for_mod(&workdir_path, &mod_path) {
-                            error!("Failed to remove keys for {}: {}", mod_name, e);
-                        } else {
-                            debug!("Successfully removed keys for {}", mod_name);
-                        }
+    // Copy bikey files in the keys folder
+    if let Some(key_source_path) = find_keys_folder(source_path) {
+        let key_target_path = Path::new(workdir_path).join("keys");
+        let jobs_handle = Arc::clone(&jobs);
+        jobs.add();
+        pool.execute(move || {
+            let result = jobs_handle.timed(|| copy_keys(&key_source_path, &key_target_path));
+            if let Err(e) = &result {
+                error!("Failed to copy keys: {}", e);
+            }
+            jobs_handle.done(result.is_ok());
+        });
+    }
 
-                        let mod_short = Mod {
-                            name: mod_name.clone(),
-                        }
-                        .short_name();
-                        let types_path = Path::new(&workdir_path)
-                            .join("mpmissions")
-                            .join(&map_name)
-                            .join(format!("{}_ce", mod_short));
-                        if types_path.exists() {
-                            if let Err(e) = remove_dir_all(types_path) {
-                                error!("Failed to remove types folder for {}: {}", mod_name, e);
-                            } else {
-                                debug!("Successfully removed types folder for {}", mod_name);
-                            }
-                        } else {
-                            info!("No types folder found for {} (this is normal for mods without types)", mod_name);
-                        }
+    // Copy types, spawnable_types and events to the mpmissions/<map_name> folder
+    if !extract_types {
+        info!("Skipping CE extraction for {}", mod_name);
+    } else if let Some(types_folder_path) = find_types_folder(source_path) {
+        let Some(map_name) = run.map_name.clone() else {
+            error!("No map was resolved for the CE files of {}", mod_name);
+            jobs.done(false);
+            return Ok(!up_to_date);
+        };
 
-                        if mod_path.exists() {
-                            if let Err(e) = remove_dir_all(mod_path) {
-                                error!("Failed to remove mod folder for {}: {}", mod_name, e);
-                            } else {
-                                info!("Successfully removed mod folder for {}", mod_name);
-                            }
-                        }
+        match analyze_types_folder(&types_folder_path) {
+            Ok((Some(types), Some(spawnable_types), Some(events))) => {
+                if !types.is_empty() || !spawnable_types.is_empty() || !events.is_empty() {
+                    let mod_short_name = Mod {
+                        name: mod_name.clone(),
+                    }
+                    .short_name();
+                    let workdir_path = workdir_path.to_string();
+                    let ce_dir = ce_dir.map(String::from);
+                    let jobs_handle = Arc::clone(&jobs);
+                    jobs.add();
+                    pool.execute(move || {
+                        let start = Instant::now();
+                        let mut success = true;
 
-                        if let Err(e) = remove_ce_entries(&workdir_path, &map_name, &mod_short) {
-                            error!("Failed to remove CE entries for {}: {}", mod_name, e);
-                        } else {
-                            info!("Successfully removed CE entries for {}", mod_name);
+                        if let Err(e) = save_extracted_data(
+                            &workdir_path,
+                            &mod_short_name,
+                            &map_name,
+                            types.clone(),
+                            spawnable_types.clone(),
+                            events.clone(),
+                            ce_dir.as_deref(),
+                        ) {
+                            error!("Error while saving data for {}: {}", mod_short_name, e);
+                            success = false;
                         }
-                    }
-                });
-            }
 
-            pool.wait();
+                        if !register_ce {
+                            info!(
+                                "Skipping cfgeconomycore.xml registration for {}",
+                                mod_short_name
+                            );
+                        } else if let Err(e) = update_cfgeconomy(
+                            &workdir_path,
+                            &map_name,
+                            &mod_short_name,
+                            types,
+                            spawnable_types,
+                            events,
+                            ce_dir.as_deref(),
+                        ) {
+                            error!(
+                                "Error updating cfgeconomy.xml for {}: {}",
+                                mod_short_name, e
+                            );
+                            success = false;
+                        }
 
-            if let Err(e) = remove_mods_from_profile(&selected_mods) {
-                error!("Failed to update config.json: {}", e);
-            } else {
-                debug!(
-                    "Successfully removed {} mods from config",
-                    selected_mods.len()
+                        jobs_handle
+                            .run
+                            .timings
+                            .record(&jobs_handle.mod_name, start.elapsed());
+                        jobs_handle.done(success);
+                    });
+                } else {
+                    warn!(
+                        "No types, spawnable_types or events found in mod: {}",
+                        source_path.display()
+                    );
+                }
+            }
+            Ok(_) => {
+                error!(
+                    "Incomplete data in types directory for mod: {}",
+                    source_path.display()
+                );
+            }
+            Err(e) => {
+                error!(
+                    "Error parsing types directory for mod {}: {}",
+                    source_path.display(),
+                    e
                 );
             }
         }
-        Err(_) => return Err(ModError::SelectError),
+    } else {
+        info!(
+            "No types directory found for {} (this is normal for mods without CE content)",
+            mod_name
+        );
     }
 
-    Ok(())
+    jobs.done(true);
+
+    Ok(!up_to_date)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serde_json::json;
+/// Installs the mods of a loadout file and updates the active profile to match it.
+///
+/// The loadout file (`mods.json`) lists the desired mods in load order, see `Loadout`.
+/// Missing mods are installed, and the server-only flags and load order of the listed
+/// mods are taken from the file. Installed mods that are not listed are left alone,
+/// unless `prune` is set and the user confirms their removal. With `yes` the removal
+/// is not confirmed.
+///
+/// Returns the resulting startup parameter string. If `write_params` is set, it is also
+/// written to the workdir, see `write_mod_params`.
+pub fn install_loadout(
+    pool: &ThreadPool,
+    profile: Profile,
+    loadout_path: &Path,
+    map: Option<&str>,
+    prune: bool,
+    yes: bool,
+    write_params: bool,
+) -> Result<String, ModError> {
+    check_distinct_paths(&profile)?;
+
+    let content = std::fs::read_to_string(loadout_path).map_err(|_| ModError::ReadError)?;
+    let loadout: Loadout = serde_json::from_str(&content).map_err(|e| {
+        error!("Invalid loadout file {}: {}", loadout_path.display(), e);
+        ModError::ParseError
+    })?;
+
+    let unlisted_mods = get_unlisted_mods(&profile, &loadout);
+    let prune = if prune && !unlisted_mods.is_empty() && !yes {
+        require_interactive("--yes to confirm --prune")?;
+        Confirm::new(&format!(
+            "Uninstall the mods not listed in the loadout ({})?",
+            unlisted_mods.join(", ")
+        ))
+        .with_default(false)
+        .prompt()
+        .unwrap_or(false)
+    } else {
+        prune && !unlisted_mods.is_empty()
+    };
+
+    let applied = apply_loadout(pool, profile, &loadout, map, prune)?;
+
+    let mut updated_profile = get_config_path()
+        .and_then(|path| get_profile(&path))
+        .map_err(|_| ModError::InstallError)?;
+    updated_profile.installed_mods = applied.installed_mods;
+    updated_profile.mod_sources = applied.mod_sources;
+    updated_profile.server_mods = applied.server_mods;
+    updated_profile.load_order = applied.load_order;
+    updated_profile.mod_dependencies = applied.mod_dependencies;
+    updated_profile.start_parameters = applied.start_parameters.clone();
+    save_profile(&updated_profile).map_err(|_| ModError::InstallError)?;
+    if write_params {
+        write_mod_params(&applied.workdir_path, &updated_profile)?;
+    }
+
+    Ok(applied.start_parameters.unwrap_or_default())
+}
+
+/// Applies a loadout to a profile and returns the updated profile.
+///
+/// All enabled mods of the loadout must exist in the workshop directory, otherwise
+/// nothing is installed. Disabled entries are ignored. If `prune` is set, installed mods
+/// that are not listed are uninstalled. The returned profile is not saved to the
+/// configuration file.
+pub fn apply_loadout(
+    pool: &ThreadPool,
+    mut profile: Profile,
+    loadout: &Loadout,
+    map: Option<&str>,
+    prune: bool,
+) -> Result<Profile, ModError> {
+    check_distinct_paths(&profile)?;
+
+    let enabled_mods: Vec<&LoadoutMod> = loadout.mods.iter().filter(|m| m.enabled).collect();
+
+    for loadout_mod in &enabled_mods {
+        let source_name = loadout_mod.source.as_deref().unwrap_or(&loadout_mod.name);
+        if !Path::new(&profile.workshop_path).join(source_name).exists() {
+            error!(
+                "Mod {} of the loadout was not found in the workshop",
+                source_name
+            );
+            return Err(ModError::PathError);
+        }
+    }
+
+    let installed_mods_names: Vec<String> = profile
+        .installed_mods
+        .iter()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+    let missing_mods: Vec<&&LoadoutMod> = enabled_mods
+        .iter()
+        .filter(|m| !installed_mods_names.contains(&m.name))
+        .collect();
+
+    let options = InstallOptions {
+        map: map.map(String::from),
+        ..Default::default()
+    };
+    let sources: Vec<PathBuf> = missing_mods
+        .iter()
+        .map(|m| Path::new(&profile.workshop_path).join(m.source.as_deref().unwrap_or(&m.name)))
+        .collect();
+    let run = InstallRun::new(
+        ProgressBar::new(
+            missing_mods.len() as u64,
+            30,
+            "Installing mods",
+            Arc::new(THEME.clone()),
+        )
+        .with_item_count(),
+        resolve_install_map(&profile, &options, &sources)?,
+    );
+
+    for loadout_mod in &missing_mods {
+        let source_name = loadout_mod.source.as_deref().unwrap_or(&loadout_mod.name);
+        let source_path = Path::new(&profile.workshop_path).join(source_name);
+        warn_on_branch_mismatch(&profile, &source_path, &loadout_mod.name);
+        if let Err(e) = install_mod(
+            pool,
+            &source_path,
+            get_dir_size(&source_path),
+            &loadout_mod.name,
+            &profile,
+            &options,
+            &run,
+        ) {
+            pool.wait();
+            return Err(e);
+        }
+    }
+    pool.wait();
+    log_timings(&run.timings);
+
+    if prune {
+        let unlisted_mods = get_unlisted_mods(&profile, loadout);
+        if !unlisted_mods.is_empty() {
+            let map_name = resolve_map_name(&profile.workdir_path, map)?;
+            for mod_name in &unlisted_mods {
+                info!("Pruning {} as it is not listed in the loadout", mod_name);
+                uninstall_mod(
+                    pool,
+                    &profile.workdir_path,
+                    std::slice::from_ref(&map_name),
+                    mod_name,
+                    !profile.manual_ce_mods.contains(mod_name),
+                    profile.ce_dir.as_deref(),
+                );
+            }
+            pool.wait();
+
+            forget_mods(&mut profile, &unlisted_mods);
+        }
+    }
+
+    let installed_mods_names: Vec<String> = profile
+        .installed_mods
+        .iter()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+    let listed_names: Vec<String> = enabled_mods.iter().map(|m| m.name.clone()).collect();
+    let mut installed_mods: Vec<Value> = listed_names.iter().map(|n| json!(n)).collect();
+    installed_mods.extend(
+        installed_mods_names
+            .iter()
+            .filter(|name| !listed_names.contains(name))
+            .map(|name| json!(name)),
+    );
+    profile.installed_mods = installed_mods;
+
+    for loadout_mod in &enabled_mods {
+        match &loadout_mod.source {
+            Some(source) if *source != loadout_mod.name => {
+                profile
+                    .mod_sources
+                    .insert(loadout_mod.name.clone(), source.clone());
+            }
+            _ => {
+                profile.mod_sources.remove(&loadout_mod.name);
+            }
+        }
+    }
+
+    profile
+        .server_mods
+        .retain(|name| !listed_names.contains(name));
+    profile.server_mods.extend(
+        enabled_mods
+            .iter()
+            .filter(|m| m.server_mod)
+            .map(|m| m.name.clone()),
+    );
+    profile.load_order = listed_names;
+
+    for loadout_mod in &enabled_mods {
+        if loadout_mod.depends_on.is_empty() {
+            profile.mod_dependencies.remove(&loadout_mod.name);
+        } else {
+            profile
+                .mod_dependencies
+                .insert(loadout_mod.name.clone(), loadout_mod.depends_on.clone());
+        }
+    }
+
+    let startup_parameters = parse_startup_parameter(&profile)?;
+    profile.start_parameters = Some(startup_parameters.to_string());
+
+    Ok(profile)
+}
+
+/// Replaces an installed mod with another workshop mod and saves the profile.
+///
+/// See `apply_swap` for how the mods are exchanged. Returns the resulting startup
+/// parameter string.
+pub fn swap_mod(
+    pool: &ThreadPool,
+    profile: Profile,
+    old_mod: &str,
+    new_mod: &str,
+    map: Option<&str>,
+) -> Result<String, ModError> {
+    let applied = apply_swap(pool, profile, old_mod, new_mod, map)?;
+
+    let mut updated_profile = get_config_path()
+        .and_then(|path| get_profile(&path))
+        .map_err(|_| ModError::InstallError)?;
+    updated_profile.installed_mods = applied.installed_mods;
+    updated_profile.mod_sources = applied.mod_sources;
+    updated_profile.server_mods = applied.server_mods;
+    updated_profile.load_order = applied.load_order;
+    updated_profile.manual_ce_mods = applied.manual_ce_mods;
+    updated_profile.mod_tags = applied.mod_tags;
+    updated_profile.start_parameters = applied.start_parameters.clone();
+    save_profile(&updated_profile).map_err(|_| ModError::InstallError)?;
+
+    info!("Swapped {} for {}", old_mod, new_mod);
+    Ok(applied.start_parameters.unwrap_or_default())
+}
+
+/// Replaces an installed mod with another workshop mod and returns the updated profile.
+///
+/// `new_mod` is the workshop folder of the replacement. It is installed before `old_mod`
+/// is uninstalled, so if the install fails, the partial copy is removed and the old mod
+/// stays in place with its keys. The new mod takes over the position of the old one in
+/// the installed mods and the load order, as well as its server-only flag.
+///
+/// The keys of the new mod are copied again after the old mod was uninstalled, as forks
+/// often ship a key file of the same name. The returned profile is not saved to the
+/// configuration file.
+pub fn apply_swap(
+    pool: &ThreadPool,
+    mut profile: Profile,
+    old_mod: &str,
+    new_mod: &str,
+    map: Option<&str>,
+) -> Result<Profile, ModError> {
+    check_distinct_paths(&profile)?;
+
+    let installed_mods_names: Vec<String> = profile
+        .installed_mods
+        .iter()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+    let Some(index) = installed_mods_names.iter().position(|name| name == old_mod) else {
+        error!("Mod {} is not installed", old_mod);
+        return Err(ModError::NotFound);
+    };
+    if installed_mods_names.iter().any(|name| name == new_mod) {
+        error!("Mod {} is already installed", new_mod);
+        return Err(ModError::InstallError);
+    }
+
+    let source_path = Path::new(&profile.workshop_path).join(new_mod);
+    if !source_path.is_dir() {
+        error!("Mod {} not found in workshop", new_mod);
+        return Err(ModError::NotFound);
+    }
+
+    let map_name = resolve_map_name(&profile.workdir_path, map)?;
+    let old_path = Path::new(&profile.workdir_path).join(old_mod);
+    let new_path = Path::new(&profile.workdir_path).join(new_mod);
+    let keys_path = Path::new(&profile.workdir_path).join("keys");
+
+    warn_on_branch_mismatch(&profile, &source_path, new_mod);
+    let run = InstallRun::new(
+        ProgressBar::new(1, 30, "Installing mods", Arc::new(THEME.clone())).with_item_count(),
+        Some(map_name.clone()),
+    );
+    let options = InstallOptions {
+        map: map.map(String::from),
+        register_ce: !profile.manual_ce_mods.iter().any(|name| name == old_mod),
+        ..Default::default()
+    };
+    let installed = install_mod(
+        pool,
+        &source_path,
+        get_dir_size(&source_path),
+        new_mod,
+        &profile,
+        &options,
+        &run,
+    );
+    pool.wait();
+
+    if installed.is_err() || !(new_path.is_dir() || is_dry_run()) {
+        error!("Failed to install {}, keeping {}", new_mod, old_mod);
+        uninstall_mod(
+            pool,
+            &profile.workdir_path,
+            std::slice::from_ref(&map_name),
+            new_mod,
+            true,
+            profile.ce_dir.as_deref(),
+        );
+        pool.wait();
+        if let Some(old_keys) = find_keys_folder(&old_path) {
+            copy_keys(&old_keys, &keys_path)?;
+        }
+        return Err(installed.err().unwrap_or(ModError::InstallError));
+    }
+
+    uninstall_mod(
+        pool,
+        &profile.workdir_path,
+        std::slice::from_ref(&map_name),
+        old_mod,
+        !profile.manual_ce_mods.iter().any(|name| name == old_mod),
+        profile.ce_dir.as_deref(),
+    );
+    pool.wait();
+    if let Some(new_keys) = find_keys_folder(&new_path) {
+        copy_keys(&new_keys, &keys_path)?;
+    }
+
+    profile.installed_mods[index] = json!(new_mod);
+    for name in profile
+        .load_order
+        .iter_mut()
+        .chain(profile.server_mods.iter_mut())
+        .chain(profile.manual_ce_mods.iter_mut())
+    {
+        if name == old_mod {
+            *name = new_mod.to_string();
+        }
+    }
+    profile.mod_sources.remove(old_mod);
+    if let Some(tags) = profile.mod_tags.remove(old_mod) {
+        profile.mod_tags.insert(new_mod.to_string(), tags);
+    }
+
+    let startup_parameters = parse_startup_parameter(&profile)?;
+    profile.start_parameters = Some(startup_parameters.to_string());
+
+    Ok(profile)
+}
+
+/// Moves an installed mod to a position in the load order and saves the profile.
+///
+/// See `apply_load_order_move` for how the position is applied. Returns the resulting
+/// startup parameter string.
+pub fn move_load_order(profile: Profile, name: &str, index: usize) -> Result<String, ModError> {
+    let applied = apply_load_order_move(profile, name, index)?;
+
+    let mut updated_profile = get_config_path()
+        .and_then(|path| get_profile(&path))
+        .map_err(|_| ModError::InstallError)?;
+    updated_profile.load_order = applied.load_order;
+    updated_profile.start_parameters = applied.start_parameters.clone();
+    save_profile(&updated_profile).map_err(|_| ModError::InstallError)?;
+
+    info!("Moved {} to position {} of the load order", name, index);
+    Ok(applied.start_parameters.unwrap_or_default())
+}
+
+/// Moves an installed mod to `index` in the load order and returns the updated profile.
+///
+/// The load order is first resolved against the installed mods, so the stored order
+/// afterwards lists every installed mod. `index` is zero-based and must be smaller than
+/// the number of installed mods.
+pub fn apply_load_order_move(
+    mut profile: Profile,
+    name: &str,
+    index: usize,
+) -> Result<Profile, ModError> {
+    let installed_mods_names: Vec<String> = profile
+        .installed_mods
+        .iter()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+    let mut order = order_by_load_order(&installed_mods_names, &profile.load_order);
+
+    let Some(current) = order.iter().position(|mod_name| mod_name == name) else {
+        return Err(ModError::NotInstalled(name.to_string()));
+    };
+    if index >= order.len() {
+        return Err(ModError::InvalidLoadOrderIndex(index, order.len()));
+    }
+
+    let moved = order.remove(current);
+    order.insert(index, moved);
+    profile.load_order = order;
+
+    let startup_parameters = parse_startup_parameter(&profile)?;
+    profile.start_parameters = Some(startup_parameters.to_string());
+
+    Ok(profile)
+}
+
+/// Returns the installed mods of a profile that are not enabled in the loadout.
+fn get_unlisted_mods(profile: &Profile, loadout: &Loadout) -> Vec<String> {
+    profile
+        .installed_mods
+        .iter()
+        .filter_map(|v| v.as_str())
+        .filter(|name| !loadout.mods.iter().any(|m| m.enabled && m.name == *name))
+        .map(String::from)
+        .collect()
+}
+
+/// Logs the time spent per mod, the slowest first, if any job was timed.
+fn log_timings(timings: &TimingReport) {
+    if !timings.is_empty() {
+        info!("{}", timings.format());
+    }
+}
+
+/// The progress bar and reports shared by the mods installed in one run, see `install_mod`.
+#[derive(Debug)]
+struct InstallRun {
+    progress: ProgressBar,
+    timings: TimingReport,
+    /// The mods whose install jobs all succeeded.
+    installed: Mutex<Vec<String>>,
+    /// The map the CE files are extracted to, see `resolve_install_map`.
+    map_name: Option<String>,
+}
+
+impl InstallRun {
+    fn new(progress: ProgressBar, map_name: Option<String>) -> Arc<Self> {
+        Arc::new(InstallRun {
+            progress,
+            map_name,
+            timings: TimingReport::default(),
+            installed: Mutex::new(Vec::new()),
+        })
+    }
+}
+
+/// Resolves the map the CE files of an install go to, before any install job is started.
+///
+/// Returns `None` if CE extraction is off or none of `sources` has a types folder, so
+/// mods without CE content install into a workdir without a detectable map.
+fn resolve_install_map(
+    profile: &Profile,
+    options: &InstallOptions,
+    sources: &[PathBuf],
+) -> Result<Option<String>, ModError> {
+    if !options.extract_types
+        || !sources
+            .iter()
+            .any(|source| find_types_folder(source).is_some())
+    {
+        return Ok(None);
+    }
+
+    resolve_map_name(&profile.workdir_path, options.map.as_deref()).map(Some)
+}
+
+/// Tracks the pool jobs of a single mod installation as one unit.
+///
+/// The counter starts at one for the dispatching thread, so the mod can not be reported
+/// as complete while its jobs are still being scheduled. The last call to `done`
+/// advances the progress bar of the run by `weight`.
+struct ModJobs {
+    mod_name: String,
+    pending: AtomicUsize,
+    failed: AtomicBool,
+    run: Arc<InstallRun>,
+    weight: u64,
+}
+
+impl ModJobs {
+    fn new(mod_name: &str, run: Arc<InstallRun>, weight: u64) -> Arc<Self> {
+        Arc::new(ModJobs {
+            mod_name: mod_name.to_string(),
+            pending: AtomicUsize::new(1),
+            failed: AtomicBool::new(false),
+            run,
+            weight,
+        })
+    }
+
+    /// Runs `job` and adds its duration to the time of the mod.
+    fn timed<T>(&self, job: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = job();
+        self.run.timings.record(&self.mod_name, start.elapsed());
+        result
+    }
+
+    /// Returns the shared bar if it counts bytes, for `copy_dir` to advance per file.
+    fn byte_progress(&self) -> Option<&ProgressBar> {
+        (!self.run.progress.counts_items()).then_some(&self.run.progress)
+    }
+
+    /// Registers a job that is about to be dispatched.
+    fn add(&self) {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Marks a job as completed.
+    fn done(&self, success: bool) {
+        if !success {
+            self.failed.store(true, Ordering::SeqCst);
+        }
+
+        if self.pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+            if self.failed.load(Ordering::SeqCst) {
+                error!("{} was not installed completely", self.mod_name);
+            } else {
+                debug!("All install jobs of {} completed", self.mod_name);
+                self.run
+                    .installed
+                    .lock()
+                    .unwrap()
+                    .push(self.mod_name.clone());
+            }
+            self.run.progress.inc(self.weight);
+        }
+    }
+}
+
+/// Lists all installed mods for a given DayZ profile.
+///
+/// This function retrieves a list of all installed mods from the specified profile
+/// and displays them in the console. The mods are displayed one per line using
+/// the info log level. The function handles the conversion from the internal
+/// JSON representation to readable mod names.
+///
+/// The displayed mod names include their '@' prefix as they appear in the
+/// DayZ server directory structure.
+///
+/// If `outdated` is set, each mod is annotated with its update state compared to the
+/// workshop (see `get_mod_statuses`). If `markdown` is set, the list is printed as
+/// Markdown with workshop links instead (see `format_mods_markdown`). If `with_keys` is
+/// set, each mod is listed with its keys and whether they are in the server's keys
+/// folder (see `get_key_statuses`). If `tag` is set, only the mods carrying that tag are
+/// listed.
+pub fn list_installed_mods(
+    mut profile: Profile,
+    outdated: bool,
+    markdown: bool,
+    with_keys: bool,
+    tag: Option<&str>,
+) -> Result<(), ModError> {
+    if let Some(tag) = tag {
+        let tagged = mods_with_tag(&profile, tag);
+        profile.installed_mods.retain(|entry| {
+            entry
+                .as_str()
+                .is_some_and(|name| tagged.iter().any(|t| t == name))
+        });
+    }
+
+    let installed_mods = get_installed_mod_list(profile.clone()).unwrap();
+    let installed_mods_names: Vec<String> = installed_mods
+        .into_iter()
+        .map(|v| v.as_str().unwrap().to_string())
+        .collect();
+
+    if installed_mods_names.is_empty() {
+        info!("No mods installed.");
+        return Ok(());
+    }
+
+    if markdown {
+        println!("{}", format_mods_markdown(&profile)?);
+        return Ok(());
+    }
+
+    if outdated {
+        return for_each_mod_status(&profile, &THREAD_POOL, |mod_name, status| match status {
+            ModStatus::UpToDate => info!("{} ({})", mod_name, status),
+            _ => warn!("{} ({})", mod_name, status),
+        });
+    }
+
+    if with_keys {
+        for (mod_name, keys) in get_key_statuses(&profile) {
+            if keys.is_empty() {
+                warn!("{} (no keys)", mod_name);
+                continue;
+            }
+            let states: Vec<String> = keys
+                .iter()
+                .map(|(key, present)| {
+                    let state = if *present { "present" } else { "missing" };
+                    format!("{} {}", key, state)
+                })
+                .collect();
+            if keys.iter().all(|(_, present)| *present) {
+                info!("{} ({})", mod_name, states.join(", "));
+            } else {
+                warn!("{} ({})", mod_name, states.join(", "));
+            }
+        }
+        return Ok(());
+    }
+
+    for mod_name in installed_mods_names {
+        match profile.mod_tags.get(&mod_name) {
+            Some(tags) if !tags.is_empty() => info!("{} [{}]", mod_name, tags.join(", ")),
+            _ => info!("{}", mod_name),
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the installed mods that carry `tag`, in install order.
+///
+/// Tags are compared case-insensitively.
+pub fn mods_with_tag(profile: &Profile, tag: &str) -> Vec<String> {
+    profile
+        .installed_mods
+        .iter()
+        .filter_map(|v| v.as_str())
+        .filter(|name| {
+            profile
+                .mod_tags
+                .get(*name)
+                .is_some_and(|tags| tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+        })
+        .map(String::from)
+        .collect()
+}
+
+/// Assigns tags to an installed mod and saves the profile.
+///
+/// See `apply_mod_tags` for how the tags are stored.
+pub fn tag_mod(profile: Profile, name: &str, tags: &[String]) -> Result<(), ModError> {
+    let applied = apply_mod_tags(profile, name, tags)?;
+
+    let mut updated_profile = get_config_path()
+        .and_then(|path| get_profile(&path))
+        .map_err(|_| ModError::InstallError)?;
+    updated_profile.mod_tags = applied.mod_tags;
+    save_profile(&updated_profile).map_err(|_| ModError::InstallError)?;
+
+    if tags.is_empty() {
+        info!("Removed the tags of {}", name);
+    } else {
+        info!("Tagged {} with {}", name, normalize_tags(tags).join(", "));
+    }
+    Ok(())
+}
+
+/// Replaces the tags of an installed mod and returns the updated profile.
+///
+/// The tags are normalized with `normalize_tags`. An empty list removes the mod's tags.
+pub fn apply_mod_tags(
+    mut profile: Profile,
+    name: &str,
+    tags: &[String],
+) -> Result<Profile, ModError> {
+    if !profile
+        .installed_mods
+        .iter()
+        .any(|v| v.as_str() == Some(name))
+    {
+        return Err(ModError::NotInstalled(name.to_string()));
+    }
+
+    let tags = normalize_tags(tags);
+    if tags.is_empty() {
+        profile.mod_tags.remove(name);
+    } else {
+        profile.mod_tags.insert(name.to_string(), tags);
+    }
+
+    Ok(profile)
+}
+
+/// Formats the installed mods of a profile as a Markdown list.
+///
+/// Each mod is listed with the display name from its `meta.cpp` and linked to its
+/// Steam Workshop page. The `meta.cpp` of the workdir copy is preferred, falling back to
+/// the workshop copy. Mods without a workshop ID are listed by name only.
+pub fn format_mods_markdown(profile: &Profile) -> Result<String, ModError> {
+    let installed_mods = get_installed_mod_list(profile.clone())?;
+    let mut lines = Vec::new();
+
+    for mod_entry in installed_mods {
+        let mod_name = mod_entry.as_str().ok_or(ModError::ParseError)?;
+        let workshop_name = profile
+            .mod_sources
+            .get(mod_name)
+            .map(String::as_str)
+            .unwrap_or(mod_name);
+        let paths = [
+            Path::new(&profile.workdir_path).join(mod_name),
+            Path::new(&profile.workshop_path).join(workshop_name),
+        ];
+
+        let display_name = paths
+            .iter()
+            .find_map(|path| read_mod_name(path))
+            .unwrap_or_else(|| mod_name.to_string());
+        let display_name = display_name.trim_start_matches('@');
+
+        match paths.iter().find_map(|path| read_published_id(path)) {
+            Some(id) => lines.push(format!("- [{}]({}{})", display_name, WORKSHOP_URL, id)),
+            None => lines.push(format!("- {}", display_name)),
+        }
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Compares every installed mod of a profile with its workshop copy.
+///
+/// Mods whose workshop folder no longer exists are reported as
+/// `ModStatus::MissingFromWorkshop`. Mods whose workdir folder was deleted are reported
+/// as `ModStatus::MissingFromWorkdir`. Mods failing the comparison are reported as
+/// `ModStatus::Outdated`, since an update would replace them.
+pub fn get_mod_statuses(
+    profile: &Profile,
+    pool: &ThreadPool,
+) -> Result<Vec<(String, ModStatus)>, ModError> {
+    let mut statuses = Vec::new();
+    for_each_mod_status(profile, pool, |mod_name, status| {
+        statuses.push((mod_name.to_string(), status));
+    })?;
+    Ok(statuses)
+}
+
+/// Returns the status of every installed mod and every mod in the workshop.
+///
+/// The installed mods come first, in install order, with their status from
+/// `get_mod_statuses`. They are followed by the workshop mods that are not installed
+/// (neither under their own name nor as the source of a renamed mod), sorted by name
+/// and marked as `ModStatus::Available`. The workshop is searched `scan_depth` levels
+/// deep, see `find_workshop_mods`.
+pub fn get_workshop_statuses(
+    profile: &Profile,
+    pool: &ThreadPool,
+    scan_depth: usize,
+) -> Result<Vec<(String, ModStatus)>, ModError> {
+    let mut statuses = get_mod_statuses(profile, pool)?;
+
+    let mut available: Vec<String> =
+        find_workshop_mods(Path::new(&profile.workshop_path), scan_depth)
+            .iter()
+            .filter(|path| path.is_dir())
+            .filter_map(|path| {
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+            })
+            .filter(|name| {
+                !statuses.iter().any(|(installed, _)| {
+                    installed == name || profile.mod_sources.get(installed) == Some(name)
+                })
+            })
+            .collect();
+    available.sort();
+    available.dedup();
+
+    statuses.extend(
+        available
+            .into_iter()
+            .map(|name| (name, ModStatus::Available)),
+    );
+    Ok(statuses)
+}
+
+/// Prints the installed and available mods with their status as a table.
+///
+/// See `get_workshop_statuses` for the rows.
+pub fn show_mod_status(profile: &Profile, scan_depth: usize) -> Result<(), ModError> {
+    let statuses = get_workshop_statuses(profile, &THREAD_POOL, scan_depth)?;
+    if statuses.is_empty() {
+        info!("No mods installed or available.");
+        return Ok(());
+    }
+
+    let width = statuses
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    println!("{}", THEME.header(format!("{:<width$}  Status", "Mod")));
+    for (name, status) in statuses {
+        let row = format!("{:<width$}  {}", name, status);
+        match status {
+            ModStatus::UpToDate => println!("{}", THEME.value(row)),
+            ModStatus::Available => println!("{}", THEME.value_italic(row)),
+            _ => println!("{}", THEME.value_bold(row)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks every installed mod against the workshop without updating it.
+///
+/// The statuses from `get_mod_statuses` are printed as a table with the labels `OK`,
+/// `OUTDATED`, `MISSING IN WORKSHOP` and `MISSING IN WORKDIR`, and returned so the
+/// caller can fail on drift (see `ModStatus::needs_update`).
+pub fn verify_mods(
+    profile: &Profile,
+    pool: &ThreadPool,
+) -> Result<Vec<(String, ModStatus)>, ModError> {
+    let statuses = get_mod_statuses(profile, pool)?;
+    if statuses.is_empty() {
+        info!("No mods installed.");
+        return Ok(statuses);
+    }
+
+    let width = statuses
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    println!("{}", THEME.header(format!("{:<width$}  Status", "Mod")));
+    for (name, status) in &statuses {
+        let label = match status {
+            ModStatus::UpToDate => "OK",
+            ModStatus::Outdated => "OUTDATED",
+            ModStatus::MissingFromWorkshop => "MISSING IN WORKSHOP",
+            ModStatus::MissingFromWorkdir => "MISSING IN WORKDIR",
+            ModStatus::Available => "AVAILABLE",
+        };
+        let row = format!("{:<width$}  {}", name, label);
+        match status {
+            ModStatus::UpToDate => println!("{}", THEME.value(row)),
+            _ => println!("{}", THEME.value_bold(row)),
+        }
+    }
+
+    Ok(statuses)
+}
+
+/// Compares the installed mods one by one and passes each status to `on_status`.
+///
+/// `on_status` is called as soon as a mod has been compared, before the next mod is
+/// hashed, so large mod lists can be printed while they are checked. See
+/// `get_mod_statuses` for how the status is determined.
+pub fn for_each_mod_status(
+    profile: &Profile,
+    pool: &ThreadPool,
+    mut on_status: impl FnMut(&str, ModStatus),
+) -> Result<(), ModError> {
+    let installed_mods = get_installed_mod_list(profile.clone())?;
+
+    for mod_entry in installed_mods {
+        let mod_name = mod_entry.as_str().ok_or(ModError::ParseError)?.to_string();
+        let workshop_name = profile.mod_sources.get(&mod_name).unwrap_or(&mod_name);
+        let mod_workshop_path = Path::new(&profile.workshop_path).join(workshop_name);
+        let mod_workdir_path = Path::new(&profile.workdir_path).join(&mod_name);
+
+        let status = if !mod_workshop_path.exists() {
+            ModStatus::MissingFromWorkshop
+        } else if !mod_workdir_path.exists() {
+            ModStatus::MissingFromWorkdir
+        } else {
+            match compare_mod_versions(&mod_workshop_path, &mod_workdir_path, pool) {
+                Ok(true) => ModStatus::UpToDate,
+                Ok(false) => ModStatus::Outdated,
+                Err(e) => {
+                    error!("Failed to compare versions for {}: {}", mod_name, e);
+                    ModStatus::Outdated
+                }
+            }
+        };
+
+        on_status(&mod_name, status);
+    }
+
+    Ok(())
+}
+
+/// Lists the installed mods that `update_mods` would replace, without updating them.
+///
+/// Excluded mods are skipped. With `verbose`, the added, removed and changed files of
+/// each outdated mod are counted with `diff_mod_files` and printed below it. Returns
+/// the outdated mods, with their diff if `verbose` is set.
+pub fn check_updates(
+    profile: &Profile,
+    pool: &ThreadPool,
+    exclude: &[String],
+    verbose: bool,
+) -> Result<Vec<(String, Option<ModDiff>)>, ModError> {
+    let mut outdated = Vec::new();
+    for (mod_name, status) in get_mod_statuses(profile, pool)? {
+        if !status.needs_update() || exclude.contains(&mod_name) {
+            continue;
+        }
+        println!(
+            "{} {}",
+            THEME.label(&mod_name),
+            THEME.value_bold(status.to_string())
+        );
+
+        let diff = if verbose {
+            let workshop_name = profile.mod_sources.get(&mod_name).unwrap_or(&mod_name);
+            let diff = diff_mod_files(
+                &Path::new(&profile.workshop_path).join(workshop_name),
+                &Path::new(&profile.workdir_path).join(&mod_name),
+                pool,
+            )
+            .map_err(|e| {
+                error!("Failed to compare the files of {}: {}", mod_name, e);
+                ModError::ReadError
+            })?;
+            println!(
+                "  {} added, {} removed, {} changed",
+                THEME.value_bold(diff.added.len().to_string()),
+                THEME.value_bold(diff.removed.len().to_string()),
+                THEME.value_bold(diff.changed.len().to_string()),
+            );
+            Some(diff)
+        } else {
+            None
+        };
+        outdated.push((mod_name, diff));
+    }
+
+    if outdated.is_empty() {
+        info!("All mods are up to date.");
+    }
+    Ok(outdated)
+}
+
+/// Updates installed mods by replacing their directories and types configurations.
+///
+/// This function performs the following operations for each installed mod:
+/// 1. Removes the existing mod directory from the workdir
+/// 2. Copies the latest version from the workshop directory
+/// 3. Updates types configurations if changes are detected
+///
+/// The mods are processed in the profile's load order (see `order_by_load_order`). Once
+/// all copies finished, the types of up-to-date mods that depend on a successfully
+/// updated mod (see `Profile::mod_dependencies`) are re-extracted to keep them consistent.
+/// Other up-to-date mods keep their CE files, including any edits made to them.
+///
+/// The function uses a thread pool for parallel processing of mods to improve performance.
+/// All operations are logged for tracking and debugging purposes. The time spent copying
+/// and extracting each mod is logged at the end, the slowest mod first. A single progress
+/// bar counts the bytes of all mods and is advanced by `copy_dir` as the files are copied.
+///
+/// If `map` is given, it is used as the mission folder for the CE files instead of the
+/// map detected from `serverDZ.cfg`.
+///
+/// Mods named in `exclude` are skipped for this run. Excluded names that are not
+/// installed are warned about.
+///
+/// Returns an `UpdateSummary` with the outcome of every processed mod. Mods whose copy
+/// fails inside the pool are reported as failed instead of updated.
+///
+/// By default, a failing mod is recorded and the remaining mods are still updated. With
+/// `fail_fast`, no further mod is processed after the first failure and
+/// `ModError::UpdateAborted` is returned instead of the summary. Copies are then awaited
+/// one at a time, so a failed copy is noticed before the next mod is touched.
+pub fn update_mods(
+    profile: Profile,
+    pool: &ThreadPool,
+    map: Option<&str>,
+    exclude: &[String],
+    fail_fast: bool,
+) -> Result<UpdateSummary, ModError> {
+    check_distinct_paths(&profile)?;
+
+    let installed_mods = get_installed_mod_list(profile.clone()).unwrap();
+    let installed_mods_names: Vec<String> = installed_mods
+        .into_iter()
+        .map(|v| v.as_str().unwrap().to_string())
+        .collect();
+    let workdir_path = profile.workdir_path.clone();
+    let workshop_path = profile.workshop_path.clone();
+
+    if installed_mods_names.is_empty() {
+        info!("No mods installed.");
+        return Ok(UpdateSummary::default());
+    }
+
+    for name in exclude {
+        if !installed_mods_names.contains(name) {
+            warn!("Excluded mod {} is not installed", name);
+        }
+    }
+
+    info!("Starting mod updates...");
+
+    // The bar is weighted by size. Copies advance it per file, skipped mods all at once.
+    let sizes: HashMap<&str, u64> = installed_mods_names
+        .iter()
+        .filter(|name| !exclude.contains(name))
+        .map(|name| {
+            let workshop_name = profile.mod_sources.get(name).unwrap_or(name);
+            let size = get_dir_size(&Path::new(&workshop_path).join(workshop_name));
+            (name.as_str(), size)
+        })
+        .collect();
+    let progress = Arc::new(ProgressBar::new(
+        sizes.values().sum(),
+        30,
+        "Updating mods",
+        Arc::new(THEME.clone()),
+    ));
+
+    let mut summary = UpdateSummary::default();
+    let mut copied = Vec::new();
+    let mut dependents = Vec::new();
+    let updated_copies = Arc::new(Mutex::new(Vec::new()));
+    let failed_copies = Arc::new(Mutex::new(Vec::new()));
+    let timings = Arc::new(TimingReport::default());
+
+    for mod_name in order_by_load_order(&installed_mods_names, &profile.load_order) {
+        if is_cancelled() {
+            warn!("Mod update cancelled, no further mods are processed");
+            break;
+        }
+
+        if fail_fast && (!summary.failed.is_empty() || !failed_copies.lock().unwrap().is_empty()) {
+            break;
+        }
+
+        if exclude.contains(&mod_name) {
+            info!("Skipping excluded mod {}", mod_name);
+            summary.excluded.push(mod_name);
+            continue;
+        }
+
+        let mod_workdir_path = Path::new(&workdir_path).join(&mod_name);
+        let workshop_name = profile.mod_sources.get(&mod_name).unwrap_or(&mod_name);
+        let mod_workshop_path = Path::new(&workshop_path).join(workshop_name);
+        let size = sizes.get(mod_name.as_str()).copied().unwrap_or_default();
+        let progress = Arc::clone(&progress);
+
+        if !mod_workshop_path.exists() {
+            error!(
+                "Workshop path does not exist for {}: {}",
+                mod_name,
+                mod_workshop_path.display()
+            );
+            summary.missing_from_workshop.push(mod_name);
+            continue;
+        }
+
+        if mod_workdir_path.exists() {
+            info!("Checking if update is needed for {}", mod_name);
+            match compare_mod_versions(&mod_workshop_path, &mod_workdir_path, &THREAD_POOL) {
+                Ok(true) => {
+                    progress.inc(size);
+                    info!("Mod {} is up to date, skipping", mod_name);
+                    summary.up_to_date.push(mod_name.clone());
+                    if profile
+                        .mod_dependencies
+                        .get(&mod_name)
+                        .is_some_and(|deps| deps.iter().any(|dep| copied.contains(dep)))
+                    {
+                        dependents.push((mod_name, mod_workshop_path));
+                    }
+                    continue;
+                }
+                Ok(false) => info!("Update needed for {}", mod_name),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {
+                    warn!("Version check for {} was cancelled", mod_name);
+                    break;
+                }
+                Err(e) => {
+                    error!("Failed to compare versions for {}: {}", mod_name, e);
+                    progress.inc(size);
+                    summary.failed.push(mod_name);
+                    continue;
+                }
+            }
+
+            info!("Removing {} from workdir", mod_name);
+            if let Err(e) = remove_dir(&mod_workdir_path) {
+                error!(
+                    "Failed to remove {} from workdir at {}: {}",
+                    mod_name,
+                    mod_workdir_path.display(),
+                    e
+                );
+                progress.inc(size);
+                summary.failed.push(mod_name);
+                continue;
+            }
+        }
+
+        info!("Updating {} from workshop", mod_name);
+        copied.push(mod_name.clone());
+        pool.execute({
+            let updated_copies = Arc::clone(&updated_copies);
+            let failed_copies = Arc::clone(&failed_copies);
+            let workdir_path = workdir_path.clone();
+            let map = map.map(String::from);
+            let ce_dir = profile.ce_dir.clone();
+            let merge_types = profile.merge_types;
+            let timings = Arc::clone(&timings);
+            move || {
+                let start = Instant::now();
+                let result = copy_dir(&mod_workshop_path, &mod_workdir_path, Some(&progress));
+                if is_dry_run() {
+                    progress.inc(size);
+                }
+                if result.is_ok() {
+                    info!("Successfully copied {} to workdir", mod_name);
+                    update_types_data(
+                        &mod_name,
+                        &mod_workshop_path,
+                        &workdir_path,
+                        map.as_deref(),
+                        ce_dir.as_deref(),
+                        merge_types,
+                    );
+                }
+                timings.record(&mod_name, start.elapsed());
+
+                match result {
+                    Ok(_) => {
+                        info!("Successfully updated {}", mod_name);
+                        updated_copies.lock().unwrap().push(mod_name);
+                    }
+                    Err(e) => {
+                        error!(
+                            "Failed to update {} to workdir.\nSource: {}\nTarget: {}\nError: {:?}",
+                            mod_name,
+                            mod_workshop_path.display(),
+                            mod_workdir_path.display(),
+                            e
+                        );
+                        failed_copies.lock().unwrap().push(mod_name);
+                    }
+                }
+            }
+        });
+        if fail_fast {
+            pool.wait();
+        }
+    }
+
+    pool.wait();
+
+    let updated_copies = updated_copies.lock().unwrap();
+    summary.updated = copied
+        .into_iter()
+        .filter(|name| updated_copies.contains(name))
+        .collect();
+    summary
+        .failed
+        .extend(failed_copies.lock().unwrap().iter().cloned());
+
+    for (mod_name, mod_workshop_path) in dependents {
+        let dependencies = &profile.mod_dependencies[&mod_name];
+        if is_cancelled() || !dependencies.iter().any(|dep| summary.updated.contains(dep)) {
+            continue;
+        }
+
+        info!(
+            "Re-extracting the types of {} after a dependency update",
+            mod_name
+        );
+        pool.execute({
+            let workdir_path = workdir_path.clone();
+            let map = map.map(String::from);
+            let ce_dir = profile.ce_dir.clone();
+            let merge_types = profile.merge_types;
+            let timings = Arc::clone(&timings);
+            move || {
+                let start = Instant::now();
+                update_types_data(
+                    &mod_name,
+                    &mod_workshop_path,
+                    &workdir_path,
+                    map.as_deref(),
+                    ce_dir.as_deref(),
+                    merge_types,
+                );
+                timings.record(&mod_name, start.elapsed());
+            }
+        });
+    }
+    pool.wait();
+    log_timings(&timings);
+
+    if fail_fast {
+        if let Some(failed) = summary.failed.first() {
+            warn!("Mod update stopped early: {}", summary);
+            return Err(ModError::UpdateAborted(failed.clone()));
+        }
+    }
+
+    info!("All mod updates completed.");
+    Ok(summary)
+}
+
+/// Extracts the types data of a workshop mod and saves it to the mission folder.
+///
+/// If `merge_types` is set, admin-added types of the existing types file are kept (see
+/// `merge_custom_types`). Errors are logged instead of returned, as this runs inside the
+/// update jobs.
+fn update_types_data(
+    mod_name: &str,
+    mod_workshop_path: &Path,
+    workdir_path: &str,
+    map: Option<&str>,
+    ce_dir: Option<&str>,
+    merge_types: bool,
+) {
+    let Some(types_folder_path) = find_types_folder(mod_workshop_path) else {
+        info!("No types folder found for {}", mod_name);
+        return;
+    };
+
+    info!(
+        "Found types folder for {}: {}",
+        mod_name,
+        types_folder_path.display()
+    );
+
+    match analyze_types_folder(&types_folder_path) {
+        Ok((Some(types), Some(spawnable_types), Some(events))) => {
+            if types.is_empty() && spawnable_types.is_empty() && events.is_empty() {
+                info!("No types data found for {}", mod_name);
+                return;
+            }
+
+            let mod_short_name = Mod {
+                name: mod_name.to_string(),
+            }
+            .short_name();
+
+            match resolve_map_name(workdir_path, map) {
+                Ok(map_name) => {
+                    info!("Updating types data for {} ({})", mod_name, mod_short_name);
+                    let types = if merge_types {
+                        merge_custom_types(workdir_path, &map_name, &mod_short_name, ce_dir, types)
+                    } else {
+                        types
+                    };
+
+                    if let Err(e) = save_extracted_data(
+                        workdir_path,
+                        &mod_short_name,
+                        &map_name,
+                        types,
+                        spawnable_types,
+                        events,
+                        ce_dir,
+                    ) {
+                        error!("Error updating types data for {}: {}", mod_name, e);
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to get map name for {}: {:?}", mod_name, e);
+                }
+            }
+        }
+        Ok(_) => {
+            error!("Incomplete types data for mod: {}", mod_name);
+        }
+        Err(e) => {
+            error!("Error analyzing types for mod {}: {}", mod_name, e);
+        }
+    }
+}
+
+/// Uninstalls selected mods from the DayZ server directory.
+///
+/// This function performs a complete uninstallation of selected mods by:
+/// 1. Removing bikey files from the keys directory
+/// 2. Deleting mod-specific types folders from the mpmissions directory
+/// 3. Removing the mod directory from the workdir
+/// 4. Cleaning up CE entries from cfgeconomycore.xml
+/// 5. Updating the config.json to remove the mods from installed_mods
+///
+/// The function uses parallel processing through a thread pool to handle multiple
+/// mod uninstallations simultaneously.
+///
+/// The CE data is removed from every mission folder in `mpmissions`, so servers running
+/// several missions keep no orphaned economy data. If `map` is given, only that mission
+/// folder is cleaned.
+///
+/// The mods named in `mods` are uninstalled, or `ModError::NotInstalled` is returned for
+/// the first one that is not installed. Without names the user selects the mods.
+pub fn uninstall_mods(
+    profile: Profile,
+    pool: &ThreadPool,
+    map: Option<&str>,
+    mods: &[String],
+) -> Result<(), ModError> {
+    check_distinct_paths(&profile)?;
+
+    let installed_mods = get_installed_mod_list(profile.clone())?;
+    let installed_mods_names: Vec<String> = installed_mods
+        .into_iter()
+        .map(|v| v.as_str().unwrap().to_string())
+        .collect();
+
+    if installed_mods_names.is_empty() {
+        info!("No mods installed.");
+        return Ok(());
+    }
+
+    let ans = if !mods.is_empty() {
+        if let Some(missing) = mods
+            .iter()
+            .find(|name| !installed_mods_names.contains(name))
+        {
+            return Err(ModError::NotInstalled(missing.clone()));
+        }
+        Ok(mods.to_vec())
+    } else {
+        require_interactive("the mod names with `mods uninstall <name>...`")?;
+        MultiSelect::new("Select mods to uninstall:", installed_mods_names.clone()).prompt()
+    };
+
+    match ans {
+        Ok(selected_mods) => {
+            let map_names = match map {
+                Some(_) => vec![resolve_map_name(&profile.workdir_path, map)?],
+                None => list_mission_folders(&profile.workdir_path)?,
+            };
+
+            debug!(
+                "Starting mod uninstalls for missions: {}",
+                map_names.join(", ")
+            );
+
+            for mod_name in &selected_mods {
+                uninstall_mod(
+                    pool,
+                    &profile.workdir_path,
+                    &map_names,
+                    mod_name,
+                    !profile.manual_ce_mods.contains(mod_name),
+                    profile.ce_dir.as_deref(),
+                );
+            }
+
+            pool.wait();
+
+            if let Err(e) = remove_mods_from_profile(&selected_mods) {
+                error!("Failed to update config.json: {}", e);
+            } else {
+                debug!(
+                    "Successfully removed {} mods from config",
+                    selected_mods.len()
+                );
+            }
+        }
+        Err(_) => return Err(ModError::SelectError),
+    }
+
+    Ok(())
+}
+
+/// Uninstalls a single mod from the DayZ server directory.
+///
+/// Schedules a pool job that removes the keys, the mod folder, and the CE types folder
+/// and CE entries of the mod in each of `map_names`. The CE entries are left alone if
+/// `remove_ce` is false, as for mods whose CE files the admin registers manually. The
+/// profile is not updated.
+pub fn uninstall_mod(
+    pool: &ThreadPool,
+    workdir_path: &str,
+    map_names: &[String],
+    mod_name: &str,
+    remove_ce: bool,
+    ce_dir: Option<&str>,
+) {
+    pool.execute({
+        let mod_name = mod_name.to_string();
+        let workdir_path = workdir_path.to_string();
+        let map_names = map_names.to_vec();
+        let ce_dir = ce_dir.map(String::from);
+
+        move || {
+            let mod_path = Path::new(&workdir_path).join(&mod_name);
+
+            if let Err(e) = remove_keys_for_mod(&workdir_path, &mod_path) {
+                error!("Failed to remove keys for {}: {}", mod_name, e);
+            } else {
+                debug!("Successfully removed keys for {}", mod_name);
+            }
+
+            if mod_path.exists() {
+                if let Err(e) = remove_dir(&mod_path) {
+                    error!("Failed to remove mod folder for {}: {}", mod_name, e);
+                } else {
+                    info!("Successfully removed mod folder for {}", mod_name);
+                }
+            }
+
+            let mod_short = Mod {
+                name: mod_name.clone(),
+            }
+            .short_name();
+
+            for (index, map_name) in map_names.iter().enumerate() {
+                debug!(
+                    "Removing CE data of {} from {} ({}/{})",
+                    mod_name,
+                    map_name,
+                    index + 1,
+                    map_names.len()
+                );
+                let mission_path = Path::new(&workdir_path).join("mpmissions").join(map_name);
+                let types_path = mission_path.join(ce_folder(&mod_short, ce_dir.as_deref()));
+                if types_path.exists() {
+                    if let Err(e) = remove_dir(&types_path) {
+                        error!(
+                            "Failed to remove types folder for {} in {}: {}",
+                            mod_name, map_name, e
+                        );
+                    } else {
+                        debug!(
+                            "Successfully removed types folder for {} in {}",
+                            mod_name, map_name
+                        );
+                    }
+                } else {
+                    debug!("No types folder found for {} in {}", mod_name, map_name);
+                }
+
+                if !remove_ce {
+                    debug!("Leaving manually managed CE entries of {}", mod_name);
+                } else if map_names.len() > 1 && !mission_path.join("cfgeconomycore.xml").exists() {
+                    debug!("No cfgeconomycore.xml in {}, skipping", map_name);
+                } else if let Err(e) =
+                    remove_ce_entries(&workdir_path, map_name, &mod_short, ce_dir.as_deref())
+                {
+                    error!(
+                        "Failed to remove CE entries for {} in {}: {}",
+                        mod_name, map_name, e
+                    );
+                } else {
+                    info!(
+                        "Successfully removed CE entries for {} in {}",
+                        mod_name, map_name
+                    );
+                }
+            }
+        }
+    });
+}
+
+/// Installs only the signing keys of a mod.
+///
+/// This function locates the mod in the workshop directory and copies its `.bikey` files
+/// into `<workdir>/keys` without touching the mod files or CE data. This fixes missing
+/// keys (e.g. after a manual file move) without a full reinstall.
+pub fn install_keys(profile: Profile, mod_name: &str) -> Result<(), ModError> {
+    check_distinct_paths(&profile)?;
+
+    let workshop_name = profile
+        .mod_sources
+        .get(mod_name)
+        .map(String::as_str)
+        .unwrap_or(mod_name);
+    let mod_workshop_path = Path::new(&profile.workshop_path).join(workshop_name);
+
+    if !mod_workshop_path.is_dir() {
+        error!(
+            "Mod {} not found in workshop: {}",
+            mod_name,
+            mod_workshop_path.display()
+        );
+        return Err(ModError::NotFound);
+    }
+
+    let key_source_path = find_keys_folder(&mod_workshop_path).ok_or_else(|| {
+        error!("No keys folder found for {}", mod_name);
+        ModError::NotFound
+    })?;
+
+    let key_target_path = Path::new(&profile.workdir_path).join("keys");
+    if !is_dry_run() {
+        create_dir_all(&key_target_path).map_err(|_| ModError::CreateDirError)?;
+    }
+    copy_keys(&key_source_path, &key_target_path)?;
+
+    info!("Successfully installed keys for {}", mod_name);
+    Ok(())
+}
+
+/// Returns the installed mods whose keys are missing from `<workdir>/keys`.
+///
+/// The `verifySignatures` setting is read from `serverDZ.cfg` with `parse_server_cfg`. A
+/// missing setting counts as `2`, the DayZ default, and with `0` no mod is checked. Mods
+/// in `server_mods` are skipped as clients do not load them. Every other mod needs at
+/// least one of its `.bikey` files, taken from the workdir copy or else the workshop copy,
+/// in the server's `keys` folder; mods without any key are reported as well.
+pub fn check_signatures(profile: &Profile) -> Result<Vec<String>, ModError> {
+    let cfg_path = Path::new(&profile.workdir_path).join("serverDZ.cfg");
+    let verify_signatures = if cfg_path.is_file() {
+        parse_server_cfg(&cfg_path)?
+            .remove("verifySignatures")
+            .unwrap_or_else(|| "2".to_string())
+    } else {
+        "2".to_string()
+    };
+    if verify_signatures.trim() == "0" {
+        info!("verifySignatures is 0, mod signatures are not checked");
+        return Ok(Vec::new());
+    }
+
+    let server_keys = bikey_names(&Path::new(&profile.workdir_path).join("keys"));
+    let mut rejected = Vec::new();
+
+    for mod_name in profile.installed_mods.iter().filter_map(|v| v.as_str()) {
+        if profile.server_mods.iter().any(|name| name == mod_name) {
+            continue;
+        }
+
+        let mod_keys = mod_key_names(profile, mod_name);
+        if mod_keys.is_empty() {
+            warn!("{} has no keys and would be rejected", mod_name);
+            rejected.push(mod_name.to_string());
+        } else if !mod_keys.iter().any(|key| server_keys.contains(key)) {
+            warn!(
+                "{} would be rejected, none of its keys ({}) are in keys/",
+                mod_name,
+                mod_keys.join(", ")
+            );
+            rejected.push(mod_name.to_string());
+        }
+    }
+
+    Ok(rejected)
+}
+
+/// Returns the keys of every installed mod and whether each is in `<workdir>/keys`.
+///
+/// The keys of a mod are looked up like in `check_signatures`. Mods without keys get an
+/// empty list. The mods are returned in install order.
+pub fn get_key_statuses(profile: &Profile) -> Vec<(String, Vec<(String, bool)>)> {
+    let server_keys = bikey_names(&Path::new(&profile.workdir_path).join("keys"));
+
+    profile
+        .installed_mods
+        .iter()
+        .filter_map(|v| v.as_str())
+        .map(|mod_name| {
+            let keys = mod_key_names(profile, mod_name)
+                .into_iter()
+                .map(|key| {
+                    let present = server_keys.contains(&key);
+                    (key, present)
+                })
+                .collect();
+            (mod_name.to_string(), keys)
+        })
+        .collect()
+}
+
+/// Returns the `.bikey` names of a mod, from its workdir copy or else its workshop copy.
+fn mod_key_names(profile: &Profile, mod_name: &str) -> Vec<String> {
+    let workshop_name = profile
+        .mod_sources
+        .get(mod_name)
+        .map(String::as_str)
+        .unwrap_or(mod_name);
+
+    [
+        Path::new(&profile.workdir_path).join(mod_name),
+        Path::new(&profile.workshop_path).join(workshop_name),
+    ]
+    .iter()
+    .filter_map(|path| find_keys_folder(path))
+    .map(|keys| bikey_names(&keys))
+    .find(|keys| !keys.is_empty())
+    .unwrap_or_default()
+}
+
+/// Formats the dependencies between the installed mods as a DOT graph.
+///
+/// Every installed mod is a node, in load order, and every entry of
+/// `Profile::mod_dependencies` an edge from the mod to the mod it requires. Required
+/// mods that are not installed are drawn in red and labelled as missing. The output can
+/// be rendered with Graphviz, e.g. `dot -Tsvg`.
+pub fn format_dependency_graph(profile: &Profile) -> String {
+    let installed_mods_names: Vec<String> = profile
+        .installed_mods
+        .iter()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+    let ordered = order_by_load_order(&installed_mods_names, &profile.load_order);
+
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    let mut missing: Vec<&str> = Vec::new();
+
+    for mod_name in &ordered {
+        nodes.push(format!("    \"{}\";", mod_name));
+        for dependency in profile.mod_dependencies.get(mod_name).into_iter().flatten() {
+            if ordered.contains(dependency) {
+                edges.push(format!("    \"{}\" -> \"{}\";", mod_name, dependency));
+            } else {
+                edges.push(format!(
+                    "    \"{}\" -> \"{}\" [color=red];",
+                    mod_name, dependency
+                ));
+                if !missing.contains(&dependency.as_str()) {
+                    missing.push(dependency);
+                }
+            }
+        }
+    }
+    for dependency in missing {
+        nodes.push(format!(
+            "    \"{0}\" [label=\"{0} (missing)\", color=red, fontcolor=red];",
+            dependency
+        ));
+    }
+
+    let mut lines = vec!["digraph mods {".to_string()];
+    lines.extend(nodes);
+    lines.extend(edges);
+    lines.push("}".to_string());
+    lines.join("\n")
+}
+
+/// Combines the extracted types of all installed mods into one view of the loot economy.
+///
+/// The `<mod>_types.xml` of every installed mod is read from the `_ce` folder of the map,
+/// in load order. If `vanilla` is given, that types file is read first, as the vanilla
+/// types are loaded before the mods. A type defined by several sources is reported as an
+/// override; the last definition wins and decides the category. Mods without a types
+/// file are skipped. Nothing is written.
+pub fn economy_preview(
+    profile: &Profile,
+    map: Option<&str>,
+    vanilla: Option<&Path>,
+) -> Result<EconomyPreview, ModError> {
+    let map_name = resolve_map_name(&profile.workdir_path, map)?;
+    let mission_path = Path::new(&profile.workdir_path)
+        .join("mpmissions")
+        .join(&map_name);
+    let installed_mods_names: Vec<String> = profile
+        .installed_mods
+        .iter()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+
+    let mut sources: Vec<(String, Vec<Type>)> = Vec::new();
+    if let Some(vanilla) = vanilla {
+        sources.push(("vanilla".to_string(), read_types_file(vanilla)?));
+    }
+    for mod_name in order_by_load_order(&installed_mods_names, &profile.load_order) {
+        let mod_short_name = Mod {
+            name: mod_name.clone(),
+        }
+        .short_name();
+        let types_path = mission_path
+            .join(ce_folder(&mod_short_name, profile.ce_dir.as_deref()))
+            .join(format!("{}_types.xml", mod_short_name));
+        if types_path.is_file() {
+            sources.push((mod_name, read_types_file(&types_path)?));
+        }
+    }
+
+    let mut preview = EconomyPreview::default();
+    let mut definitions: Vec<(String, Vec<String>, Option<String>)> = Vec::new();
+    // The index of each type in `definitions`, by its lowercase name.
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    for (source, types) in sources {
+        preview.total_entries += types.len();
+        for item in types {
+            let category = item.category.map(|c| c.name);
+            match indices.get(&item.name.to_lowercase()) {
+                Some(&index) => {
+                    let (_, defined_by, winning_category) = &mut definitions[index];
+                    if !defined_by.contains(&source) {
+                        defined_by.push(source.clone());
+                    }
+                    *winning_category = category;
+                }
+                None => {
+                    indices.insert(item.name.to_lowercase(), definitions.len());
+                    definitions.push((item.name, vec![source.clone()], category));
+                }
+            }
+        }
+    }
+
+    preview.unique_items = definitions.len();
+    for (name, defined_by, category) in definitions {
+        *preview
+            .categories
+            .entry(category.unwrap_or_else(|| "none".to_string()))
+            .or_default() += 1;
+        if defined_by.len() > 1 {
+            preview.overrides.push(TypeOverride {
+                name,
+                sources: defined_by,
+            });
+        }
+    }
+
+    Ok(preview)
+}
+
+/// Returns the lowercase file names of the `.bikey` files in a directory.
+fn bikey_names(dir: &Path) -> Vec<String> {
+    let Ok(entries) = dir.read_dir() else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_lowercase())
+        .filter(|name| name.ends_with(".bikey"))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Rebuilds the CE entries of all installed mods in cfgeconomycore.xml from scratch.
+///
+/// All tool-managed CE blocks (including duplicates and orphans of uninstalled mods) are
+/// removed and fresh blocks are added for every installed mod with an existing `_ce`
+/// folder. Vanilla and manually added CE entries are preserved.
+pub fn rebuild_ce(profile: Profile, map: Option<&str>) -> Result<(), ModError> {
+    let map_name = resolve_map_name(&profile.workdir_path, map)?;
+    let mod_short_names: Vec<String> = get_installed_mod_list(profile.clone())?
+        .iter()
+        .filter_map(|v| v.as_str())
+        .filter(|name| !profile.manual_ce_mods.iter().any(|m| m == name))
+        .map(|name| {
+            Mod {
+                name: name.to_string(),
+            }
+            .short_name()
+        })
+        .collect();
+
+    rebuild_cfgeconomy(
+        &profile.workdir_path,
+        &map_name,
+        &mod_short_names,
+        profile.ce_dir.as_deref(),
+    )?;
+
+    info!(
+        "Rebuilt CE entries for {} installed mods",
+        mod_short_names.len()
+    );
+    Ok(())
+}
+
+/// Validates all CE XML files the tool generated for the active profile.
+///
+/// This function walks the `_ce` folders of the current map and parses every generated
+/// types, spawnable types and events file. Files that fail to parse are reported with
+/// their filename and the parse error, and returned.
+pub fn validate_xml(profile: Profile, map: Option<&str>) -> Result<Vec<PathBuf>, ModError> {
+    let map_name = resolve_map_name(&profile.workdir_path, map)?;
+    let failures = validate_ce_files(&profile.workdir_path, &map_name, profile.ce_dir.as_deref())?;
+
+    if failures.is_empty() {
+        info!("All generated XML files are valid.");
+        return Ok(Vec::new());
+    }
+
+    for (path, e) in &failures {
+        error!("{}: {}", path.display(), e);
+    }
+    error!("{} XML file(s) failed to parse", failures.len());
+
+    Ok(failures.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Collects what was installed for each of the given `(mod name, workshop path)` pairs.
+///
+/// The keys are counted in the workshop copy of each mod, the CE entries in its `_ce`
+/// folder of `map_name` (see `count_ce_entries`). Without a map, no CE entries are counted.
+pub fn summarize_install(
+    profile: &Profile,
+    map_name: Option<&str>,
+    installed: &[(String, PathBuf)],
+) -> InstallSummary {
+    let mods = installed
+        .iter()
+        .map(|(name, source_path)| {
+            let keys =
+                find_keys_folder(source_path).map_or(0, |keys_path| bikey_names(&keys_path).len());
+            let (types, spawnable_types, events) = match map_name {
+                Some(map_name) => {
+                    let mod_short_name = Mod { name: name.clone() }.short_name();
+                    count_ce_entries(
+                        &profile.workdir_path,
+                        map_name,
+                        &mod_short_name,
+                        profile.ce_dir.as_deref(),
+                    )
+                }
+                None => (0, 0, 0),
+            };
+            InstalledModSummary {
+                name: name.clone(),
+                keys,
+                types,
+                spawnable_types,
+                events,
+            }
+        })
+        .collect();
+
+    InstallSummary { mods }
+}
+
+/// Scales the `nominal` and `lifetime` values in the types file of an installed mod.
+///
+/// The `<mod>_types.xml` in the `_ce` folder of the map is read, scaled with `scale_types`
+/// and written back. Returns the number of types in the file.
+///
+/// Note that reinstalling or updating the mod extracts its original types again.
+pub fn scale_mod_types(
+    profile: &Profile,
+    name: &str,
+    map: Option<&str>,
+    nominal: Option<f64>,
+    lifetime: Option<f64>,
+) -> Result<usize, ModError> {
+    for factor in [nominal, lifetime].into_iter().flatten() {
+        if !factor.is_finite() || factor < 0.0 {
+            return Err(ModError::InvalidScaleFactor(factor));
+        }
+    }
+    if !profile
+        .installed_mods
+        .iter()
+        .any(|v| v.as_str() == Some(name))
+    {
+        return Err(ModError::NotInstalled(name.to_string()));
+    }
+
+    let map_name = resolve_map_name(&profile.workdir_path, map)?;
+    let mod_short_name = Mod {
+        name: name.to_string(),
+    }
+    .short_name();
+    let types_path = Path::new(&profile.workdir_path)
+        .join("mpmissions")
+        .join(map_name)
+        .join(ce_folder(&mod_short_name, profile.ce_dir.as_deref()))
+        .join(format!("{}_types.xml", mod_short_name));
+    if !types_path.is_file() {
+        error!("{} has no types file at {}", name, types_path.display());
+        return Err(ModError::NotFound);
+    }
+
+    let mut types = read_types_file(&types_path)?;
+    scale_types(&mut types, nominal, lifetime);
+    let count = types.len();
+    write_types_file(&types_path, types)?;
+
+    Ok(count)
+}
+
+/// Moves CE data stored under outdated short names to the current `Mod::short_name`.
+///
+/// An installed mod needs migrating if its `_ce` folder does not exist under the current
+/// short name. Its CE entry names (see `ce_entry_names`) are read from the workshop copy
+/// and compared with the `_ce` folders of the map that belong to no installed mod. If
+/// exactly one of these contains all of the mod's entries, it is renamed with
+/// `rename_ce_short_name`. The matching does not depend on how the old short name was
+/// computed. Returns the migrated mods with their old and new short names.
+pub fn recompute_short_names(
+    profile: &Profile,
+    map: Option<&str>,
+) -> Result<Vec<(String, String, String)>, ModError> {
+    let map_name = resolve_map_name(&profile.workdir_path, map)?;
+    let mission_path = Path::new(&profile.workdir_path)
+        .join("mpmissions")
+        .join(&map_name);
+    let ce_dir = profile.ce_dir.as_deref();
+    let installed_mods_names: Vec<String> = profile
+        .installed_mods
+        .iter()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect();
+    let short_names: Vec<String> = installed_mods_names
+        .iter()
+        .map(|name| Mod { name: name.clone() }.short_name())
+        .collect();
+
+    let mut orphans: Vec<(String, BTreeSet<String>)> = Vec::new();
+    if let Ok(entries) = ce_base_path(&mission_path, ce_dir).read_dir() {
+        for path in entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+        {
+            let Some(short_name) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix("_ce"))
+            else {
+                continue;
+            };
+            if path.is_dir() && !short_names.iter().any(|s| s == short_name) {
+                orphans.push((short_name.to_string(), ce_entry_names(&path)));
+            }
+        }
+    }
+
+    let mut migrated = Vec::new();
+    for (mod_name, short_name) in installed_mods_names.iter().zip(&short_names) {
+        if mission_path.join(ce_folder(short_name, ce_dir)).exists() {
+            continue;
+        }
+        let workshop_name = profile.mod_sources.get(mod_name).unwrap_or(mod_name);
+        let Some(types_folder) =
+            find_types_folder(&Path::new(&profile.workshop_path).join(workshop_name))
+        else {
+            continue;
+        };
+        let names = ce_entry_names(&types_folder);
+        if names.is_empty() {
+            continue;
+        }
+
+        let matches: Vec<usize> = orphans
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, orphan_names))| names.is_subset(orphan_names))
+            .map(|(index, _)| index)
+            .collect();
+        match matches.as_slice() {
+            [index] => {
+                let (old_short_name, _) = orphans.remove(*index);
+                rename_ce_short_name(
+                    &profile.workdir_path,
+                    &map_name,
+                    &old_short_name,
+                    short_name,
+                    ce_dir,
+                )?;
+                info!(
+                    "Migrated the CE data of {} from {} to {}",
+                    mod_name, old_short_name, short_name
+                );
+                migrated.push((mod_name.clone(), old_short_name, short_name.clone()));
+            }
+            [] => debug!("No CE data found to migrate for {}", mod_name),
+            _ => warn!(
+                "Several _ce folders match {}, migrate it by hand: {}",
+                mod_name,
+                matches
+                    .iter()
+                    .map(|index| format!("{}_ce", orphans[*index].0))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    Ok(migrated)
+}
+
+/// Checks that every CE file referenced in cfgeconomycore.xml exists.
+///
+/// Each missing file is logged as an error, see `find_dangling_ce_files`. Returns the
+/// paths of the missing files.
+pub fn check_ce(profile: &Profile, map: Option<&str>) -> Result<Vec<PathBuf>, ModError> {
+    let map_name = resolve_map_name(&profile.workdir_path, map)?;
+    let dangling = find_dangling_ce_files(&profile.workdir_path, &map_name)?;
+
+    for path in &dangling {
+        error!(
+            "cfgeconomycore.xml references a missing file: {}",
+            path.display()
+        );
+    }
+
+    Ok(dangling)
+}
+
+/// Copies the installed mods into a new snapshot in `<workdir>/.dayz-backups/<timestamp>/`.
+///
+/// Every installed mod folder found in the workdir is copied with `copy_dir`, and a
+/// `manifest.json` lists the backed up mods with their sizes. Mods whose folder is
+/// missing are skipped. Returns the path of the snapshot.
+pub fn backup_mods(profile: &Profile) -> Result<PathBuf, ModError> {
+    let workdir = Path::new(&profile.workdir_path);
+    let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+    let mut snapshot = workdir.join(BACKUP_DIR).join(&timestamp);
+    let mut counter = 2;
+    while snapshot.exists() {
+        snapshot = workdir
+            .join(BACKUP_DIR)
+            .join(format!("{}_{}", timestamp, counter));
+        counter += 1;
+    }
+
+    let mut manifest = BackupManifest {
+        created: Local::now().to_rfc3339(),
+        mods: Vec::new(),
+    };
+    for name in profile.installed_mods.iter().filter_map(|v| v.as_str()) {
+        let mod_path = workdir.join(name);
+        if !mod_path.is_dir() {
+            warn!("Skipping {}, its folder is missing from the workdir", name);
+            continue;
+        }
+
+        debug!("Backing up {}", name);
+        copy_dir(&mod_path, &snapshot.join(name), None)?;
+        manifest.mods.push(BackedUpMod {
+            name: name.to_string(),
+            size: get_dir_size(&mod_path),
+        });
+    }
+
+    let manifest_path = snapshot.join(BACKUP_MANIFEST);
+    if is_dry_run() {
+        info!("[dry-run] Would write {}", manifest_path.display());
+        return Ok(snapshot);
+    }
+    create_dir_all(&snapshot).map_err(|_| ModError::CreateDirError)?;
+    let content = serde_json::to_string_pretty(&manifest).map_err(|_| ModError::WriteError)?;
+    write(&manifest_path, content).map_err(|_| ModError::WriteError)?;
+
+    Ok(snapshot)
+}
+
+/// Returns the snapshot names in the backup folder of the workdir, newest first.
+///
+/// Only folders with a `manifest.json` are listed.
+pub fn list_backups(profile: &Profile) -> Vec<String> {
+    let backup_dir = Path::new(&profile.workdir_path).join(BACKUP_DIR);
+    let mut snapshots: Vec<String> = match backup_dir.read_dir() {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().join(BACKUP_MANIFEST).is_file())
+            .filter_map(|entry| entry.file_name().to_str().map(String::from))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    snapshots.sort_unstable_by(|a, b| b.cmp(a));
+    snapshots
+}
+
+/// Copies the mods of a snapshot created by `backup_mods` back into the workdir.
+///
+/// Each mod listed in the snapshot's manifest replaces the current folder of the same
+/// name. The names are checked before anything is touched: a name that is empty,
+/// absolute or not a single folder name (e.g. `../../etc`) fails the restore with
+/// `ModError::InvalidBackupEntry`. Returns the names of the restored mods.
+pub fn restore_backup(profile: &Profile, snapshot: &str) -> Result<Vec<String>, ModError> {
+    let workdir = Path::new(&profile.workdir_path);
+    let snapshot_path = workdir.join(BACKUP_DIR).join(snapshot);
+    let content =
+        read_to_string(snapshot_path.join(BACKUP_MANIFEST)).map_err(|_| ModError::ReadError)?;
+    let manifest: BackupManifest =
+        serde_json::from_str(&content).map_err(|_| ModError::ParseError)?;
+    if let Some(invalid) = manifest
+        .mods
+        .iter()
+        .find(|m| !is_plain_folder_name(&m.name))
+    {
+        return Err(ModError::InvalidBackupEntry(invalid.name.clone()));
+    }
+
+    let mut restored = Vec::new();
+    for backed_up in manifest.mods {
+        let source = snapshot_path.join(&backed_up.name);
+        if !source.is_dir() {
+            warn!("{} is missing from the backup {}", backed_up.name, snapshot);
+            continue;
+        }
+
+        let target = workdir.join(&backed_up.name);
+        if target.exists() {
+            remove_dir(&target).map_err(|_| ModError::RemoveFileError)?;
+        }
+        copy_dir(&source, &target, None)?;
+        restored.push(backed_up.name);
+    }
+
+    Ok(restored)
+}
+
+/// Returns `true` if `name` is a single folder name that stays inside its parent.
+fn is_plain_folder_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.contains(['/', '\\'])
+        && !name.contains("..")
+        && !Path::new(name).is_absolute()
+}
+
+/// Restores a snapshot of the backup folder with `restore_backup`.
+///
+/// Without a `snapshot` name the user selects one of `list_backups`.
+pub fn restore_mods(profile: &Profile, snapshot: Option<&str>) -> Result<Vec<String>, ModError> {
+    let snapshots = list_backups(profile);
+    if snapshots.is_empty() {
+        let backup_dir = Path::new(&profile.workdir_path).join(BACKUP_DIR);
+        return Err(ModError::NoBackups(backup_dir.display().to_string()));
+    }
+
+    let snapshot = match snapshot {
+        Some(snapshot) if snapshots.iter().any(|s| s == snapshot) => snapshot.to_string(),
+        Some(snapshot) => {
+            error!("No backup named {}", snapshot);
+            return Err(ModError::PathError);
+        }
+        None => {
+            require_interactive("the snapshot with `mods restore <snapshot>`")?;
+            Select::new("Select the backup to restore:", snapshots)
+                .prompt()
+                .map_err(|_| ModError::SelectError)?
+        }
+    };
+
+    restore_backup(profile, &snapshot)
+}
+
+/// Stores the file checksums of the installed mods as a baseline for `check_drift`.
+///
+/// The checksums come from `mod_file_checksums`, with a SHA256 hash for every file so
+/// edits that keep the size are detected as well, and are written to
+/// `<workdir>/.dayz-baseline.json`, replacing a previous baseline. Mods whose folder is
+/// missing are skipped. Returns the path of the baseline file.
+pub fn freeze_mods(profile: &Profile, pool: &ThreadPool) -> Result<PathBuf, ModError> {
+    let workdir = Path::new(&profile.workdir_path);
+    let mut baseline = ModBaseline {
+        created: Local::now().to_rfc3339(),
+        mods: BTreeMap::new(),
+    };
+    for name in profile.installed_mods.iter().filter_map(|v| v.as_str()) {
+        let mod_path = workdir.join(name);
+        if !mod_path.is_dir() {
+            warn!("Skipping {}, its folder is missing from the workdir", name);
+            continue;
+        }
+
+        let checksums = mod_file_checksums(&mod_path, pool, true).map_err(|e| {
+            error!("Failed to calculate the checksums of {}: {}", name, e);
+            ModError::ReadError
+        })?;
+        baseline.mods.insert(name.to_string(), checksums);
+    }
+
+    let baseline_path = workdir.join(BASELINE_FILE);
+    if is_dry_run() {
+        info!("[dry-run] Would write {}", baseline_path.display());
+        return Ok(baseline_path);
+    }
+    let content = serde_json::to_string_pretty(&baseline).map_err(|_| ModError::WriteError)?;
+    write(&baseline_path, content).map_err(|_| ModError::WriteError)?;
+    info!(
+        "Froze the checksums of {} mods in {}",
+        baseline.mods.len(),
+        baseline_path.display()
+    );
+
+    Ok(baseline_path)
+}
+
+/// Compares the installed mods against the baseline written by `freeze_mods`.
+///
+/// Each frozen mod whose files were added, removed or changed since is printed with
+/// the counts and returned with its `ModDiff`. A frozen mod whose folder was deleted
+/// reports all its files as removed. Installed mods that are not in the baseline are
+/// only logged.
+pub fn check_drift(
+    profile: &Profile,
+    pool: &ThreadPool,
+) -> Result<Vec<(String, ModDiff)>, ModError> {
+    let workdir = Path::new(&profile.workdir_path);
+    let baseline_path = workdir.join(BASELINE_FILE);
+    let content = read_to_string(&baseline_path)
+        .map_err(|_| ModError::NoBaseline(baseline_path.display().to_string()))?;
+    let baseline: ModBaseline = serde_json::from_str(&content).map_err(|_| ModError::ParseError)?;
+
+    for name in profile.installed_mods.iter().filter_map(|v| v.as_str()) {
+        if !baseline.mods.contains_key(name) {
+            warn!("{} is not in the baseline of {}", name, baseline.created);
+        }
+    }
+
+    let mut drifted = Vec::new();
+    for (name, frozen) in &baseline.mods {
+        let current = mod_file_checksums(&workdir.join(name), pool, true).map_err(|e| {
+            error!("Failed to calculate the checksums of {}: {}", name, e);
+            ModError::ReadError
+        })?;
+        let diff = diff_checksums(frozen, &current);
+        if diff.is_empty() {
+            continue;
+        }
+
+        println!(
+            "{} {}",
+            THEME.label(name),
+            THEME.value_bold("changed since the baseline")
+        );
+        println!(
+            "  {} added, {} removed, {} changed",
+            THEME.value_bold(diff.added.len().to_string()),
+            THEME.value_bold(diff.removed.len().to_string()),
+            THEME.value_bold(diff.changed.len().to_string()),
+        );
+        drifted.push((name.clone(), diff));
+    }
+
+    if drifted.is_empty() {
+        info!("No mod changed since the baseline of {}", baseline.created);
+    }
+    Ok(drifted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CopyLimiter, SummaryVerbosity};
+    use std::collections::BTreeMap;
+    use std::fs;
+
+    fn test_run(total: u64) -> Arc<InstallRun> {
+        InstallRun::new(
+            ProgressBar::new(total, 30, "Installing mods", Arc::new(THEME.clone()))
+                .with_item_count(),
+            None,
+        )
+    }
+
+    const APPLE_TYPES: &str =
+        "<types>\n<type name=\"Apple\">\n<nominal>10</nominal>\n</type>\n</types>\n";
+    const EMPTY_CFGECONOMY: &str = "<economycore>\n</economycore>";
+
+    fn workdir_profile(workdir: &Path) -> Profile {
+        Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Creates `<base>/workshop/<mod_name>` with a `types.xml`, and `<base>/workdir` with a
+    /// keys folder and a Chernarus mission selected in `serverDZ.cfg`. Returns the mod
+    /// folder and the mission folder.
+    fn create_ce_fixture(base: &Path, mod_name: &str) -> (PathBuf, PathBuf) {
+        let _ = fs::remove_dir_all(base);
+        let source = base.join("workshop").join(mod_name);
+        let workdir = base.join("workdir");
+        let mission = workdir.join("mpmissions/dayzOffline.chernarusplus");
+
+        fs::create_dir_all(source.join("types")).unwrap();
+        fs::write(source.join("types/types.xml"), APPLE_TYPES).unwrap();
+        fs::create_dir_all(workdir.join("keys")).unwrap();
+        fs::create_dir_all(&mission).unwrap();
+        fs::write(
+            workdir.join("serverDZ.cfg"),
+            "template=\"dayzOffline.chernarusplus\";",
+        )
+        .unwrap();
+        fs::write(mission.join("cfgeconomycore.xml"), EMPTY_CFGECONOMY).unwrap();
+
+        (source, mission)
+    }
+
+    /// Creates a mod with an `addons/mod.pbo` holding its name in `<base>/workshop` for
+    /// each of `names`, and copies it to `<base>/workdir` if `installed` is set.
+    fn seed_workshop(base: &Path, names: &[&str], installed: bool) {
+        for name in names {
+            let source = base.join("workshop").join(name);
+            fs::create_dir_all(source.join("addons")).unwrap();
+            fs::write(source.join("addons/mod.pbo"), name).unwrap();
+            if installed {
+                copy_dir(&source, &base.join("workdir").join(name), None).unwrap();
+            }
+        }
+    }
+
+    /// Installs `source` as `mod_name` and waits for its jobs, see `install_mod`.
+    fn install_test_mod(
+        profile: &Profile,
+        source: &Path,
+        mod_name: &str,
+        options: &InstallOptions,
+    ) -> bool {
+        let pool = ThreadPool::new(2);
+        let map_name = resolve_install_map(profile, options, &[source.to_path_buf()]).unwrap();
+        let run = InstallRun::new(
+            ProgressBar::new(1, 30, "Installing mods", Arc::new(THEME.clone())).with_item_count(),
+            map_name,
+        );
+        let copied = install_mod(
+            &pool,
+            source,
+            get_dir_size(source),
+            mod_name,
+            profile,
+            options,
+            &run,
+        )
+        .unwrap();
+        pool.wait();
+        copied
+    }
+
+    #[test]
+    fn test_resolve_install_map_before_any_job() {
+        let base = std::env::temp_dir().join("dayz_tool_resolve_install_map");
+        let (source, _) = create_ce_fixture(&base, "@Food");
+        let workdir = base.join("workdir");
+        let profile = workdir_profile(&workdir);
+        let options = InstallOptions::default();
+        let sources = vec![source.clone()];
+
+        assert_eq!(
+            resolve_install_map(&profile, &options, &sources),
+            Ok(Some("dayzOffline.chernarusplus".to_string()))
+        );
+
+        fs::remove_file(workdir.join("serverDZ.cfg")).unwrap();
+        assert!(resolve_install_map(&profile, &options, &sources).is_err());
+        let no_extraction = InstallOptions {
+            extract_types: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_install_map(&profile, &no_extraction, &sources),
+            Ok(None)
+        );
+        fs::remove_dir_all(source.join("types")).unwrap();
+        assert_eq!(resolve_install_map(&profile, &options, &sources), Ok(None));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_mod_jobs_increment_progress_once_all_completed() {
+        let run = test_run(1);
+        let jobs = ModJobs::new("@CF", Arc::clone(&run), 1);
+
+        jobs.add();
+        jobs.add();
+        jobs.done(true);
+        jobs.done(false);
+        assert_eq!(run.progress.position(), 0);
+
+        jobs.done(true);
+        assert_eq!(run.progress.position(), 1);
+        assert!(run.installed.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_list_installed_mods() {
+        let mod1 = json!("@mod1");
+        let mod2 = json!("@mod2");
+        let mod3 = json!("@mod3");
+        let installed_mods = vec![mod1.clone(), mod2.clone(), mod3.clone()];
+        let profile = Profile {
+            name: String::from("DayZTestServer"),
+            workdir_path: String::from("/home/karnes/Servers/DayZTestServer"),
+            workshop_path: String::from("/home/karnes/Servers/!Workshop"),
+            installed_mods: installed_mods.clone(),
+            start_parameters: Some("".to_string()),
+            is_active: true,
+            ..Default::default()
+        };
+
+        let result = list_installed_mods(profile.clone(), false, false, false, None);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_install_mod_skips_up_to_date_mod() {
+        let base = std::env::temp_dir().join("dayz_tool_install_resume");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(&base, &["@present", "@missing"], false);
+        copy_dir(&workshop.join("@present"), &workdir.join("@present"), None).unwrap();
+        let marker = workdir.join("@present/addons/mod.pbo");
+        let modified_before = fs::metadata(&marker).unwrap().modified().unwrap();
+
+        let pool = ThreadPool::new(2);
+        let run = test_run(2);
+        let profile = workdir_profile(&workdir);
+        let copied_present = install_mod(
+            &pool,
+            &workshop.join("@present"),
+            get_dir_size(&workshop.join("@present")),
+            "@present",
+            &profile,
+            &InstallOptions::default(),
+            &run,
+        )
+        .unwrap();
+        let copied_missing = install_mod(
+            &pool,
+            &workshop.join("@missing"),
+            get_dir_size(&workshop.join("@missing")),
+            "@missing",
+            &profile,
+            &InstallOptions::default(),
+            &run,
+        )
+        .unwrap();
+        pool.wait();
+
+        assert_eq!(run.progress.position(), 2);
+
+        assert!(!copied_present);
+        assert!(copied_missing);
+        assert_eq!(
+            fs::metadata(&marker).unwrap().modified().unwrap(),
+            modified_before
+        );
+        assert!(workdir.join("@missing/addons/mod.pbo").exists());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_install_mod_records_timings() {
+        let base = std::env::temp_dir().join("dayz_tool_install_timings");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        let names = ["@Small", "@Large"];
+        seed_workshop(&base, &names, false);
+        for name in names {
+            fs::create_dir_all(workshop.join(name).join("keys")).unwrap();
+            fs::write(workshop.join(name).join("keys/mod.bikey"), name).unwrap();
+        }
+        fs::create_dir_all(workdir.join("keys")).unwrap();
+
+        let pool = ThreadPool::new(2);
+        let run = test_run(2);
+        let profile = workdir_profile(&workdir);
+        for name in names {
+            install_mod(
+                &pool,
+                &workshop.join(name),
+                get_dir_size(&workshop.join(name)),
+                name,
+                &profile,
+                &InstallOptions::default(),
+                &run,
+            )
+            .unwrap();
+        }
+        pool.wait();
+
+        let mut timed: Vec<String> = run
+            .timings
+            .timings()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        timed.sort();
+        assert_eq!(timed, vec!["@Large", "@Small"]);
+        let report = run.timings.format();
+        assert_eq!(report.lines().count(), 3);
+        assert!(names.iter().all(|name| report.contains(name)));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_install_mod_advances_byte_progress_by_mod_size() {
+        let base = std::env::temp_dir().join("dayz_tool_install_byte_progress");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        fs::create_dir_all(workshop.join("@Small/addons")).unwrap();
+        fs::write(workshop.join("@Small/addons/mod.pbo"), vec![0u8; 10]).unwrap();
+        fs::create_dir_all(workshop.join("@Large/addons")).unwrap();
+        fs::write(workshop.join("@Large/addons/mod.pbo"), vec![0u8; 990]).unwrap();
+
+        let pool = ThreadPool::new(2);
+        let run = InstallRun::new(
+            ProgressBar::new(1000, 30, "Installing mods", Arc::new(THEME.clone())),
+            None,
+        );
+        let profile = workdir_profile(&workdir);
+        install_mod(
+            &pool,
+            &workshop.join("@Small"),
+            get_dir_size(&workshop.join("@Small")),
+            "@Small",
+            &profile,
+            &InstallOptions::default(),
+            &run,
+        )
+        .unwrap();
+        pool.wait();
+        assert_eq!(run.progress.position(), 10);
+
+        install_mod(
+            &pool,
+            &workshop.join("@Large"),
+            get_dir_size(&workshop.join("@Large")),
+            "@Large",
+            &profile,
+            &InstallOptions::default(),
+            &run,
+        )
+        .unwrap();
+        pool.wait();
+        assert_eq!(run.progress.position(), 1000);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_install_mod_as_renamed_folder() {
+        let base = std::env::temp_dir().join("dayz_tool_install_rename");
+        let workdir = base.join("workdir");
+        let (source, mission) = create_ce_fixture(&base, "@Original Mod");
+        fs::create_dir_all(source.join("keys")).unwrap();
+        fs::write(source.join("keys/original.bikey"), "key").unwrap();
+
+        install_test_mod(
+            &workdir_profile(&workdir),
+            &source,
+            "@Renamed",
+            &InstallOptions::default(),
+        );
+
+        let short_name = Mod {
+            name: "@Renamed".to_string(),
+        }
+        .short_name();
+        assert!(workdir.join("@Renamed/types/types.xml").exists());
+        assert!(!workdir.join("@Original Mod").exists());
+        assert!(workdir.join("keys/original.bikey").exists());
+        assert!(mission
+            .join(format!("{}_ce/{}_types.xml", short_name, short_name))
+            .exists());
+        let cfgeconomy = fs::read_to_string(mission.join("cfgeconomycore.xml")).unwrap();
+        assert!(cfgeconomy.contains(&format!("<ce folder=\"{}_ce\">", short_name)));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_install_keys_copies_only_keys() {
+        let base = std::env::temp_dir().join("dayz_tool_install_keys");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        let source = workshop.join("@KeyMod");
+        fs::create_dir_all(source.join("Keys")).unwrap();
+        fs::create_dir_all(source.join("addons")).unwrap();
+        fs::write(source.join("Keys/keymod.bikey"), "key").unwrap();
+        fs::write(source.join("addons/keymod.pbo"), "pbo").unwrap();
+        fs::create_dir_all(&workdir).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+
+        install_keys(profile, "@KeyMod").unwrap();
+
+        assert!(workdir.join("keys/keymod.bikey").exists());
+        assert!(!workdir.join("@KeyMod").exists());
+        assert_eq!(fs::read_dir(&workdir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_update_mods_follows_load_order() {
+        let base = std::env::temp_dir().join("dayz_tool_update_order");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        let installed = ["@Trader", "@CF", "@Expansion"];
+        seed_workshop(&base, &installed, false);
+        for name in installed {
+            fs::create_dir_all(workshop.join(name).join("types")).unwrap();
+            fs::write(workshop.join(name).join("types/types.xml"), APPLE_TYPES).unwrap();
+        }
+        let mission = workdir.join("mpmissions/dayzOffline.chernarusplus");
+        fs::create_dir_all(&mission).unwrap();
+        fs::write(
+            workdir.join("serverDZ.cfg"),
+            "template=\"dayzOffline.chernarusplus\";",
+        )
+        .unwrap();
+        fs::write(mission.join("cfgeconomycore.xml"), EMPTY_CFGECONOMY).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: installed.iter().map(|name| json!(name)).collect(),
+            load_order: vec!["@CF".to_string(), "@Expansion".to_string()],
+            mod_dependencies: BTreeMap::from([("@Expansion".to_string(), vec!["@CF".to_string()])]),
+            ..Default::default()
+        };
+
+        let pool = ThreadPool::new(2);
+        let updated = update_mods(profile.clone(), &pool, None, &[], false).unwrap();
+
+        assert_eq!(updated.updated, vec!["@CF", "@Expansion", "@Trader"]);
+        assert!(workdir.join("@Trader/addons/mod.pbo").exists());
+
+        // Only @Expansion depends on the updated @CF, so @Trader keeps its CE files.
+        let ce_path = |name: &str| {
+            let short_name = Mod {
+                name: name.to_string(),
+            }
+            .short_name();
+            mission.join(ce_folder(&short_name, None))
+        };
+        fs::remove_dir_all(ce_path("@Expansion")).unwrap();
+        fs::remove_dir_all(ce_path("@Trader")).unwrap();
+        fs::write(workshop.join("@CF/addons/mod.pbo"), "new version").unwrap();
+
+        let updated = update_mods(profile, &pool, None, &[], false).unwrap();
+
+        assert_eq!(updated.updated, vec!["@CF"]);
+        assert_eq!(updated.up_to_date, vec!["@Expansion", "@Trader"]);
+        assert!(ce_path("@Expansion").is_dir());
+        assert!(!ce_path("@Trader").exists());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_update_mods_summary() {
+        let base = std::env::temp_dir().join("dayz_tool_update_summary");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(&base, &["@Current", "@Outdated", "@Excluded"], true);
+        fs::write(workshop.join("@Outdated/addons/mod.pbo"), "new version").unwrap();
+        fs::create_dir_all(workdir.join("@Removed")).unwrap();
+        // A file instead of a mod folder makes the copy fail inside the pool.
+        fs::write(workshop.join("@Broken"), "not a mod").unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: ["@Current", "@Outdated", "@Removed", "@Excluded", "@Broken"]
+                .iter()
+                .map(|name| json!(name))
+                .collect(),
+            ..Default::default()
+        };
+
+        let pool = ThreadPool::new(2);
+        let summary = update_mods(profile, &pool, None, &["@Excluded".to_string()], false).unwrap();
+
+        assert_eq!(
+            summary,
+            UpdateSummary {
+                updated: vec!["@Outdated".to_string()],
+                up_to_date: vec!["@Current".to_string()],
+                missing_from_workshop: vec!["@Removed".to_string()],
+                excluded: vec!["@Excluded".to_string()],
+                failed: vec!["@Broken".to_string()],
+            }
+        );
+        assert_eq!(
+            summary.to_string(),
+            "1 updated, 1 up to date, 1 missing from workshop, 1 excluded, 1 failed"
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_update_mods_skips_excluded() {
+        let base = std::env::temp_dir().join("dayz_tool_update_exclude");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        let installed = ["@CF", "@Trader", "@Expansion"];
+        seed_workshop(&base, &installed, false);
+        fs::create_dir_all(&workdir).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: installed.iter().map(|name| json!(name)).collect(),
+            ..Default::default()
+        };
+
+        let pool = ThreadPool::new(2);
+        let exclude = vec!["@Trader".to_string(), "@NotInstalled".to_string()];
+        let updated = update_mods(profile, &pool, None, &exclude, false).unwrap();
+
+        assert_eq!(updated.updated, vec!["@CF", "@Expansion"]);
+        assert!(!workdir.join("@Trader").exists());
+        assert!(workdir.join("@Expansion/addons/mod.pbo").exists());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_install_progress_reaches_selected_mod_count() {
+        let base = std::env::temp_dir().join("dayz_tool_install_progress");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        let selected = ["@CF", "@Trader", "@Expansion"];
+        for name in selected {
+            fs::create_dir_all(workshop.join(name).join("keys")).unwrap();
+            fs::write(workshop.join(name).join("keys/mod.bikey"), name).unwrap();
+        }
+        fs::create_dir_all(workdir.join("keys")).unwrap();
+
+        let pool = ThreadPool::new(2);
+        let run = test_run(selected.len() as u64);
+        let profile = workdir_profile(&workdir);
+        for name in selected {
+            install_mod(
+                &pool,
+                &workshop.join(name),
+                get_dir_size(&workshop.join(name)),
+                name,
+                &profile,
+                &InstallOptions::default(),
+                &run,
+            )
+            .unwrap();
+        }
+        pool.wait();
+
+        assert_eq!(run.progress.position(), selected.len() as u64);
+        assert_eq!(run.installed.lock().unwrap().len(), selected.len());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_check_drift_after_freeze() {
+        let workdir = std::env::temp_dir().join("dayz_tool_check_drift");
+        let _ = fs::remove_dir_all(&workdir);
+        for name in ["@Stable", "@Tampered"] {
+            fs::create_dir_all(workdir.join(name).join("addons")).unwrap();
+            fs::write(workdir.join(name).join("addons/mod.pbo"), name).unwrap();
+        }
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@Stable"), json!("@Tampered")],
+            ..Default::default()
+        };
+        let pool = ThreadPool::new(2);
+
+        assert_eq!(
+            check_drift(&profile, &pool),
+            Err(ModError::NoBaseline(
+                workdir.join(BASELINE_FILE).display().to_string()
+            ))
+        );
+
+        let baseline_path = freeze_mods(&profile, &pool).unwrap();
+        assert!(baseline_path.is_file());
+        assert!(check_drift(&profile, &pool).unwrap().is_empty());
+
+        fs::write(workdir.join("@Tampered/addons/mod.pbo"), "edited by hand").unwrap();
+        fs::write(workdir.join("@Tampered/addons/extra.pbo"), "extra").unwrap();
+
+        let drifted = check_drift(&profile, &pool).unwrap();
+        assert_eq!(drifted.len(), 1);
+        assert_eq!(drifted[0].0, "@Tampered");
+        assert_eq!(
+            drifted[0].1,
+            ModDiff {
+                added: vec![PathBuf::from("addons/extra.pbo")],
+                removed: vec![],
+                changed: vec![PathBuf::from("addons/mod.pbo")],
+            }
+        );
+
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_check_drift_detects_same_size_edit() {
+        let workdir = std::env::temp_dir().join("dayz_tool_check_drift_same_size");
+        let _ = fs::remove_dir_all(&workdir);
+        let config = workdir.join("@Config/config.json");
+        fs::create_dir_all(config.parent().unwrap()).unwrap();
+        fs::write(&config, r#"{"maxPlayers": 60}"#).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@Config")],
+            ..Default::default()
+        };
+        let pool = ThreadPool::new(2);
+        freeze_mods(&profile, &pool).unwrap();
+
+        fs::write(&config, r#"{"maxPlayers": 99}"#).unwrap();
+        // The checksum cache is keyed by size and mtime, so make sure the mtime moves.
+        fs::File::options()
+            .write(true)
+            .open(&config)
+            .unwrap()
+            .set_modified(std::time::SystemTime::now() + std::time::Duration::from_secs(60))
+            .unwrap();
+
+        let drifted = check_drift(&profile, &pool).unwrap();
+        assert_eq!(drifted.len(), 1);
+        assert_eq!(drifted[0].1.changed, vec![PathBuf::from("config.json")]);
+
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_check_updates_verbose_counts_file_changes() {
+        let base = std::env::temp_dir().join("dayz_tool_check_updates");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(&base, &["@Current", "@Outdated"], true);
+        fs::write(workdir.join("@Outdated/addons/old.pbo"), "old").unwrap();
+        fs::write(workshop.join("@Outdated/addons/mod.pbo"), "new version").unwrap();
+        fs::write(workshop.join("@Outdated/addons/new.pbo"), "new").unwrap();
+        fs::write(workshop.join("@Outdated/addons/extra.pbo"), "extra").unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@Current"), json!("@Outdated")],
+            ..Default::default()
+        };
+
+        let pool = ThreadPool::new(2);
+        let outdated = check_updates(&profile, &pool, &[], true).unwrap();
+
+        assert_eq!(outdated.len(), 1);
+        let (name, diff) = &outdated[0];
+        assert_eq!(name, "@Outdated");
+        let diff = diff.as_ref().unwrap();
+        assert_eq!(diff.to_string(), "2 added, 1 removed, 1 changed");
+        assert_eq!(diff.changed, vec![PathBuf::from("addons/mod.pbo")]);
+
+        assert_eq!(
+            check_updates(&profile, &pool, &[], false).unwrap(),
+            vec![("@Outdated".to_string(), None)]
+        );
+        assert!(
+            check_updates(&profile, &pool, &["@Outdated".to_string()], true)
+                .unwrap()
+                .is_empty()
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_verify_mods() {
+        let base = std::env::temp_dir().join("dayz_tool_verify_mods");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(&base, &["@Current", "@Outdated", "@Deleted"], true);
+        fs::write(workshop.join("@Outdated/addons/mod.pbo"), "new version").unwrap();
+        fs::remove_dir_all(workdir.join("@Deleted")).unwrap();
+        fs::create_dir_all(workdir.join("@Removed")).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![
+                json!("@Current"),
+                json!("@Outdated"),
+                json!("@Deleted"),
+                json!("@Removed"),
+            ],
+            ..Default::default()
+        };
+
+        let pool = ThreadPool::new(2);
+        let statuses = verify_mods(&profile, &pool).unwrap();
+
+        assert_eq!(
+            statuses,
+            vec![
+                ("@Current".to_string(), ModStatus::UpToDate),
+                ("@Outdated".to_string(), ModStatus::Outdated),
+                ("@Deleted".to_string(), ModStatus::MissingFromWorkdir),
+                ("@Removed".to_string(), ModStatus::MissingFromWorkshop),
+            ]
+        );
+        let needs_update: Vec<&str> = statuses
+            .iter()
+            .filter(|(_, status)| status.needs_update())
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(needs_update, vec!["@Outdated", "@Deleted"]);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_get_mod_statuses() {
+        let base = std::env::temp_dir().join("dayz_tool_mod_statuses");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(&base, &["@Current", "@Outdated"], true);
+        fs::write(workshop.join("@Outdated/addons/mod.pbo"), "new version").unwrap();
+        fs::create_dir_all(workdir.join("@Removed")).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@Current"), json!("@Outdated"), json!("@Removed")],
+            ..Default::default()
+        };
+
+        let pool = ThreadPool::new(2);
+        let statuses = get_mod_statuses(&profile, &pool).unwrap();
+
+        assert_eq!(
+            statuses,
+            vec![
+                ("@Current".to_string(), ModStatus::UpToDate),
+                ("@Outdated".to_string(), ModStatus::Outdated),
+                ("@Removed".to_string(), ModStatus::MissingFromWorkshop),
+            ]
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_get_workshop_statuses() {
+        let base = std::env::temp_dir().join("dayz_tool_workshop_statuses");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(
+            &base,
+            &["@Current", "@Outdated", "@Trader", "@New", "@Another"],
+            false,
+        );
+        fs::write(workshop.join("notes.txt"), "not a mod").unwrap();
+        copy_dir(&workshop.join("@Current"), &workdir.join("@Current"), None).unwrap();
+        copy_dir(
+            &workshop.join("@Outdated"),
+            &workdir.join("@Outdated"),
+            None,
+        )
+        .unwrap();
+        copy_dir(&workshop.join("@Trader"), &workdir.join("@MyTrader"), None).unwrap();
+        fs::write(workshop.join("@Outdated/addons/mod.pbo"), "new version").unwrap();
+        fs::create_dir_all(workdir.join("@Removed")).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![
+                json!("@Outdated"),
+                json!("@Current"),
+                json!("@MyTrader"),
+                json!("@Removed"),
+            ],
+            mod_sources: BTreeMap::from([("@MyTrader".to_string(), "@Trader".to_string())]),
+            ..Default::default()
+        };
+
+        let pool = ThreadPool::new(2);
+        let statuses = get_workshop_statuses(&profile, &pool, 1).unwrap();
+
+        assert_eq!(
+            statuses,
+            vec![
+                ("@Outdated".to_string(), ModStatus::Outdated),
+                ("@Current".to_string(), ModStatus::UpToDate),
+                ("@MyTrader".to_string(), ModStatus::UpToDate),
+                ("@Removed".to_string(), ModStatus::MissingFromWorkshop),
+                ("@Another".to_string(), ModStatus::Available),
+                ("@New".to_string(), ModStatus::Available),
+            ]
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_for_each_mod_status_streams_per_mod() {
+        let base = std::env::temp_dir().join("dayz_tool_mod_status_stream");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(&base, &["@First", "@Second"], true);
 
-    #[test]
-    fn test_list_installed_mods() {
-        let mod1 = json!("@mod1");
-        let mod2 = json!("@mod2");
-        let mod3 = json!("@mod3");
-        let installed_mods = vec![mod1.clone(), mod2.clone(), mod3.clone()];
         let profile = Profile {
-            name: String::from("DayZTestServer"),
-            workdir_path: String::from("/home/karnes/Servers/DayZTestServer"),
-            workshop_path: String::from("/home/karnes/Servers/!Workshop"),
-            installed_mods: installed_mods.clone(),
-            start_parameters: Some("".to_string()),
-            is_active: true,
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@First"), json!("@Second")],
+            ..Default::default()
         };
 
-        let result = list_installed_mods(profile.clone());
+        // Removing @Second while the row of @First is emitted only shows up in the
+        // result if @Second is checked after that row.
+        let pool = ThreadPool::new(2);
+        let mut rows = Vec::new();
+        for_each_mod_status(&profile, &pool, |mod_name, status| {
+            if rows.is_empty() {
+                fs::remove_dir_all(workshop.join("@Second")).unwrap();
+            }
+            rows.push((mod_name.to_string(), status));
+        })
+        .unwrap();
 
-        assert!(result.is_ok());
+        assert_eq!(
+            rows,
+            vec![
+                ("@First".to_string(), ModStatus::UpToDate),
+                ("@Second".to_string(), ModStatus::MissingFromWorkshop),
+            ]
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_write_mod_params_after_install() {
+        let base = std::env::temp_dir().join("dayz_tool_mod_params");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(&base, &["@CF", "@Admin"], false);
+        fs::create_dir_all(&workdir).unwrap();
+
+        let options = InstallOptions {
+            register_ce: false,
+            ..Default::default()
+        };
+        for name in ["@CF", "@Admin"] {
+            install_test_mod(
+                &workdir_profile(&workdir),
+                &workshop.join(name),
+                name,
+                &options,
+            );
+        }
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@CF"), json!("@Admin")],
+            server_mods: vec!["@Admin".to_string()],
+            ..Default::default()
+        };
+        let params_path = write_mod_params(&profile.workdir_path, &profile).unwrap();
+
+        assert_eq!(params_path, workdir.join(MOD_PARAMS_FILE));
+        assert_eq!(
+            fs::read_to_string(&params_path).unwrap(),
+            "\"-mod=@CF\"\n\"-serverMod=@Admin\"\n"
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_format_mods_markdown() {
+        let base = std::env::temp_dir().join("dayz_tool_mods_markdown");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        fs::create_dir_all(workdir.join("@CF")).unwrap();
+        fs::write(
+            workdir.join("@CF/meta.cpp"),
+            "protocol = 1;\npublishedid = 1559212036;\nname = \"Community Framework\";\n",
+        )
+        .unwrap();
+        fs::create_dir_all(workshop.join("@Admin Tools")).unwrap();
+        fs::write(
+            workshop.join("@Admin Tools/meta.cpp"),
+            "protocol = 1;\npublishedid = 1564026768;\nname = \"VPPAdminTools\";\n",
+        )
+        .unwrap();
+        fs::create_dir_all(workdir.join("@Local")).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@CF"), json!("@AdminTools"), json!("@Local")],
+            mod_sources: BTreeMap::from([("@AdminTools".to_string(), "@Admin Tools".to_string())]),
+            ..Default::default()
+        };
+
+        let markdown = format_mods_markdown(&profile).unwrap();
+
+        assert_eq!(
+            markdown,
+            "- [Community Framework](https://steamcommunity.com/sharedfiles/filedetails/?id=1559212036)\n\
+             - [VPPAdminTools](https://steamcommunity.com/sharedfiles/filedetails/?id=1564026768)\n\
+             - Local"
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_apply_loadout() {
+        let base = std::env::temp_dir().join("dayz_tool_apply_loadout");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(&base, &["@CF", "@Admin Tools", "@Trader", "@Unused"], false);
+        copy_dir(&workshop.join("@CF"), &workdir.join("@CF"), None).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@CF")],
+            ..Default::default()
+        };
+        let loadout: Loadout = serde_json::from_value(json!({
+            "mods": [
+                { "name": "@Trader" },
+                { "name": "@CF" },
+                { "name": "@AdminTools", "source": "@Admin Tools", "serverMod": true },
+                { "name": "@Unused", "enabled": false }
+            ]
+        }))
+        .unwrap();
+
+        let pool = ThreadPool::new(2);
+        let profile = apply_loadout(&pool, profile, &loadout, None, false).unwrap();
+
+        assert_eq!(
+            profile.installed_mods,
+            vec![json!("@Trader"), json!("@CF"), json!("@AdminTools")]
+        );
+        assert_eq!(profile.load_order, vec!["@Trader", "@CF", "@AdminTools"]);
+        assert_eq!(profile.server_mods, vec!["@AdminTools"]);
+        assert_eq!(
+            profile.mod_sources.get("@AdminTools"),
+            Some(&"@Admin Tools".to_string())
+        );
+        assert_eq!(
+            profile.start_parameters.as_deref(),
+            Some("\"-mod=@Trader;@CF\" \"-serverMod=@AdminTools\"")
+        );
+        assert!(workdir.join("@AdminTools/addons/mod.pbo").exists());
+        assert!(!workdir.join("@Unused").exists());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_apply_loadout_prune_removes_unlisted_mod() {
+        let base = std::env::temp_dir().join("dayz_tool_loadout_prune");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let mission = workdir.join("mpmissions/dayzOffline.chernarusplus");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(&base, &["@CF", "@Extra"], true);
+        fs::create_dir_all(&mission).unwrap();
+        fs::write(
+            workdir.join("serverDZ.cfg"),
+            "template=\"dayzOffline.chernarusplus\";",
+        )
+        .unwrap();
+        fs::write(
+            mission.join("cfgeconomycore.xml"),
+            "<economycore>\n</economycore>",
+        )
+        .unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@CF"), json!("@Extra")],
+            server_mods: vec!["@Extra".to_string()],
+            mod_tags: BTreeMap::from([("@Extra".to_string(), vec!["pvp".to_string()])]),
+            mod_dependencies: BTreeMap::from([("@Extra".to_string(), vec!["@CF".to_string()])]),
+            ..Default::default()
+        };
+        let loadout: Loadout = serde_json::from_value(json!({
+            "mods": [{ "name": "@CF" }]
+        }))
+        .unwrap();
+
+        let pool = ThreadPool::new(2);
+        let kept = apply_loadout(&pool, profile.clone(), &loadout, None, false).unwrap();
+        assert_eq!(kept.installed_mods, vec![json!("@CF"), json!("@Extra")]);
+
+        let pruned = apply_loadout(&pool, profile, &loadout, None, true).unwrap();
+        assert_eq!(pruned.installed_mods, vec![json!("@CF")]);
+        assert!(pruned.server_mods.is_empty());
+        assert!(pruned.mod_tags.is_empty());
+        assert!(pruned.mod_dependencies.is_empty());
+        assert!(!workdir.join("@Extra").exists());
+        assert!(workdir.join("@CF").exists());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_apply_swap_keeps_load_order_position() {
+        let base = std::env::temp_dir().join("dayz_tool_swap_mod");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(
+            &base,
+            &["@CF", "@Trader", "@TraderPlus", "@Expansion"],
+            false,
+        );
+        fs::create_dir_all(workshop.join("@Trader/keys")).unwrap();
+        fs::write(workshop.join("@Trader/keys/trader.bikey"), "old").unwrap();
+        fs::create_dir_all(workshop.join("@TraderPlus/keys")).unwrap();
+        fs::write(workshop.join("@TraderPlus/keys/trader.bikey"), "new").unwrap();
+        for name in ["@CF", "@Trader", "@Expansion"] {
+            copy_dir(&workshop.join(name), &workdir.join(name), None).unwrap();
+        }
+        fs::create_dir_all(workdir.join("keys")).unwrap();
+        fs::write(workdir.join("keys/trader.bikey"), "old").unwrap();
+        fs::create_dir_all(workdir.join("mpmissions/dayzOffline.chernarusplus")).unwrap();
+        fs::write(
+            workdir.join("serverDZ.cfg"),
+            "template=\"dayzOffline.chernarusplus\";",
+        )
+        .unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@CF"), json!("@Trader"), json!("@Expansion")],
+            load_order: vec!["@Trader".to_string(), "@CF".to_string()],
+            ..Default::default()
+        };
+
+        let pool = ThreadPool::new(2);
+        let missing = apply_swap(&pool, profile.clone(), "@Trader", "@Missing", None);
+        assert_eq!(missing, Err(ModError::NotFound));
+        assert!(workdir.join("@Trader").exists());
+
+        let swapped = apply_swap(&pool, profile, "@Trader", "@TraderPlus", None).unwrap();
+
+        assert_eq!(
+            swapped.installed_mods,
+            vec![json!("@CF"), json!("@TraderPlus"), json!("@Expansion")]
+        );
+        assert_eq!(swapped.load_order, vec!["@TraderPlus", "@CF"]);
+        assert_eq!(
+            swapped.start_parameters.as_deref(),
+            Some("\"-mod=@TraderPlus;@CF;@Expansion\"")
+        );
+        assert!(!workdir.join("@Trader").exists());
+        assert!(workdir.join("@TraderPlus/addons/mod.pbo").exists());
+        assert_eq!(
+            fs::read_to_string(workdir.join("keys/trader.bikey")).unwrap(),
+            "new"
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_apply_load_order_move_to_front() {
+        let workdir = std::env::temp_dir().join("dayz_tool_move_load_order");
+        let _ = fs::remove_dir_all(&workdir);
+        for name in ["@CF", "@Trader", "@Expansion"] {
+            fs::create_dir_all(workdir.join(name)).unwrap();
+        }
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@CF"), json!("@Trader"), json!("@Expansion")],
+            load_order: vec!["@Trader".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            apply_load_order_move(profile.clone(), "@Missing", 0),
+            Err(ModError::NotInstalled("@Missing".to_string()))
+        );
+        assert_eq!(
+            apply_load_order_move(profile.clone(), "@CF", 3),
+            Err(ModError::InvalidLoadOrderIndex(3, 3))
+        );
+
+        let moved = apply_load_order_move(profile, "@Expansion", 0).unwrap();
+
+        assert_eq!(moved.load_order, vec!["@Expansion", "@Trader", "@CF"]);
+        assert_eq!(
+            moved.start_parameters.as_deref(),
+            Some("\"-mod=@Expansion;@Trader;@CF\"")
+        );
+
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_apply_mod_tags_and_filter() {
+        let profile = Profile {
+            installed_mods: vec![json!("@CF"), json!("@Trader"), json!("@RHS")],
+            ..Default::default()
+        };
+        let tags = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            apply_mod_tags(profile.clone(), "@Missing", &tags(&["qol"])),
+            Err(ModError::NotInstalled("@Missing".to_string()))
+        );
+
+        let profile = apply_mod_tags(profile, "@RHS", &tags(&["weapons", " vehicles "])).unwrap();
+        let profile = apply_mod_tags(profile, "@CF", &tags(&["QoL", "qol", ""])).unwrap();
+        let profile = apply_mod_tags(profile, "@Trader", &tags(&["qol"])).unwrap();
+
+        assert_eq!(profile.mod_tags["@RHS"], vec!["weapons", "vehicles"]);
+        assert_eq!(profile.mod_tags["@CF"], vec!["QoL"]);
+        assert_eq!(mods_with_tag(&profile, "qol"), vec!["@CF", "@Trader"]);
+        assert_eq!(mods_with_tag(&profile, "weapons"), vec!["@RHS"]);
+        assert!(mods_with_tag(&profile, "maps").is_empty());
+
+        let profile = apply_mod_tags(profile, "@Trader", &[]).unwrap();
+        assert!(!profile.mod_tags.contains_key("@Trader"));
+        assert_eq!(mods_with_tag(&profile, "qol"), vec!["@CF"]);
+    }
+
+    #[test]
+    fn test_check_signatures_flags_missing_key() {
+        let base = std::env::temp_dir().join("dayz_tool_check_signatures");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        for (name, key) in [("@CF", "cf.bikey"), ("@Trader", "trader.bikey")] {
+            fs::create_dir_all(workdir.join(name).join("keys")).unwrap();
+            fs::write(workdir.join(name).join("keys").join(key), "key").unwrap();
+        }
+        fs::create_dir_all(workdir.join("@Unsigned/addons")).unwrap();
+        fs::create_dir_all(workdir.join("@ServerOnly")).unwrap();
+        fs::create_dir_all(workdir.join("keys")).unwrap();
+        fs::write(workdir.join("keys/CF.bikey"), "key").unwrap();
+        fs::write(workdir.join("serverDZ.cfg"), "verifySignatures = 2;\n").unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: base.join("workshop").to_str().unwrap().to_string(),
+            installed_mods: vec![
+                json!("@CF"),
+                json!("@Trader"),
+                json!("@Unsigned"),
+                json!("@ServerOnly"),
+            ],
+            server_mods: vec!["@ServerOnly".to_string()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            check_signatures(&profile).unwrap(),
+            vec!["@Trader", "@Unsigned"]
+        );
+
+        fs::write(workdir.join("serverDZ.cfg"), "verifySignatures = 0;\n").unwrap();
+        assert!(check_signatures(&profile).unwrap().is_empty());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_get_key_statuses_shows_missing_key() {
+        let base = std::env::temp_dir().join("dayz_tool_key_statuses");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        for (name, key) in [("@CF", "cf.bikey"), ("@Trader", "trader.bikey")] {
+            fs::create_dir_all(workshop.join(name).join("Keys")).unwrap();
+            fs::write(workshop.join(name).join("Keys").join(key), "key").unwrap();
+        }
+        fs::create_dir_all(workshop.join("@Config")).unwrap();
+        fs::create_dir_all(workdir.join("keys")).unwrap();
+        fs::write(workdir.join("keys/cf.bikey"), "key").unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@CF"), json!("@Trader"), json!("@Config")],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            get_key_statuses(&profile),
+            vec![
+                ("@CF".to_string(), vec![("cf.bikey".to_string(), true)]),
+                (
+                    "@Trader".to_string(),
+                    vec![("trader.bikey".to_string(), false)]
+                ),
+                ("@Config".to_string(), vec![]),
+            ]
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_format_dependency_graph() {
+        let profile = Profile {
+            installed_mods: vec![json!("@Trader"), json!("@CF"), json!("@Expansion")],
+            load_order: vec!["@CF".to_string()],
+            mod_dependencies: BTreeMap::from([
+                ("@Trader".to_string(), vec!["@CF".to_string()]),
+                (
+                    "@Expansion".to_string(),
+                    vec!["@CF".to_string(), "@Dabs".to_string()],
+                ),
+            ]),
+            ..Default::default()
+        };
+
+        let graph = format_dependency_graph(&profile);
+
+        assert!(graph.contains("    \"@Trader\" -> \"@CF\";"));
+        assert!(graph.contains("    \"@Expansion\" -> \"@Dabs\" [color=red];"));
+        assert_eq!(
+            graph,
+            [
+                "digraph mods {",
+                "    \"@CF\";",
+                "    \"@Trader\";",
+                "    \"@Expansion\";",
+                "    \"@Dabs\" [label=\"@Dabs (missing)\", color=red, fontcolor=red];",
+                "    \"@Trader\" -> \"@CF\";",
+                "    \"@Expansion\" -> \"@CF\";",
+                "    \"@Expansion\" -> \"@Dabs\" [color=red];",
+                "}",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_summarize_install() {
+        let base = std::env::temp_dir().join("dayz_tool_install_summary");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let mission = workdir.join("mpmissions/dayzOffline.enoch");
+        let _ = fs::remove_dir_all(&base);
+
+        fs::create_dir_all(workshop.join("@Weapons/keys")).unwrap();
+        fs::write(workshop.join("@Weapons/keys/weapons.bikey"), "key").unwrap();
+        fs::write(workshop.join("@Weapons/keys/weapons_v2.bikey"), "key").unwrap();
+        fs::create_dir_all(workshop.join("@Food/Keys")).unwrap();
+        fs::write(workshop.join("@Food/Keys/food.bikey"), "key").unwrap();
+        fs::create_dir_all(workshop.join("@Tools")).unwrap();
+
+        fs::create_dir_all(mission.join("We_ce")).unwrap();
+        fs::write(
+            mission.join("We_ce/We_types.xml"),
+            r#"<types>
+    <type name="AK101">
+        <nominal>1</nominal>
+    </type>
+    <type name="M4A1">
+        <nominal>1</nominal>
+    </type>
+</types>
+"#,
+        )
+        .unwrap();
+        fs::write(
+            mission.join("We_ce/We_events.xml"),
+            r#"<events>
+    <event name="StaticHeli">
+        <nominal>1</nominal>
+    </event>
+</events>
+"#,
+        )
+        .unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        let installed: Vec<(String, PathBuf)> = ["@Weapons", "@Food", "@Tools"]
+            .iter()
+            .map(|name| (name.to_string(), workshop.join(name)))
+            .collect();
+
+        let summary = summarize_install(&profile, Some("dayzOffline.enoch"), &installed);
+        assert_eq!(
+            summary.format(SummaryVerbosity::Total),
+            "Installed 3 mods, 1 with CE data, 3 keys"
+        );
+        assert_eq!(
+            summary.format(SummaryVerbosity::PerMod),
+            "Installed 3 mods, 1 with CE data, 3 keys\n\
+             - @Weapons: 2 keys, CE data\n\
+             - @Food: 1 keys\n\
+             - @Tools: 0 keys"
+        );
+        assert_eq!(
+            summary.mods[0],
+            InstalledModSummary {
+                name: "@Weapons".to_string(),
+                keys: 2,
+                types: 2,
+                spawnable_types: 0,
+                events: 1,
+            }
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_recompute_short_names() {
+        let base = std::env::temp_dir().join("dayz_tool_recompute_short_names");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let mission = workdir.join("mpmissions/dayzOffline.enoch");
+        let _ = fs::remove_dir_all(&base);
+        let types_xml = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| {
+                    format!(
+                        "<type name=\"{}\">\n    <nominal>1</nominal>\n</type>\n",
+                        name
+                    )
+                })
+                .collect::<String>()
+        };
+
+        fs::create_dir_all(workshop.join("@Weapons/types")).unwrap();
+        fs::write(
+            workshop.join("@Weapons/types/types.xml"),
+            types_xml(&["AK101", "M4A1"]),
+        )
+        .unwrap();
+        fs::create_dir_all(mission.join("Wea_ce")).unwrap();
+        fs::write(
+            mission.join("Wea_ce/Wea_types.xml"),
+            types_xml(&["AK101", "M4A1", "AdminRifle"]),
+        )
+        .unwrap();
+        fs::create_dir_all(mission.join("Oth_ce")).unwrap();
+        fs::write(mission.join("Oth_ce/Oth_types.xml"), types_xml(&["Apple"])).unwrap();
+        fs::write(
+            mission.join("cfgeconomycore.xml"),
+            r#"<economycore>
+	<!-- Wea -->
+	<ce folder="Wea_ce">
+		<file name="Wea_types.xml" type="types" />
+	</ce>
+	<!-- Oth -->
+	<ce folder="Oth_ce">
+		<file name="Oth_types.xml" type="types" />
+	</ce>
+</economycore>"#,
+        )
+        .unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@Weapons")],
+            ..Default::default()
+        };
+
+        let migrated = recompute_short_names(&profile, Some("dayzOffline.enoch")).unwrap();
+        assert_eq!(
+            migrated,
+            vec![("@Weapons".to_string(), "Wea".to_string(), "We".to_string())]
+        );
+        assert!(!mission.join("Wea_ce").exists());
+        assert!(mission.join("We_ce/We_types.xml").is_file());
+        assert!(mission.join("Oth_ce/Oth_types.xml").is_file());
+        assert_eq!(
+            fs::read_to_string(mission.join("cfgeconomycore.xml")).unwrap(),
+            r#"<economycore>
+	<!-- We -->
+	<ce folder="We_ce">
+		<file name="We_types.xml" type="types" />
+	</ce>
+	<!-- Oth -->
+	<ce folder="Oth_ce">
+		<file name="Oth_types.xml" type="types" />
+	</ce>
+</economycore>"#
+        );
+
+        assert_eq!(
+            recompute_short_names(&profile, Some("dayzOffline.enoch")).unwrap(),
+            vec![]
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_scale_mod_types() {
+        let workdir = std::env::temp_dir().join("dayz_tool_scale_types");
+        let mission = workdir.join("mpmissions/dayzOffline.enoch");
+        let _ = fs::remove_dir_all(&workdir);
+        fs::create_dir_all(mission.join("We_ce")).unwrap();
+        fs::write(
+            mission.join("We_ce/We_types.xml"),
+            r#"<types>
+    <type name="AK101">
+        <nominal>10</nominal>
+        <lifetime>3600</lifetime>
+        <min>8</min>
+    </type>
+    <type name="Mag_AK101_30Rnd">
+        <nominal>15</nominal>
+        <lifetime>7201</lifetime>
+        <min>5</min>
+    </type>
+    <type name="AK101_Custom">
+        <lifetime>0</lifetime>
+    </type>
+</types>
+"#,
+        )
+        .unwrap();
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@Weapons")],
+            ..Default::default()
+        };
+
+        let count = scale_mod_types(
+            &profile,
+            "@Weapons",
+            Some("dayzOffline.enoch"),
+            Some(2.0),
+            Some(0.5),
+        )
+        .unwrap();
+        assert_eq!(count, 3);
+
+        let types = read_types_file(&mission.join("We_ce/We_types.xml")).unwrap();
+        let values: Vec<_> = types
+            .iter()
+            .map(|t| (t.name.as_str(), t.nominal, t.lifetime, t.min))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("AK101", Some(20), Some(1800), Some(8)),
+                ("Mag_AK101_30Rnd", Some(30), Some(3601), Some(5)),
+                ("AK101_Custom", None, Some(0), None),
+            ]
+        );
+
+        scale_mod_types(
+            &profile,
+            "@Weapons",
+            Some("dayzOffline.enoch"),
+            Some(0.1),
+            None,
+        )
+        .unwrap();
+        let types = read_types_file(&mission.join("We_ce/We_types.xml")).unwrap();
+        assert_eq!((types[0].nominal, types[0].min), (Some(2), Some(2)));
+
+        assert_eq!(
+            scale_mod_types(&profile, "@Weapons", None, Some(-1.0), None),
+            Err(ModError::InvalidScaleFactor(-1.0))
+        );
+        assert_eq!(
+            scale_mod_types(&profile, "@Food", None, Some(2.0), None),
+            Err(ModError::NotInstalled("@Food".to_string()))
+        );
+
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_economy_preview_merges_mods() {
+        let base = std::env::temp_dir().join("dayz_tool_economy_preview");
+        let workdir = base.join("workdir");
+        let mission = workdir.join("mpmissions/dayzOffline.enoch");
+        let _ = fs::remove_dir_all(&base);
+        let type_xml = |name: &str, category: &str| {
+            format!(
+                "<type name=\"{}\">\n    <nominal>1</nominal>\n    <category name=\"{}\"/>\n</type>\n",
+                name, category
+            )
+        };
+
+        fs::create_dir_all(mission.join("We_ce")).unwrap();
+        fs::write(
+            mission.join("We_ce/We_types.xml"),
+            type_xml("AK101", "weapons") + &type_xml("Apple", "food"),
+        )
+        .unwrap();
+        fs::create_dir_all(mission.join("Fo_ce")).unwrap();
+        fs::write(
+            mission.join("Fo_ce/Fo_types.xml"),
+            type_xml("Apple", "tools") + &type_xml("Pear", "food"),
+        )
+        .unwrap();
+        fs::write(base.join("types.xml"), type_xml("AK101", "weapons")).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@Food"), json!("@Weapons"), json!("@NoTypes")],
+            load_order: vec!["@Weapons".to_string()],
+            ..Default::default()
+        };
+
+        let preview = economy_preview(&profile, Some("dayzOffline.enoch"), None).unwrap();
+
+        assert_eq!(preview.total_entries, 4);
+        assert_eq!(preview.unique_items, 3);
+        assert_eq!(
+            preview.overrides,
+            vec![TypeOverride {
+                name: "Apple".to_string(),
+                sources: vec!["@Weapons".to_string(), "@Food".to_string()],
+            }]
+        );
+        assert_eq!(
+            preview.categories,
+            BTreeMap::from([
+                ("food".to_string(), 1),
+                ("tools".to_string(), 1),
+                ("weapons".to_string(), 1),
+            ])
+        );
+
+        let vanilla = base.join("types.xml");
+        let preview =
+            economy_preview(&profile, Some("dayzOffline.enoch"), Some(vanilla.as_path())).unwrap();
+        assert_eq!(preview.total_entries, 5);
+        assert_eq!(preview.overrides[0].name, "AK101");
+        assert_eq!(preview.overrides[0].sources, vec!["vanilla", "@Weapons"]);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_copy_limiter_limits_large_copies() {
+        let limiter = Arc::new(CopyLimiter::new(1, 100));
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let pool = ThreadPool::new(4);
+
+        for _ in 0..4 {
+            let limiter = Arc::clone(&limiter);
+            let active = Arc::clone(&active);
+            let peak = Arc::clone(&peak);
+            pool.execute(move || {
+                let _permit = limiter.acquire(1000);
+                let current = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(current, Ordering::SeqCst);
+                std::thread::sleep(std::time::Duration::from_millis(20));
+                active.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+        pool.wait();
+
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+        assert!(limiter.acquire(10).is_none());
+    }
+
+    #[test]
+    fn test_install_mod_without_ce_registration() {
+        let base = std::env::temp_dir().join("dayz_tool_no_ce_register");
+        let (source, mission) = create_ce_fixture(&base, "@Manual");
+
+        let options = InstallOptions {
+            register_ce: false,
+            ..Default::default()
+        };
+        install_test_mod(
+            &workdir_profile(&base.join("workdir")),
+            &source,
+            "@Manual",
+            &options,
+        );
+
+        let short_name = Mod {
+            name: "@Manual".to_string(),
+        }
+        .short_name();
+        assert!(mission
+            .join(format!("{}_ce", short_name))
+            .join(format!("{}_types.xml", short_name))
+            .exists());
+        assert_eq!(
+            fs::read_to_string(mission.join("cfgeconomycore.xml")).unwrap(),
+            EMPTY_CFGECONOMY
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_uninstall_mod_from_all_missions() {
+        let base = std::env::temp_dir().join("dayz_tool_uninstall_all_missions");
+        let source = base.join("workshop/@Multi");
+        let workdir = base.join("workdir");
+        let missions = ["dayzOffline.chernarusplus", "dayzOffline.enoch"];
+        let _ = fs::remove_dir_all(&base);
+
+        fs::create_dir_all(source.join("types")).unwrap();
+        fs::write(source.join("types/types.xml"), APPLE_TYPES).unwrap();
+        for mission in missions {
+            let mission_path = workdir.join("mpmissions").join(mission);
+            fs::create_dir_all(&mission_path).unwrap();
+            fs::write(mission_path.join("cfgeconomycore.xml"), EMPTY_CFGECONOMY).unwrap();
+        }
+
+        let profile = workdir_profile(&workdir);
+        for mission in missions {
+            let options = InstallOptions {
+                map: Some(mission.to_string()),
+                ..Default::default()
+            };
+            install_test_mod(&profile, &source, "@Multi", &options);
+        }
+        let pool = ThreadPool::new(2);
+
+        let short_name = Mod {
+            name: "@Multi".to_string(),
+        }
+        .short_name();
+        for mission in missions {
+            let mission_path = workdir.join("mpmissions").join(mission);
+            assert!(mission_path.join(format!("{}_ce", short_name)).exists());
+        }
+        let map_names = list_mission_folders(workdir.to_str().unwrap()).unwrap();
+        assert_eq!(map_names, missions);
+
+        uninstall_mod(
+            &pool,
+            workdir.to_str().unwrap(),
+            &map_names,
+            "@Multi",
+            true,
+            None,
+        );
+        pool.wait();
+
+        for mission in missions {
+            let mission_path = workdir.join("mpmissions").join(mission);
+            assert!(!mission_path.join(format!("{}_ce", short_name)).exists());
+            assert!(!fs::read_to_string(mission_path.join("cfgeconomycore.xml"))
+                .unwrap()
+                .contains(&format!("{}_ce", short_name)));
+        }
+        assert!(!workdir.join("@Multi").exists());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_install_mod_without_types() {
+        let base = std::env::temp_dir().join("dayz_tool_no_types");
+        let workdir = base.join("workdir");
+        let (source, mission) = create_ce_fixture(&base, "@NoTypes");
+        fs::create_dir_all(source.join("keys")).unwrap();
+        fs::write(source.join("keys/notypes.bikey"), "key").unwrap();
+
+        let options = InstallOptions {
+            extract_types: false,
+            ..Default::default()
+        };
+        install_test_mod(&workdir_profile(&workdir), &source, "@NoTypes", &options);
+
+        assert!(workdir.join("@NoTypes/types/types.xml").exists());
+        assert!(workdir.join("keys/notypes.bikey").exists());
+        assert_eq!(fs::read_dir(&mission).unwrap().count(), 1);
+        assert_eq!(
+            fs::read_to_string(mission.join("cfgeconomycore.xml")).unwrap(),
+            EMPTY_CFGECONOMY
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_install_mod_into_custom_ce_dir() {
+        let base = std::env::temp_dir().join("dayz_tool_custom_ce_dir");
+        let workdir = base.join("workdir");
+        let (source, mission) = create_ce_fixture(&base, "@Custom");
+
+        let profile = Profile {
+            ce_dir: Some("custom".to_string()),
+            ..workdir_profile(&workdir)
+        };
+        install_test_mod(&profile, &source, "@Custom", &InstallOptions::default());
+        let pool = ThreadPool::new(2);
+
+        let short_name = Mod {
+            name: "@Custom".to_string(),
+        }
+        .short_name();
+        assert!(mission
+            .join(format!("custom/{}_ce", short_name))
+            .join(format!("{}_types.xml", short_name))
+            .exists());
+        let cfgeconomy = fs::read_to_string(mission.join("cfgeconomycore.xml")).unwrap();
+        assert!(cfgeconomy.contains(&format!("<ce folder=\"custom/{}_ce\">", short_name)));
+
+        uninstall_mod(
+            &pool,
+            workdir.to_str().unwrap(),
+            &["dayzOffline.chernarusplus".to_string()],
+            "@Custom",
+            true,
+            Some("custom"),
+        );
+        pool.wait();
+
+        assert!(!mission.join(format!("custom/{}_ce", short_name)).exists());
+        assert!(!fs::read_to_string(mission.join("cfgeconomycore.xml"))
+            .unwrap()
+            .contains("<ce folder="));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_update_mods_fail_fast_stops_after_first_failure() {
+        let base = std::env::temp_dir().join("dayz_tool_update_fail_fast");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        fs::create_dir_all(&workshop).unwrap();
+        fs::write(workshop.join("@Broken"), "not a folder").unwrap();
+        fs::create_dir_all(workshop.join("@Next/addons")).unwrap();
+        fs::write(workshop.join("@Next/addons/next.pbo"), "next").unwrap();
+        fs::create_dir_all(&workdir).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@Broken"), json!("@Next")],
+            ..Default::default()
+        };
+
+        let pool = ThreadPool::new(2);
+        let result = update_mods(profile.clone(), &pool, None, &[], true);
+
+        assert_eq!(result, Err(ModError::UpdateAborted("@Broken".to_string())));
+        assert!(!workdir.join("@Next").exists());
+
+        let _ = fs::remove_dir_all(workdir.join("@Broken"));
+        let summary = update_mods(profile, &pool, None, &[], false).unwrap();
+
+        assert_eq!(summary.failed, vec!["@Broken"]);
+        assert_eq!(summary.updated, vec!["@Next"]);
+        assert!(workdir.join("@Next/addons/next.pbo").exists());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_verify_installed_copies_flags_truncated_copy() {
+        let base = std::env::temp_dir().join("dayz_tool_verify_copies");
+        let workshop = base.join("workshop");
+        let workdir = base.join("workdir");
+        let _ = fs::remove_dir_all(&base);
+
+        seed_workshop(&base, &["@CF", "@Trader"], true);
+        fs::write(workdir.join("@Trader/addons/mod.pbo"), "@Tra").unwrap();
+
+        let copies = vec![
+            ("@CF".to_string(), workshop.join("@CF")),
+            ("@Trader".to_string(), workshop.join("@Trader")),
+            ("@Missing".to_string(), workshop.join("@CF")),
+        ];
+        let pool = ThreadPool::new(2);
+
+        assert_eq!(
+            verify_installed_copies(&pool, workdir.to_str().unwrap(), &copies),
+            vec!["@Trader", "@Missing"]
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_update_mods_aborts_on_identical_paths() {
+        let base = std::env::temp_dir().join("dayz_tool_identical_paths");
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("@CF/addons")).unwrap();
+        fs::write(base.join("@CF/addons/cf.pbo"), "cf").unwrap();
+
+        let profile = Profile {
+            workdir_path: base.to_str().unwrap().to_string(),
+            workshop_path: base.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@CF")],
+            ..Default::default()
+        };
+
+        let pool = ThreadPool::new(2);
+        let result = update_mods(profile, &pool, None, &[], false);
+
+        assert!(matches!(result, Err(ModError::OverlappingPaths(_, _))));
+        assert_eq!(
+            fs::read_to_string(base.join("@CF/addons/cf.pbo")).unwrap(),
+            "cf"
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_backup_and_restore_mods() {
+        let workdir = std::env::temp_dir().join("dayz_tool_backup_mods");
+        let _ = fs::remove_dir_all(&workdir);
+        fs::create_dir_all(workdir.join("@CF/addons")).unwrap();
+        fs::write(workdir.join("@CF/addons/cf.pbo"), "original").unwrap();
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            installed_mods: vec![json!("@CF"), json!("@Missing")],
+            ..Default::default()
+        };
+
+        let snapshot = backup_mods(&profile).unwrap();
+        assert!(snapshot.starts_with(workdir.join(BACKUP_DIR)));
+        let manifest: BackupManifest =
+            serde_json::from_str(&fs::read_to_string(snapshot.join(BACKUP_MANIFEST)).unwrap())
+                .unwrap();
+        assert_eq!(
+            manifest.mods,
+            vec![BackedUpMod {
+                name: "@CF".to_string(),
+                size: 8,
+            }]
+        );
+
+        let snapshot_name = snapshot.file_name().unwrap().to_str().unwrap();
+        assert_eq!(list_backups(&profile), vec![snapshot_name.to_string()]);
+
+        fs::write(workdir.join("@CF/addons/cf.pbo"), "corrupt").unwrap();
+        fs::write(workdir.join("@CF/addons/extra.pbo"), "extra").unwrap();
+        let restored = restore_backup(&profile, snapshot_name).unwrap();
+        assert_eq!(restored, vec!["@CF".to_string()]);
+        assert_eq!(
+            fs::read_to_string(workdir.join("@CF/addons/cf.pbo")).unwrap(),
+            "original"
+        );
+        assert!(!workdir.join("@CF/addons/extra.pbo").exists());
+
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_restore_backup_rejects_paths_outside_the_workdir() {
+        let base = std::env::temp_dir().join("dayz_tool_restore_traversal");
+        let _ = fs::remove_dir_all(&base);
+        let workdir = base.join("workdir");
+        let snapshot = workdir.join(BACKUP_DIR).join("snapshot");
+        fs::create_dir_all(snapshot.join("../../../outside")).unwrap();
+        fs::write(base.join("outside/keep.txt"), "keep").unwrap();
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+
+        for name in ["../../outside", "@CF/../..", "", "/tmp", "..\\outside"] {
+            let manifest = BackupManifest {
+                created: String::new(),
+                mods: vec![BackedUpMod {
+                    name: name.to_string(),
+                    size: 0,
+                }],
+            };
+            fs::write(
+                snapshot.join(BACKUP_MANIFEST),
+                serde_json::to_string(&manifest).unwrap(),
+            )
+            .unwrap();
+
+            assert_eq!(
+                restore_backup(&profile, "snapshot"),
+                Err(ModError::InvalidBackupEntry(name.to_string()))
+            );
+        }
+        assert_eq!(
+            fs::read_to_string(base.join("outside/keep.txt")).unwrap(),
+            "keep"
+        );
+
+        fs::remove_dir_all(&base).unwrap();
     }
 }
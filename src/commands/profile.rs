@@ -1,14 +1,21 @@
-use std::path::PathBuf;
+use std::{
+    fs::{read_to_string, File},
+    path::PathBuf,
+};
 
-use inquire::{Confirm, InquireError, Select, Text};
+use inquire::{Confirm, InquireError, Password, Select, Text};
 use log::{debug, error};
+use serde_json::{json, Value};
 
 use crate::{
+    require_interactive,
     utils::{
-        add_profile, get_profiles, get_render_config, remove_profile, save_profile,
-        switch_active_profile,
+        add_profile, get_log_dir, get_profile, get_profiles, get_render_config, normalize_tags,
+        prompt_path, read_config_file, remove_profile, save_profile, switch_active_profile,
+        validate_config, validate_profile, write_config_file, CredentialStore, SystemKeyring,
+        KEYRING_SERVICE,
     },
-    ConfigError, Profile, THEME,
+    ConfigError, Profile, Root, THEME,
 };
 
 /// Displays the configuration details of a DayZ profile in a formatted output.
@@ -29,6 +36,15 @@ pub fn show_profile(profile: Profile) -> Result<(), ConfigError> {
         THEME.label("!Workshop"),
         THEME.value(&profile.workshop_path)
     );
+    println!(
+        "{}:\t\t{}",
+        THEME.label("Logs"),
+        THEME.value(
+            get_log_dir(Some(&profile))
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default()
+        )
+    );
     println!("{}:", THEME.label("Installed Mods"));
 
     if profile.installed_mods.is_empty() {
@@ -52,17 +68,33 @@ pub fn show_profile(profile: Profile) -> Result<(), ConfigError> {
     Ok(())
 }
 
+/// Returns the name of the active profile.
+///
+/// This is the short form of `show_profile`, meant for scripts and prompts.
+///
+/// # Arguments
+/// * `config_path` - A PathBuf reference to the configuration file
+///
+/// # Returns
+/// * `Ok(String)` - The name of the active profile
+/// * `Err(ConfigError)` - If the configuration cannot be read or no profile is active
+pub fn current_profile(config_path: &PathBuf) -> Result<String, ConfigError> {
+    get_profile(config_path).map(|profile| profile.name)
+}
+
 /// Updates an existing profile through an interactive command-line interface.
 ///
 /// This function guides the user through a series of prompts to update various profile settings:
 /// - Profile name
 /// - Working directory path
 /// - Workshop directory path
+/// - Log location (config directory or working directory)
 ///
 /// After each potential modification, the user is prompted to confirm whether they want to save
 /// the changes. The function uses the inquire crate for user interaction and provides
 /// a user-friendly interface with default values and help messages.
 pub fn update_profile(mut profile: Profile) -> Result<(), ConfigError> {
+    require_interactive("the changes by editing the configuration file")?;
     debug!("Starting profile update for '{}'", profile.name);
 
     println!("{}", THEME.header("Update Profile"));
@@ -110,6 +142,18 @@ pub fn update_profile(mut profile: Profile) -> Result<(), ConfigError> {
         profile.workshop_path = new_workshop;
     }
 
+    if let Ok(true) = Confirm::new("Update log location?")
+        .with_default(false)
+        .with_help_message("Change where the operation logs of this profile are written")
+        .prompt()
+    {
+        profile.log_to_workdir = Confirm::new("Write logs to the working directory?")
+            .with_default(profile.log_to_workdir)
+            .with_help_message("Store logs in <workdir>/logs instead of the config directory")
+            .prompt()
+            .expect("Failed to get log location");
+    }
+
     if let Ok(true) = Confirm::new("Save changes?")
         .with_default(true)
         .with_help_message("Save all changes to this profile")
@@ -134,15 +178,16 @@ pub fn update_profile(mut profile: Profile) -> Result<(), ConfigError> {
 /// The created profile is then added to the configuration file.
 pub fn create_profile(config_path: &PathBuf) -> Result<(), ConfigError> {
     debug!("Creating a new profile");
+    require_interactive("the profile with `profile import <path>`")?;
 
     let name = Text::new("Please enter a name.")
         .with_help_message("Please enter a name for your profile. (e.g. Your server's name)")
         .prompt()
         .expect("Failed to get name");
 
-    let workdir_path = Text::new("What's your workdir path?").with_help_message("Please enter the path to your DayZ server's working directory. (e.g. /home/user/DayZServer)").prompt().expect("Failed to get workdir path");
+    let workdir_path = prompt_path("What's your workdir path?", "Please enter the path to your DayZ server's working directory. (e.g. /home/user/DayZServer)");
 
-    let workshop_path = Text::new("What's your !Workshop path?").with_help_message("Please enter the path to your DayZ server's workshop directory. (e.g. for the DayZ Standalone Launcher /path/to/steam/steamapps/common/DayZ/!Workshop)").prompt().expect("Failed to get workshop path");
+    let workshop_path = prompt_path("What's your !Workshop path?", "Please enter the path to your DayZ server's workshop directory. (e.g. for the DayZ Standalone Launcher /path/to/steam/steamapps/common/DayZ/!Workshop)");
 
     let profile = Profile {
         name,
@@ -151,6 +196,7 @@ pub fn create_profile(config_path: &PathBuf) -> Result<(), ConfigError> {
         installed_mods: vec![],
         start_parameters: Some("".to_string()),
         is_active: false,
+        ..Default::default()
     };
 
     add_profile(config_path, &profile)?;
@@ -214,6 +260,7 @@ pub fn delete_profile(config_path: &PathBuf) -> Result<(), ConfigError> {
 
     let profile_names: Vec<String> = profiles.iter().map(|p| p.name.clone()).collect();
 
+    require_interactive("the profile removal by editing the configuration file")?;
     let ans: Result<String, InquireError> =
         Select::new("Select a profile to remove", profile_names).prompt();
 
@@ -253,6 +300,7 @@ pub fn switch_profile(config_path: &PathBuf) -> Result<(), ConfigError> {
 
     let profile_names: Vec<String> = profiles.iter().map(|p| p.name.clone()).collect();
 
+    require_interactive("the profile with DAYZ_TOOL_PROFILE")?;
     let ans: Result<String, InquireError> =
         Select::new("Select a profile to switch to", profile_names).prompt();
 
@@ -270,3 +318,519 @@ pub fn switch_profile(config_path: &PathBuf) -> Result<(), ConfigError> {
 
     Ok(())
 }
+
+/// Imports profiles from a JSON file into the configuration.
+///
+/// The file may either contain a single profile or a whole configuration with a
+/// `profiles` array. Every profile is strictly validated before anything is imported,
+/// so a single invalid profile aborts the import with a detailed error. Imported
+/// profiles are never set as active.
+///
+/// # Arguments
+/// * `config_path` - Path to the configuration file
+/// * `import_path` - Path to the JSON file to import
+///
+/// # Returns
+/// * `Ok(())` if all profiles were imported
+/// * `Err(ConfigError)` if the file could not be read or a profile is invalid
+pub fn import_profiles(config_path: &PathBuf, import_path: &PathBuf) -> Result<(), ConfigError> {
+    debug!("Import profiles from {}", import_path.display());
+    let file = File::open(import_path).map_err(|_| ConfigError::OpenFileError)?;
+    let value: Value = serde_json::from_reader(file).map_err(|_| ConfigError::ParseError)?;
+
+    let profiles = if value.get("profiles").is_some() {
+        validate_config(&value)?.profiles
+    } else {
+        vec![validate_profile(&value, 0)?]
+    };
+
+    let existing_names: Vec<String> = get_profiles(config_path)?
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+
+    for mut profile in profiles {
+        if existing_names.contains(&profile.name) {
+            error!(
+                "A profile named '{}' already exists, skipping",
+                profile.name
+            );
+            continue;
+        }
+
+        profile.is_active = false;
+        add_profile(config_path, &profile)?;
+        println!(
+            "{} {}",
+            THEME.label("Imported profile:"),
+            THEME.value(&profile.name)
+        );
+    }
+
+    Ok(())
+}
+
+/// Restores a single profile from a configuration backup.
+///
+/// The backup is parsed as JSON or, failing that, as TOML, so both `config.json.bak`
+/// and `config.toml.bak` are accepted. Only the profile named `name` is added to the
+/// current configuration; all other profiles stay untouched. If a profile with that
+/// name already exists, the restored one is renamed to `<name> (restored)`, with a
+/// counter appended on further collisions. The restored profile is never set as active.
+///
+/// # Arguments
+/// * `config_path` - Path to the configuration file
+/// * `backup_path` - Path to the configuration backup
+/// * `name` - Name of the profile to restore
+///
+/// # Returns
+/// * `Ok(String)` with the name the profile was restored as
+/// * `Err(ConfigError)` if the backup could not be read or contains no such profile
+pub fn restore_profile(
+    config_path: &PathBuf,
+    backup_path: &PathBuf,
+    name: &str,
+) -> Result<String, ConfigError> {
+    debug!("Restore profile '{}' from {}", name, backup_path.display());
+    let content = read_to_string(backup_path).map_err(|_| ConfigError::OpenFileError)?;
+    let backup: Root = serde_json::from_str(&content)
+        .or_else(|_| toml::from_str(&content))
+        .map_err(|_| ConfigError::ParseError)?;
+
+    let mut profile = backup
+        .profiles
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or(ConfigError::ProfileNotFoundError)?;
+
+    let existing_names: Vec<String> = if config_path.exists() {
+        get_profiles(config_path)?
+            .into_iter()
+            .map(|p| p.name)
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut restored_name = name.to_string();
+    let mut counter = 1;
+    while existing_names.contains(&restored_name) {
+        restored_name = if counter == 1 {
+            format!("{} (restored)", name)
+        } else {
+            format!("{} (restored {})", name, counter)
+        };
+        counter += 1;
+    }
+
+    profile.name = restored_name.clone();
+    profile.is_active = false;
+    add_profile(config_path, &profile)?;
+
+    Ok(restored_name)
+}
+
+/// Rewrites the configuration file with canonical formatting.
+///
+/// The configuration is read and written back through `write_config_file`, which
+/// pretty-prints it with the fields in their canonical order. Leading and trailing
+/// whitespace is trimmed from profile names, paths and mod names, as left behind by
+/// manual edits.
+///
+/// # Arguments
+/// * `config_path` - Path to the configuration file
+///
+/// # Returns
+/// * `Ok(())` if the configuration was rewritten
+/// * `Err(ConfigError)` if the file could not be read, parsed or written
+pub fn format_config(config_path: &PathBuf) -> Result<(), ConfigError> {
+    debug!("Formatting {}", config_path.display());
+    let mut config = read_config_file(config_path)?;
+
+    for profile in &mut config.profiles {
+        normalize_profile(profile);
+    }
+
+    write_config_file(config_path, &config)
+}
+
+/// Returns a single value from the configuration file.
+///
+/// The key has the form `<profile>.<field>`, where `<profile>` is a profile name or
+/// `active` for the profile commands work on, and `<field>` is a profile field in
+/// snake_case (e.g. `active.workdir_path`). Strings are returned as is, unset values as
+/// an empty string and lists or maps as compact JSON.
+///
+/// # Arguments
+/// * `config_path` - Path to the configuration file
+/// * `key` - The `<profile>.<field>` key to look up
+///
+/// # Returns
+/// * `Ok(String)` with the value
+/// * `Err(ConfigError)` if the key is malformed, or the profile or field does not exist
+pub fn get_config_value(config_path: &PathBuf, key: &str) -> Result<String, ConfigError> {
+    let (profile_name, field) = key
+        .rsplit_once('.')
+        .filter(|(profile_name, field)| !profile_name.is_empty() && !field.is_empty())
+        .ok_or_else(|| ConfigError::InvalidConfigKey(key.to_string()))?;
+
+    let profile = if profile_name == "active" {
+        get_profile(config_path)?
+    } else {
+        get_profiles(config_path)?
+            .into_iter()
+            .find(|profile| profile.name == profile_name)
+            .ok_or(ConfigError::ProfileNotFoundError)?
+    };
+
+    let value = match field {
+        "name" => json!(profile.name),
+        "workdir_path" => json!(profile.workdir_path),
+        "workshop_path" => json!(profile.workshop_path),
+        "start_parameters" => json!(profile.start_parameters),
+        "installed_mods" => json!(profile.installed_mods),
+        "is_active" => json!(profile.is_active),
+        "log_to_workdir" => json!(profile.log_to_workdir),
+        "mod_sources" => json!(profile.mod_sources),
+        "server_mods" => json!(profile.server_mods),
+        "load_order" => json!(profile.load_order),
+        "steamcmd" => json!(profile.steamcmd),
+        "manual_ce_mods" => json!(profile.manual_ce_mods),
+        "branch" => json!(profile.branch),
+        "startup_template" => json!(profile.startup_template),
+        "ce_dir" => json!(profile.ce_dir),
+        "script_dir" => json!(profile.script_dir),
+        "mod_tags" => json!(profile.mod_tags),
+        "mod_dependencies" => json!(profile.mod_dependencies),
+        "merge_types" => json!(profile.merge_types),
+        "environment" => json!(profile.environment),
+        _ => return Err(ConfigError::UnknownField(field.to_string())),
+    };
+
+    Ok(match value {
+        Value::String(value) => value,
+        Value::Null => String::new(),
+        value => value.to_string(),
+    })
+}
+
+/// Prompts for the SteamCMD password and stores it in the OS keyring.
+///
+/// With `from_stdin` the password is read from the first line of stdin instead.
+/// See `set_steamcmd_password`.
+pub fn store_steamcmd_password(config_path: &PathBuf, from_stdin: bool) -> Result<(), ConfigError> {
+    let login = get_profile(config_path)?
+        .steamcmd
+        .ok_or(ConfigError::MissingSteamCmdConfig)?
+        .login;
+
+    let password = if from_stdin {
+        let mut line = String::new();
+        std::io::stdin()
+            .read_line(&mut line)
+            .map_err(|_| ConfigError::NoPassword)?;
+        line.trim_end_matches(['\r', '\n']).to_string()
+    } else {
+        require_interactive("the password with --password-stdin")?;
+        Password::new(&format!("SteamCMD password for {}:", login))
+            .prompt()
+            .map_err(|_| ConfigError::NoPassword)?
+    };
+
+    set_steamcmd_password(config_path, &SystemKeyring, &password)
+}
+
+/// Stores the SteamCMD password of the profile commands work on in a credential store.
+///
+/// The password is stored for the `login` of the profile's `steamcmd` settings under
+/// `KEYRING_SERVICE`, and the settings get a reference to the keyring. Only the reference
+/// is written to the configuration file, `mod fetch` reads the password at runtime.
+///
+/// # Arguments
+/// * `config_path` - Path to the configuration file
+/// * `store` - The credential store to save the password in
+/// * `password` - The SteamCMD password
+///
+/// # Returns
+/// * `Ok(())` if the password was stored
+/// * `Err(ConfigError)` if the profile has no SteamCMD settings or the store failed
+pub fn set_steamcmd_password(
+    config_path: &PathBuf,
+    store: &dyn CredentialStore,
+    password: &str,
+) -> Result<(), ConfigError> {
+    if password.is_empty() {
+        return Err(ConfigError::NoPassword);
+    }
+
+    let name = get_profile(config_path)?.name;
+    let mut config = read_config_file(config_path)?;
+    let steamcmd = config
+        .profiles
+        .iter_mut()
+        .find(|profile| profile.name == name)
+        .ok_or(ConfigError::ProfileNotFoundError)?
+        .steamcmd
+        .as_mut()
+        .ok_or(ConfigError::MissingSteamCmdConfig)?;
+
+    store.set_password(KEYRING_SERVICE, &steamcmd.login, password)?;
+    steamcmd.keyring = Some(KEYRING_SERVICE.to_string());
+
+    write_config_file(config_path, &config)
+}
+
+/// Trims the names, paths and mod names of a profile, including the dependency entries.
+fn normalize_profile(profile: &mut Profile) {
+    let trim = |value: &mut String| *value = value.trim().to_string();
+    let trim_option = |value: &mut Option<String>| {
+        if let Some(inner) = value {
+            *inner = inner.trim().to_string();
+        }
+    };
+
+    trim(&mut profile.name);
+    trim(&mut profile.workdir_path);
+    trim(&mut profile.workshop_path);
+    trim_option(&mut profile.start_parameters);
+    trim_option(&mut profile.ce_dir);
+    trim_option(&mut profile.script_dir);
+    trim_option(&mut profile.branch);
+    if let Some(steamcmd) = &mut profile.steamcmd {
+        trim(&mut steamcmd.path);
+    }
+
+    for entry in &mut profile.installed_mods {
+        if let Value::String(name) = entry {
+            trim(name);
+        }
+    }
+    profile
+        .server_mods
+        .iter_mut()
+        .chain(profile.load_order.iter_mut())
+        .chain(profile.manual_ce_mods.iter_mut())
+        .for_each(trim);
+    profile.mod_sources = std::mem::take(&mut profile.mod_sources)
+        .into_iter()
+        .map(|(name, source)| (name.trim().to_string(), source.trim().to_string()))
+        .collect();
+    profile.mod_tags = std::mem::take(&mut profile.mod_tags)
+        .into_iter()
+        .map(|(name, tags)| (name.trim().to_string(), normalize_tags(&tags)))
+        .collect();
+    profile.mod_dependencies = std::mem::take(&mut profile.mod_dependencies)
+        .into_iter()
+        .map(|(name, mut dependencies)| {
+            dependencies.iter_mut().for_each(trim);
+            (name.trim().to_string(), dependencies)
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Root;
+    use std::fs;
+
+    #[test]
+    fn test_get_config_value() {
+        let dir = std::env::temp_dir().join("dayz_tool_get_config_value");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.json");
+
+        write_config_file(
+            &config_path,
+            &Root {
+                profiles: vec![
+                    Profile {
+                        name: "Chernarus".to_string(),
+                        workdir_path: "/srv/chernarus".to_string(),
+                        is_active: true,
+                        ..Default::default()
+                    },
+                    Profile {
+                        name: "dev.local".to_string(),
+                        workdir_path: "/srv/dev".to_string(),
+                        server_mods: vec!["@Admin".to_string()],
+                        ..Default::default()
+                    },
+                ],
+            },
+        )
+        .unwrap();
+
+        let get = |key: &str| get_config_value(&config_path, key);
+        assert_eq!(get("active.workdir_path").unwrap(), "/srv/chernarus");
+        assert_eq!(get("dev.local.workdir_path").unwrap(), "/srv/dev");
+        assert_eq!(get("dev.local.server_mods").unwrap(), r#"["@Admin"]"#);
+        assert_eq!(get("Chernarus.ce_dir").unwrap(), "");
+        assert_eq!(
+            get("active.workdir"),
+            Err(ConfigError::UnknownField("workdir".to_string()))
+        );
+        assert_eq!(
+            get("Namalsk.workdir_path"),
+            Err(ConfigError::ProfileNotFoundError)
+        );
+        assert_eq!(
+            get("workdir_path"),
+            Err(ConfigError::InvalidConfigKey("workdir_path".to_string()))
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_restore_profile() {
+        let dir = std::env::temp_dir().join("dayz_tool_restore_profile");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.json");
+        let backup_path = dir.join("config.json.bak");
+
+        let profile = |name: &str, workdir: &str, active: bool| Profile {
+            name: name.to_string(),
+            workdir_path: workdir.to_string(),
+            workshop_path: "/srv/!Workshop".to_string(),
+            is_active: active,
+            ..Default::default()
+        };
+        write_config_file(
+            &backup_path,
+            &Root {
+                profiles: vec![
+                    profile("Chernarus", "/old/chernarus", true),
+                    profile("Livonia", "/old/livonia", false),
+                ],
+            },
+        )
+        .unwrap();
+        write_config_file(
+            &config_path,
+            &Root {
+                profiles: vec![
+                    profile("Chernarus", "/srv/chernarus", true),
+                    profile("Sakhal", "/srv/sakhal", false),
+                ],
+            },
+        )
+        .unwrap();
+
+        let restored = restore_profile(&config_path, &backup_path, "Livonia").unwrap();
+        assert_eq!(restored, "Livonia");
+        let restored = restore_profile(&config_path, &backup_path, "Chernarus").unwrap();
+        assert_eq!(restored, "Chernarus (restored)");
+        let restored = restore_profile(&config_path, &backup_path, "Chernarus").unwrap();
+        assert_eq!(restored, "Chernarus (restored 2)");
+        assert_eq!(
+            restore_profile(&config_path, &backup_path, "Namalsk"),
+            Err(ConfigError::ProfileNotFoundError)
+        );
+
+        let profiles = get_profiles(&config_path).unwrap();
+        let summary: Vec<(&str, &str, bool)> = profiles
+            .iter()
+            .map(|p| (p.name.as_str(), p.workdir_path.as_str(), p.is_active))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Chernarus", "/srv/chernarus", true),
+                ("Sakhal", "/srv/sakhal", false),
+                ("Livonia", "/old/livonia", false),
+                ("Chernarus (restored)", "/old/chernarus", false),
+                ("Chernarus (restored 2)", "/old/chernarus", false),
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_format_config() {
+        let dir = std::env::temp_dir().join("dayz_tool_format_config");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.json");
+
+        fs::write(
+            &config_path,
+            r#"{"profiles":[{ "isActive":true,"installedMods":[" @CF ","@Admin" ],
+  "name":"  Chernarus ","workshopPath":"/srv/!Workshop  ",
+      "modSources":{"@Admin ":"@Admin Tools"},"workdirPath":" /srv/dayz","startParameters":null,
+"loadOrder":["@CF ", "@Admin"],"modDependencies":{" @Admin":[" @CF "]}}]}"#,
+        )
+        .unwrap();
+
+        format_config(&config_path).unwrap();
+        let formatted = fs::read_to_string(&config_path).unwrap();
+        assert_eq!(
+            formatted,
+            r#"{
+  "profiles": [
+    {
+      "name": "Chernarus",
+      "workdirPath": "/srv/dayz",
+      "workshopPath": "/srv/!Workshop",
+      "startParameters": null,
+      "installedMods": [
+        "@CF",
+        "@Admin"
+      ],
+      "isActive": true,
+      "logToWorkdir": false,
+      "modSources": {
+        "@Admin": "@Admin Tools"
+      },
+      "loadOrder": [
+        "@CF",
+        "@Admin"
+      ],
+      "modDependencies": {
+        "@Admin": [
+          "@CF"
+        ]
+      }
+    }
+  ]
+}"#
+        );
+
+        format_config(&config_path).unwrap();
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), formatted);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_current_profile() {
+        let dir = std::env::temp_dir().join("dayz_tool_current_profile");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join("config.json");
+
+        let profile = |name: &str, is_active: bool| Profile {
+            name: name.to_string(),
+            is_active,
+            ..Default::default()
+        };
+        let write_config = |profiles: Vec<Profile>| {
+            let json = serde_json::to_string(&Root { profiles }).unwrap();
+            fs::write(&config_path, json).unwrap();
+        };
+
+        write_config(vec![profile("Chernarus", false), profile("Livonia", true)]);
+        assert_eq!(current_profile(&config_path), Ok("Livonia".to_string()));
+
+        write_config(vec![profile("Chernarus", false)]);
+        assert_eq!(
+            current_profile(&config_path),
+            Err(ConfigError::NoActiveProfile)
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,10 +1,21 @@
-use std::{env::consts::OS, fs::write};
+use std::{
+    collections::BTreeMap,
+    env::consts::OS,
+    fs::{create_dir_all, read_to_string, write},
+    path::{Path, PathBuf},
+};
 
 use chrono::Local;
 use inquire::{Confirm, MultiSelect, Text};
-use log::{debug, error};
+use log::{debug, error, info, warn};
+use regex::Regex;
+use sha2::{Digest, Sha256};
 
-use crate::{ConfigError, Profile};
+use crate::{
+    is_dry_run, require_interactive,
+    utils::{get_config_path, get_map_name, get_profile, save_profile},
+    ConfigError, Profile, StartupScript, StartupTemplate,
+};
 
 /// Generates a startup script for the DayZ server based on the provided profile.
 ///
@@ -12,12 +23,22 @@ use crate::{ConfigError, Profile};
 /// with configurable server parameters. It allows users to either use a predefined
 /// template or customize their own parameter selection.
 ///
+/// If the profile stores a `startup_template`, it is used without asking. Otherwise the
+/// choice may be saved to the profile, so later runs only ask for the port. With a
+/// `port` and a stored template nothing is asked.
+///
 /// # Arguments
 /// * `profile` - A Profile struct containing server configuration details
+/// * `port` - The server port, asked for if `None`
+/// * `output_dir` - The directory to write the script to, overriding the profile's `script_dir`
 ///
 /// # Returns
-/// * `Result<(), ConfigError>` - Ok(()) on success, or ConfigError on failure
-pub fn generate_startup_script(profile: Profile) -> Result<(), ConfigError> {
+/// * `Result<StartupScript, ConfigError>` - The written script on success, or ConfigError on failure
+pub fn generate_startup_script(
+    profile: Profile,
+    port: Option<u16>,
+    output_dir: Option<&Path>,
+) -> Result<StartupScript, ConfigError> {
     debug!("Starting generating start script");
 
     let available_parameters: Vec<String> = vec![
@@ -37,27 +58,37 @@ pub fn generate_startup_script(profile: Profile) -> Result<(), ConfigError> {
 
     let mut final_parameters = vec![];
 
-    let port = Text::new("Server Port:")
-        .with_default("2302")
-        .with_help_message("The port of your server")
-        .prompt()
-        .expect("Failed to get input");
+    let port = match port {
+        Some(port) => port.to_string(),
+        None => {
+            require_interactive("--port <port>")?;
+            Text::new("Server Port:")
+                .with_default("2302")
+                .with_help_message("The port of your server")
+                .prompt()
+                .expect("Failed to get input")
+        }
+    };
+
+    if let Some(parameters) = stored_template_parameters(&profile) {
+        info!(
+            "Using the startup template stored in profile '{}'",
+            profile.name
+        );
+        return write_startup_script(&profile, &port, &parameters, output_dir);
+    }
+
+    require_interactive("the parameters as `startupTemplate` in the profile")?;
 
     let use_template = Confirm::new("Use template?")
         .with_default(true)
         .with_help_message("Use a template for the startup script")
         .prompt();
 
-    match use_template {
+    let template = match use_template {
         Ok(true) => {
-            let template_parameters = vec![
-                "-BEpath=battleye".to_string(),
-                "-doLogs".to_string(),
-                "-adminLog".to_string(),
-                "-netLog".to_string(),
-                "-freezeCheck".to_string(),
-            ];
-            final_parameters.extend(template_parameters);
+            final_parameters.extend(builtin_template_parameters());
+            Some(StartupTemplate::Builtin)
         }
         Ok(false) => {
             let selected_parameters = MultiSelect::new("Select parameters", available_parameters)
@@ -69,25 +100,408 @@ pub fn generate_startup_script(profile: Profile) -> Result<(), ConfigError> {
                     debug!("Selected parameters: {:?}", parameters);
 
                     for parameter in parameters {
-                        if parameter.ends_with('=') {
+                        if parameter == "-mission=" {
+                            let mut prompt = Text::new("Enter value for -mission=")
+                                .with_help_message("The mission folder in mpmissions");
+                            let default = default_mission_value(&profile.workdir_path);
+                            if let Some(default) = default.as_deref() {
+                                prompt = prompt.with_default(default);
+                            }
+                            let value = prompt.prompt().expect("Failed to get input");
+                            let parameter = format!("{}{}", parameter, value);
+                            check_parameter_path(&profile.workdir_path, &parameter);
+                            final_parameters.push(parameter);
+                        } else if parameter.ends_with('=') {
                             let value = Text::new(&format!("Enter value for {}", parameter))
                                 .with_help_message("Enter the value for this parameter")
                                 .prompt()
                                 .expect("Failed to get input");
-                            final_parameters.push(format!("{}{}", parameter, value));
+                            let parameter = format!("{}{}", parameter, value);
+                            if let Some(missing) =
+                                check_parameter_path(&profile.workdir_path, &parameter)
+                            {
+                                offer_to_create(&missing);
+                            }
+                            final_parameters.push(parameter);
                         } else {
                             final_parameters.push(parameter);
                         }
                     }
 
                     debug!("Final parameters: {:?}", final_parameters);
+                    Some(StartupTemplate::Custom {
+                        parameters: final_parameters.clone(),
+                    })
+                }
+                Err(_) => {
+                    error!("Failed to select parameters");
+                    None
+                }
+            }
+        }
+        Err(_) => {
+            error!("Failed confirm use template");
+            None
+        }
+    };
+
+    if let Some(template) = template {
+        let remember = Confirm::new("Save this choice as the profile default?")
+            .with_default(false)
+            .with_help_message("Later runs will use these parameters without asking")
+            .prompt()
+            .unwrap_or(false);
+
+        if remember {
+            let mut stored_profile = get_profile(&get_config_path()?)?;
+            stored_profile.startup_template = Some(template);
+            save_profile(&stored_profile)?;
+        }
+    }
+
+    write_startup_script(&profile, &port, &final_parameters, output_dir)
+}
+
+/// The parameters of the built-in startup template.
+fn builtin_template_parameters() -> Vec<String> {
+    vec![
+        "-BEpath=battleye".to_string(),
+        "-doLogs".to_string(),
+        "-adminLog".to_string(),
+        "-netLog".to_string(),
+        "-freezeCheck".to_string(),
+    ]
+}
+
+/// Returns the path the startup script of a profile is written to.
+///
+/// The script is placed in `output_dir` if given, else in the profile's `script_dir`,
+/// else in the workdir.
+fn script_path(profile: &Profile, output_dir: Option<&Path>) -> PathBuf {
+    let filename = if OS == "windows" {
+        "start_server.bat"
+    } else {
+        "start_server.sh"
+    };
+    output_dir
+        .or(profile.script_dir.as_deref().map(Path::new))
+        .unwrap_or(Path::new(&profile.workdir_path))
+        .join(filename)
+}
+
+/// Returns the hex encoded SHA256 hash of a startup script's content.
+pub fn script_hash(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Returns the path of the `.sha256` sidecar file of a startup script.
+fn hash_sidecar_path(script_path: &Path) -> PathBuf {
+    let mut path = script_path.as_os_str().to_owned();
+    path.push(".sha256");
+    PathBuf::from(path)
+}
+
+/// Writes the hash of a startup script to a `.sha256` file next to it.
+///
+/// The file uses the `sha256sum` format, so it can also be checked with
+/// `sha256sum -c`.
+///
+/// # Arguments
+/// * `script` - The written startup script
+///
+/// # Returns
+/// * `Result<PathBuf, ConfigError>` - The path of the sidecar file on success, or ConfigError on failure
+pub fn write_script_hash(script: &StartupScript) -> Result<PathBuf, ConfigError> {
+    let sidecar_path = hash_sidecar_path(&script.path);
+    let filename = script
+        .path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    if is_dry_run() {
+        info!("[dry-run] Would write {}", sidecar_path.display());
+        return Ok(sidecar_path);
+    }
+
+    write(
+        &sidecar_path,
+        format!("{}  {}\n", script_hash(&script.content), filename),
+    )
+    .map_err(|_| ConfigError::WriteFileError)?;
+
+    Ok(sidecar_path)
+}
+
+/// Checks whether a profile's startup script still matches its recorded hash.
+///
+/// # Arguments
+/// * `profile` - The profile the script was generated for
+/// * `output_dir` - The directory the script was written to, if not the profile's default
+///
+/// # Returns
+/// * `Result<bool, ConfigError>` - Whether the script is unchanged, or ConfigError if the
+///   script or its `.sha256` file cannot be read
+pub fn verify_startup_script(
+    profile: &Profile,
+    output_dir: Option<&Path>,
+) -> Result<bool, ConfigError> {
+    let path = script_path(profile, output_dir);
+    let content = read_to_string(&path)
+        .map_err(|_| ConfigError::ScriptNotFound(path.display().to_string()))?;
+    let sidecar_path = hash_sidecar_path(&path);
+    let recorded = read_to_string(&sidecar_path)
+        .map_err(|_| ConfigError::MissingScriptHash(sidecar_path.display().to_string()))?;
+    let recorded = recorded
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| ConfigError::MissingScriptHash(sidecar_path.display().to_string()))?;
+
+    Ok(recorded.eq_ignore_ascii_case(&script_hash(&content)))
+}
+
+/// Returns the `-mission=` value for the map detected from `serverDZ.cfg`.
+///
+/// # Arguments
+/// * `workdir` - The server's working directory
+///
+/// # Returns
+/// * `Option<String>` - The mission path, or `None` if no map could be detected
+pub fn default_mission_value(workdir: &str) -> Option<String> {
+    get_map_name(workdir)
+        .ok()
+        .map(|map_name| format!("./mpmissions/{}", map_name))
+}
+
+/// Parameters whose values are paths, relative to the workdir unless absolute.
+const PATH_PARAMETERS: [&str; 4] = ["-bepath=", "-profiles=", "-storage=", "-mission="];
+
+/// Checks that the path of a path-valued parameter exists and warns if it does not.
+///
+/// The value of `-BEpath=`, `-profiles=` and `-storage=` is resolved against the workdir.
+/// For `-mission=`, the folder of that name in `mpmissions` is checked. Other parameters
+/// are ignored.
+///
+/// # Arguments
+/// * `workdir` - The server's working directory
+/// * `parameter` - The parameter including its value (e.g. `-BEpath=battleye`)
+///
+/// # Returns
+/// * `Option<PathBuf>` - The missing path, or `None` if it exists or is not checked
+fn check_parameter_path(workdir: &str, parameter: &str) -> Option<PathBuf> {
+    let key = parameter_key(parameter);
+    if !PATH_PARAMETERS.contains(&key.as_str()) {
+        return None;
+    }
+    let value = parameter[key.len()..].trim().trim_matches('"');
+    if value.is_empty() {
+        return None;
+    }
+
+    if key == "-mission=" {
+        if mission_folder_exists(workdir, value) {
+            return None;
+        }
+        warn!(
+            "Mission folder '{}' does not exist in {}",
+            value,
+            Path::new(workdir).join("mpmissions").display()
+        );
+        return Some(Path::new(workdir).join(value));
+    }
+
+    let path = Path::new(workdir).join(value);
+    if path.exists() {
+        return None;
+    }
+    warn!(
+        "The path of {} does not exist: {}",
+        &parameter[..key.len()],
+        path.display()
+    );
+    Some(path)
+}
+
+/// Asks whether a missing parameter path should be created and creates it.
+fn offer_to_create(path: &Path) {
+    let create = Confirm::new(&format!("Create {}?", path.display()))
+        .with_default(false)
+        .prompt()
+        .unwrap_or(false);
+    if !create {
+        return;
+    }
+
+    if is_dry_run() {
+        info!("[dry-run] Would create {}", path.display());
+    } else if let Err(e) = create_dir_all(path) {
+        error!("Failed to create {}: {}", path.display(), e);
+    } else {
+        info!("Created {}", path.display());
+    }
+}
+
+/// Checks whether a `-mission=` value names an existing folder in `mpmissions`.
+fn mission_folder_exists(workdir: &str, value: &str) -> bool {
+    Path::new(value.trim_end_matches(['/', '\\']))
+        .file_name()
+        .is_some_and(|name| Path::new(workdir).join("mpmissions").join(name).is_dir())
+}
+
+/// Returns the parameters of the startup template stored in the profile.
+///
+/// # Arguments
+/// * `profile` - The profile to read the template preference from
+///
+/// # Returns
+/// * `Option<Vec<String>>` - The parameters, or `None` if the profile stores no preference
+pub fn stored_template_parameters(profile: &Profile) -> Option<Vec<String>> {
+    match profile.startup_template.as_ref()? {
+        StartupTemplate::Builtin => Some(builtin_template_parameters()),
+        StartupTemplate::Custom { parameters } => Some(parameters.clone()),
+    }
+}
+
+/// Parameters the script template sets on its own.
+const TEMPLATE_PARAMETERS: [&str; 3] = ["-port=", "-config=", "-profiles="];
+
+/// Parameters whose values are `;`-separated lists that may be merged.
+const LIST_PARAMETERS: [&str; 2] = ["-mod=", "-servermod="];
+
+/// Returns the lowercase name of a parameter, including the '=' of valued parameters.
+fn parameter_key(parameter: &str) -> String {
+    match parameter.find('=') {
+        Some(index) => parameter[..=index].to_lowercase(),
+        None => parameter.to_lowercase(),
+    }
+}
+
+/// Removes duplicate and conflicting parameters before they are written.
+///
+/// Parameters are compared by their name, ignoring case. Repeated `-mod=` and
+/// `-serverMod=` entries are collapsed into one, merging their mod lists. For other
+/// repeated parameters, the first occurrence is kept. Parameters the script template
+/// already sets (`-port=`, `-config=`, `-profiles=`) are dropped. Every change is
+/// logged as a warning.
+///
+/// # Arguments
+/// * `parameters` - The selected server parameters
+///
+/// # Returns
+/// * `Vec<String>` - The parameters without duplicates, in their original order
+pub fn validate_parameters(parameters: &[String]) -> Vec<String> {
+    let mut validated: Vec<String> = Vec::new();
+
+    for parameter in parameters {
+        let key = parameter_key(parameter);
+
+        if TEMPLATE_PARAMETERS.contains(&key.as_str()) {
+            warn!("Dropping {}, it is already set by the template", parameter);
+            continue;
+        }
+
+        let existing = validated.iter_mut().find(|p| parameter_key(p) == key);
+        match existing {
+            None => validated.push(parameter.clone()),
+            Some(existing) if LIST_PARAMETERS.contains(&key.as_str()) => {
+                let mut entries: Vec<String> = existing[key.len()..]
+                    .split(';')
+                    .filter(|e| !e.is_empty())
+                    .map(String::from)
+                    .collect();
+                for entry in parameter[key.len()..].split(';') {
+                    if !entry.is_empty() && !entries.iter().any(|e| e == entry) {
+                        entries.push(entry.to_string());
+                    }
                 }
-                Err(_) => error!("Failed to select parameters"),
+                warn!(
+                    "Merging duplicate {} into one parameter",
+                    &existing[..key.len()]
+                );
+                *existing = format!("{}{}", &existing[..key.len()], entries.join(";"));
+            }
+            Some(existing) if existing == parameter => {
+                warn!("Dropping duplicate {}", parameter);
+            }
+            Some(existing) => {
+                warn!(
+                    "{} conflicts with {}, keeping the first one",
+                    parameter, existing
+                );
             }
         }
-        Err(_) => error!("Failed confirm use template"),
     }
 
+    validated
+}
+
+/// Renders the profile's environment variables as the `{environment}` block of a script.
+///
+/// Each variable becomes an `export NAME="value"` line in `.sh` scripts, so references
+/// like `$LD_LIBRARY_PATH` in a value are still expanded by the shell. In `.bat` scripts
+/// it becomes a `set "NAME=value"` line with `%` escaped as `%%`, so cmd takes the value
+/// literally; a value containing `"` can not be quoted there and is skipped with a
+/// warning. Names that are not valid variable names are skipped with a warning as well.
+/// Returns an empty string without variables.
+fn render_environment(environment: &BTreeMap<String, String>, os: &str) -> String {
+    let valid_name = Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap();
+    let mut lines = Vec::new();
+    for (name, value) in environment {
+        if !valid_name.is_match(name) {
+            warn!("Skipping invalid environment variable name '{}'", name);
+            continue;
+        }
+        if os == "windows" && value.contains('"') {
+            warn!(
+                "Skipping environment variable '{}', cmd can not quote a value containing '\"'",
+                name
+            );
+            continue;
+        }
+        lines.push(match os {
+            "windows" => format!("set \"{}={}\"", name, value.replace('%', "%%")),
+            _ => format!(
+                "export {}=\"{}\"",
+                name,
+                value.replace('\\', "\\\\").replace('"', "\\\"")
+            ),
+        });
+    }
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    let header = match os {
+        "windows" => "rem Environment",
+        _ => "# Environment",
+    };
+    format!("{}\n{}\n\n", header, lines.join("\n"))
+}
+
+/// Renders the startup script template and writes it into the profile's workdir.
+///
+/// The parameters are checked with `validate_parameters` first. The profile's
+/// `environment` is set at the top of the script, see `render_environment`. The script
+/// is written to `output_dir` if given, or to the profile's `script_dir` if set, instead
+/// of the workdir. The script always refers to the workdir, wherever it is written.
+///
+/// # Arguments
+/// * `profile` - The profile providing the server name and workdir
+/// * `port` - The server port
+/// * `parameters` - The additional server parameters
+/// * `output_dir` - The directory to write the script to, if not the profile's default
+///
+/// # Returns
+/// * `Result<StartupScript, ConfigError>` - The target path and content of the script
+pub fn write_startup_script(
+    profile: &Profile,
+    port: &str,
+    parameters: &[String],
+    output_dir: Option<&Path>,
+) -> Result<StartupScript, ConfigError> {
+    let parameters = validate_parameters(parameters);
     let os = OS;
     let template_content = match os {
         "windows" => include_str!("../../templates/start_server.bat.template"),
@@ -96,27 +510,313 @@ pub fn generate_startup_script(profile: Profile) -> Result<(), ConfigError> {
 
     let generation_date = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
     let final_content = template_content
+        .replace(
+            "{environment}",
+            &render_environment(&profile.environment, os),
+        )
         .replace("{server_name}", &profile.name)
         .replace("{server_path}", &profile.workdir_path)
-        .replace("{server_port}", &port)
+        .replace("{server_port}", port)
         .replace("{generation_date}", &generation_date)
-        .replace("{additional_parameters}", &final_parameters.join(" "));
+        .replace("{additional_parameters}", &parameters.join(" "));
 
-    let filename = if os == "windows" {
-        "start_server.bat"
-    } else {
-        "start_server.sh"
+    let script = StartupScript {
+        path: script_path(profile, output_dir),
+        content: final_content,
     };
-    let target_path = format!("{}/{}", profile.workdir_path, filename);
+    info!(
+        "SHA256 of {}: {}",
+        script.path.display(),
+        script_hash(&script.content)
+    );
+
+    if is_dry_run() {
+        info!("[dry-run] Would write {}", script.path.display());
+        return Ok(script);
+    }
 
-    write(&target_path, final_content).unwrap();
+    if let Some(target_dir) = script.path.parent() {
+        create_dir_all(target_dir).map_err(|_| ConfigError::CreateFileError)?;
+    }
+    write(&script.path, &script.content).map_err(|_| ConfigError::WriteFileError)?;
 
     if os != "windows" {
         use std::os::unix::fs::PermissionsExt;
-        let mut perms = std::fs::metadata(&target_path).unwrap().permissions();
+        let mut perms = std::fs::metadata(&script.path).unwrap().permissions();
         perms.set_mode(0o755);
-        std::fs::set_permissions(&target_path, perms).unwrap();
+        std::fs::set_permissions(&script.path, perms).unwrap();
     }
 
-    Ok(())
+    Ok(script)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_startup_script() {
+        let workdir = std::env::temp_dir().join("dayz_tool_startup_script");
+        let _ = std::fs::remove_dir_all(&workdir);
+        std::fs::create_dir_all(&workdir).unwrap();
+
+        let profile = Profile {
+            name: "Test Server".to_string(),
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+        let parameters = vec!["-doLogs".to_string(), "-BEpath=battleye".to_string()];
+
+        let script = write_startup_script(&profile, "2402", &parameters, None).unwrap();
+
+        let filename = if OS == "windows" {
+            "start_server.bat"
+        } else {
+            "start_server.sh"
+        };
+        assert_eq!(script.path, workdir.join(filename));
+        assert!(script.content.contains("-doLogs -BEpath=battleye"));
+        assert!(script.content.contains("2402"));
+        assert_eq!(
+            std::fs::read_to_string(&script.path).unwrap(),
+            script.content
+        );
+
+        std::fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_write_startup_script_with_environment() {
+        let workdir = std::env::temp_dir().join("dayz_tool_startup_environment");
+        let _ = std::fs::remove_dir_all(&workdir);
+        std::fs::create_dir_all(&workdir).unwrap();
+
+        let profile = Profile {
+            name: "Test Server".to_string(),
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            environment: BTreeMap::from([
+                ("DAYZ_BE".to_string(), "battleye".to_string()),
+                (
+                    "LD_LIBRARY_PATH".to_string(),
+                    "/opt/dayz/lib:$LD_LIBRARY_PATH".to_string(),
+                ),
+                ("NOT VALID".to_string(), "ignored".to_string()),
+            ]),
+            ..Default::default()
+        };
+
+        let script = write_startup_script(&profile, "2302", &[], None).unwrap();
+
+        if OS == "windows" {
+            assert!(script
+                .content
+                .contains("rem Environment\nset \"DAYZ_BE=battleye\"\n"));
+        } else {
+            assert!(script.content.contains(
+                "# Environment\n\
+                 export DAYZ_BE=\"battleye\"\n\
+                 export LD_LIBRARY_PATH=\"/opt/dayz/lib:$LD_LIBRARY_PATH\"\n\
+                 \n\
+                 # Server Configuration"
+            ));
+        }
+        assert!(!script.content.contains("NOT VALID"));
+        assert!(!script.content.contains("{environment}"));
+
+        std::fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_render_environment() {
+        let environment = BTreeMap::from([("MSG".to_string(), r#"say "hi" \o/"#.to_string())]);
+
+        assert_eq!(
+            render_environment(&environment, "linux"),
+            "# Environment\nexport MSG=\"say \\\"hi\\\" \\\\o/\"\n\n"
+        );
+        assert_eq!(render_environment(&environment, "windows"), "");
+
+        let environment = BTreeMap::from([
+            ("LOAD".to_string(), "100% of %PATH%".to_string()),
+            ("MSG".to_string(), r#"say "hi""#.to_string()),
+        ]);
+        assert_eq!(
+            render_environment(&environment, "windows"),
+            "rem Environment\nset \"LOAD=100%% of %%PATH%%\"\n\n"
+        );
+        assert_eq!(render_environment(&BTreeMap::new(), "linux"), "");
+    }
+
+    #[test]
+    fn test_write_startup_script_to_output_dir() {
+        let base = std::env::temp_dir().join("dayz_tool_startup_output");
+        let _ = std::fs::remove_dir_all(&base);
+        let workdir = base.join("server");
+        let script_dir = base.join("ops");
+        let output_dir = base.join("override");
+        std::fs::create_dir_all(&workdir).unwrap();
+
+        let profile = Profile {
+            name: "Test Server".to_string(),
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            script_dir: Some(script_dir.to_str().unwrap().to_string()),
+            ..Default::default()
+        };
+        let filename = if OS == "windows" {
+            "start_server.bat"
+        } else {
+            "start_server.sh"
+        };
+
+        let script = write_startup_script(&profile, "2302", &[], None).unwrap();
+        assert_eq!(script.path, script_dir.join(filename));
+
+        let script = write_startup_script(&profile, "2302", &[], Some(&output_dir)).unwrap();
+        assert_eq!(script.path, output_dir.join(filename));
+        assert!(script.content.contains(workdir.to_str().unwrap()));
+        assert_eq!(
+            std::fs::read_to_string(output_dir.join(filename)).unwrap(),
+            script.content
+        );
+        assert!(!workdir.join(filename).exists());
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_script_hash_sidecar() {
+        let workdir = std::env::temp_dir().join("dayz_tool_startup_hash");
+        let _ = std::fs::remove_dir_all(&workdir);
+        std::fs::create_dir_all(&workdir).unwrap();
+
+        let profile = Profile {
+            name: "Test Server".to_string(),
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+
+        let script = write_startup_script(&profile, "2302", &[], None).unwrap();
+        let sidecar_path = write_script_hash(&script).unwrap();
+        assert_eq!(sidecar_path, hash_sidecar_path(&script.path));
+
+        let sidecar = std::fs::read_to_string(&sidecar_path).unwrap();
+        let recorded = sidecar.split_whitespace().next().unwrap();
+        let content = std::fs::read_to_string(&script.path).unwrap();
+        assert_eq!(recorded, script_hash(&content));
+        assert_eq!(recorded.len(), 64);
+        assert!(verify_startup_script(&profile, None).unwrap());
+
+        std::fs::write(&script.path, format!("{}\necho tampered\n", content)).unwrap();
+        assert!(!verify_startup_script(&profile, None).unwrap());
+
+        std::fs::remove_file(&sidecar_path).unwrap();
+        assert!(matches!(
+            verify_startup_script(&profile, None),
+            Err(ConfigError::MissingScriptHash(_))
+        ));
+
+        std::fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_default_mission_value() {
+        let workdir = std::env::temp_dir().join("dayz_tool_startup_mission");
+        let _ = std::fs::remove_dir_all(&workdir);
+        std::fs::create_dir_all(workdir.join("mpmissions/dayzOffline.enoch")).unwrap();
+        let workdir_path = workdir.to_str().unwrap();
+
+        assert_eq!(default_mission_value(workdir_path), None);
+
+        std::fs::write(
+            workdir.join("serverDZ.cfg"),
+            "class Missions\n{\n    class DayZ\n    {\n        template=\"dayzOffline.enoch\";\n    };\n};\n",
+        )
+        .unwrap();
+
+        let mission = default_mission_value(workdir_path).unwrap();
+        assert_eq!(mission, "./mpmissions/dayzOffline.enoch");
+        assert!(mission_folder_exists(workdir_path, &mission));
+        assert!(!mission_folder_exists(
+            workdir_path,
+            "./mpmissions/dayzOffline.chernarusplus"
+        ));
+
+        std::fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_check_parameter_path() {
+        let workdir = std::env::temp_dir().join("dayz_tool_startup_paths");
+        let _ = std::fs::remove_dir_all(&workdir);
+        std::fs::create_dir_all(workdir.join("battleye")).unwrap();
+        std::fs::create_dir_all(workdir.join("mpmissions/dayzOffline.enoch")).unwrap();
+        let workdir_path = workdir.to_str().unwrap();
+
+        assert_eq!(
+            check_parameter_path(workdir_path, "-BEpath=battleeye"),
+            Some(workdir.join("battleeye"))
+        );
+        assert_eq!(check_parameter_path(workdir_path, "-BEpath=battleye"), None);
+        assert_eq!(
+            check_parameter_path(workdir_path, "-storage=storage_1"),
+            Some(workdir.join("storage_1"))
+        );
+        assert_eq!(
+            check_parameter_path(workdir_path, "-mission=./mpmissions/dayzOffline.enoch"),
+            None
+        );
+        assert!(check_parameter_path(workdir_path, "-mission=dayzOffline.sakhal").is_some());
+        assert_eq!(check_parameter_path(workdir_path, "-cpuCount=4"), None);
+
+        std::fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_validate_parameters() {
+        let parameters: Vec<String> = [
+            "-mod=@CF;@VPPAdminTools",
+            "-doLogs",
+            "-cpuCount=4",
+            "-MOD=@CF;@Trader",
+            "-doLogs",
+            "-cpuCount=2",
+            "-port=2402",
+        ]
+        .iter()
+        .map(|p| p.to_string())
+        .collect();
+
+        assert_eq!(
+            validate_parameters(&parameters),
+            vec![
+                "-mod=@CF;@VPPAdminTools;@Trader".to_string(),
+                "-doLogs".to_string(),
+                "-cpuCount=4".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stored_template_parameters() {
+        let mut profile = Profile::default();
+        assert_eq!(stored_template_parameters(&profile), None);
+
+        profile.startup_template = Some(StartupTemplate::Builtin);
+        assert_eq!(
+            stored_template_parameters(&profile),
+            Some(builtin_template_parameters())
+        );
+
+        let parameters = vec!["-doLogs".to_string(), "-cpuCount=4".to_string()];
+        profile.startup_template = Some(StartupTemplate::Custom {
+            parameters: parameters.clone(),
+        });
+        assert_eq!(stored_template_parameters(&profile), Some(parameters));
+
+        let json = serde_json::to_value(&profile.startup_template).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "custom", "parameters": ["-doLogs", "-cpuCount=4"] })
+        );
+    }
 }
@@ -1,11 +1,14 @@
 use std::{
-    io::{self, Write},
+    collections::BTreeMap,
+    fmt,
+    io::{self, IsTerminal, Write},
     path::PathBuf,
     sync::{
-        atomic::{AtomicU64, AtomicUsize, Ordering},
-        mpsc, Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        mpsc, Arc, Condvar, Mutex,
     },
     thread,
+    time::{Duration, SystemTime},
 };
 
 use colored::Colorize;
@@ -26,9 +29,15 @@ pub enum GuidError {
     InvalidPrefix,
     #[error("Steam64ID must contain only numeric characters")]
     InvalidCharacters,
+    #[error("Line {0}: {1}")]
+    InvalidLine(usize, String),
+    #[error("Failed to read the file")]
+    ReadError,
+    #[error("Failed to write to file")]
+    WriteError,
 }
 
-#[derive(Debug, Error, PartialEq)]
+#[derive(Debug, Clone, Error, PartialEq)]
 pub enum ConfigError {
     #[error("Failed to create the configuration file")]
     CreateFileError,
@@ -44,10 +53,40 @@ pub enum ConfigError {
     NoActiveProfile,
     #[error("Failed to find the profile")]
     ProfileNotFoundError,
+    #[error("Profile '{0}' set in DAYZ_TOOL_PROFILE does not exist")]
+    UnknownProfileOverride(String),
     #[error("Failed to serialize the value")]
     SerializeError,
     #[error("Failed to update mods in profile")]
     ConfigError,
+    #[error("Profile {0} has an empty or missing name")]
+    EmptyProfileName(usize),
+    #[error("Profile '{0}' is missing the {1} path")]
+    MissingPath(String, String),
+    #[error("Profile '{0}' has an invalid installed mod entry at index {1}: expected a mod name")]
+    InvalidModEntry(String, usize),
+    #[error("Expected {0} to be {1}")]
+    InvalidShape(String, String),
+    #[error("Environment variable '{0}' used in a profile path is not set")]
+    UnknownEnvVar(String),
+    #[error("Startup script {0} not found")]
+    ScriptNotFound(String),
+    #[error("No recorded hash found at {0}")]
+    MissingScriptHash(String),
+    #[error("Failed to find a configuration directory. Set DAYZ_TOOL_CONFIG to choose one")]
+    NoConfigDir,
+    #[error("Expected a key of the form <profile>.<field>, got '{0}'")]
+    InvalidConfigKey(String),
+    #[error("Unknown profile field '{0}'")]
+    UnknownField(String),
+    #[error("No SteamCMD configuration found in the profile")]
+    MissingSteamCmdConfig,
+    #[error("No password entered")]
+    NoPassword,
+    #[error(transparent)]
+    Credential(#[from] CredentialError),
+    #[error(transparent)]
+    NonInteractive(#[from] NonInteractiveError),
 }
 
 #[derive(Debug, Error, PartialEq)]
@@ -62,6 +101,15 @@ pub enum DncError {
     InvalidNightTimeAcceleration,
 }
 
+/// A line of `serverDZ.cfg` changed by `write_dnc`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DncChange {
+    pub key: &'static str,
+    /// The line before the change, `None` if the key was missing and gets appended.
+    pub before: Option<String>,
+    pub after: String,
+}
+
 #[derive(Debug, Error, PartialEq)]
 pub enum ModError {
     #[error("Failed to find the mod")]
@@ -88,8 +136,57 @@ pub enum ModError {
     WriteError,
     #[error("Failed to read the file")]
     ReadError,
+    #[error("{0} is read-only but must be writable. {1}")]
+    ReadOnlyFile(String, String),
+    #[error("Invalid workshop ID: {0}")]
+    InvalidWorkshopId(String),
+    #[error("No SteamCMD configuration found in the profile")]
+    MissingSteamCmdConfig,
+    #[error("Failed to download the mods with SteamCMD")]
+    DownloadError,
+    #[error(
+        "The workshop path {0} and the workdir {1} must not be the same or nested in each other"
+    )]
+    OverlappingPaths(String, String),
+    #[error("Not enough disk space to copy to {0}. Free up space on the filesystem of {0} and try again")]
+    DiskFull(String),
+    #[error("Update stopped because {0} failed")]
+    UpdateAborted(String),
+    #[error("The installed copy of {0} differs from the workshop, reinstall it")]
+    VerifyFailed(String),
+    #[error("Mod {0} is not installed")]
+    NotInstalled(String),
+    #[error("Index {0} is out of range, the load order has {1} mods")]
+    InvalidLoadOrderIndex(usize, usize),
+    #[error("Invalid scale factor {0}, expected a non-negative number")]
+    InvalidScaleFactor(f64),
+    #[error(transparent)]
+    Credential(#[from] CredentialError),
+    #[error("No mod backups found in {0}")]
+    NoBackups(String),
+    #[error("Invalid mod folder name {0:?} in the backup manifest")]
+    InvalidBackupEntry(String),
+    #[error("No baseline found at {0}, run `mod freeze` first")]
+    NoBaseline(String),
+    #[error(transparent)]
+    NonInteractive(#[from] NonInteractiveError),
+}
+
+#[derive(Debug, Clone, Error, PartialEq)]
+pub enum CredentialError {
+    #[error("No password stored in the keyring for {0}")]
+    NotFound(String),
+    #[error("The keyring is not available: {0}")]
+    Unavailable(String),
 }
 
+/// A prompt that was refused because the tool runs non-interactively.
+///
+/// Holds what the user has to provide instead of answering the prompt.
+#[derive(Debug, Clone, Error, PartialEq)]
+#[error("input required but running non-interactively; provide {0}")]
+pub struct NonInteractiveError(pub String);
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Root {
@@ -105,16 +202,348 @@ pub struct Profile {
     pub start_parameters: Option<String>,
     pub installed_mods: Vec<Value>,
     pub is_active: bool,
+    #[serde(default)]
+    pub log_to_workdir: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mod_sources: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub server_mods: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub load_order: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steamcmd: Option<SteamCmdConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub manual_ce_mods: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup_template: Option<StartupTemplate>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ce_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mod_tags: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mod_dependencies: BTreeMap<String, Vec<String>>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub merge_types: bool,
+    /// Environment variables set at the top of the generated startup script.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub environment: BTreeMap<String, String>,
+}
+
+/// The SteamCMD settings used by `mod fetch` to download workshop items.
+///
+/// `command` is a template split on whitespace. The placeholders `{steamcmd}`, `{login}`
+/// and `{workshop}` are replaced by the settings and the profile's workshop path, and
+/// `{items}` expands to one `+workshop_download_item 221100 <id>` per workshop ID.
+/// `{password}` expands to the password of `login` from the OS keyring if `keyring` is
+/// set, and is dropped otherwise. The password itself is never stored in the config.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamCmdConfig {
+    pub path: String,
+    pub login: String,
+    #[serde(default = "default_steamcmd_command")]
+    pub command: String,
+    /// The keyring service holding the password of `login`, see `config steamcmd-password`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyring: Option<String>,
+}
+
+fn default_steamcmd_command() -> String {
+    "{steamcmd} +force_install_dir {workshop} +login {login} {password} {items} +quit".to_string()
+}
+
+/// The stored parameter choice of `generate start-up`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StartupTemplate {
+    /// The built-in parameter template.
+    Builtin,
+    /// A custom list of parameters, including their values.
+    Custom { parameters: Vec<String> },
+}
+
+/// A generated server startup script.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartupScript {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// The `manifest.json` of a mod backup created by `backup_mods`.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupManifest {
+    pub created: String,
+    pub mods: Vec<BackedUpMod>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackedUpMod {
+    /// The folder name of the mod in the workdir.
+    pub name: String,
+    /// The size of the mod folder in bytes.
+    pub size: u64,
+}
+
+/// A declarative description of the desired mod set of a profile (`mods.json`).
+///
+/// The order of `mods` is the load order of the profile.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Loadout {
+    pub mods: Vec<LoadoutMod>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadoutMod {
+    /// The folder name of the mod in the workdir.
+    pub name: String,
+    /// The workshop folder to install from, if it differs from `name`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub server_mod: bool,
+    /// The mods this mod requires, see `Profile::mod_dependencies`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct StartupParameters {
+    pub mods: String,
+    pub server_mods: String,
+}
+
+impl fmt::Display for StartupParameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parameters: Vec<&str> = [self.mods.as_str(), self.server_mods.as_str()]
+            .into_iter()
+            .filter(|p| !p.is_empty())
+            .collect();
+        write!(f, "{}", parameters.join(" "))
+    }
 }
 
 lazy_static! {
-    pub static ref THREAD_POOL: ThreadPool = ThreadPool::new(num_cpus::get());
+    pub static ref THREAD_POOL: ThreadPool = ThreadPool::new(pool_size(num_cpus::get()));
     pub static ref THEME: Theme = Theme::default();
+    pub static ref COPY_LIMITER: CopyLimiter = CopyLimiter::new(1, LARGE_MOD_SIZE);
+    pub static ref CHECKSUM_CACHE: Mutex<ChecksumCache> = Mutex::new(utils::load_checksum_cache());
+}
+
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables the global dry-run mode.
+///
+/// In dry-run mode all mutating operations log the intended filesystem and config
+/// changes instead of performing them.
+pub fn set_dry_run(enabled: bool) {
+    DRY_RUN.store(enabled, Ordering::SeqCst);
+}
+
+/// Returns whether the global dry-run mode is enabled.
+pub fn is_dry_run() -> bool {
+    DRY_RUN.load(Ordering::SeqCst)
+}
+
+static INTERACTIVE: AtomicBool = AtomicBool::new(true);
+
+/// Enables or disables the interactive prompts.
+///
+/// In non-interactive mode every prompt site fails with a `NonInteractiveError`
+/// instead of waiting for input that never comes.
+pub fn set_interactive(enabled: bool) {
+    INTERACTIVE.store(enabled, Ordering::SeqCst);
+}
+
+/// Returns whether prompting for input is allowed.
+pub fn is_interactive() -> bool {
+    INTERACTIVE.load(Ordering::SeqCst)
+}
+
+/// Fails if prompting is not allowed, naming the input to `provide` instead.
+pub fn require_interactive(provide: &str) -> Result<(), NonInteractiveError> {
+    if is_interactive() {
+        Ok(())
+    } else {
+        Err(NonInteractiveError(provide.to_string()))
+    }
+}
+
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables the JSON error output.
+///
+/// In this mode failed commands print a `JsonError` to stderr instead of the error log.
+pub fn set_json_errors(enabled: bool) {
+    JSON_ERRORS.store(enabled, Ordering::SeqCst);
+}
+
+/// Returns whether the JSON error output is enabled.
+pub fn is_json_errors() -> bool {
+    JSON_ERRORS.load(Ordering::SeqCst)
+}
+
+static SUMMARY_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables the summary-only output.
+///
+/// In this mode the terminal only shows errors and the summaries of bulk operations,
+/// logged with the `SUMMARY_TARGET` target. The log file still receives every entry.
+pub fn set_summary_only(enabled: bool) {
+    SUMMARY_ONLY.store(enabled, Ordering::SeqCst);
+}
+
+/// Returns whether the summary-only output is enabled.
+pub fn is_summary_only() -> bool {
+    SUMMARY_ONLY.load(Ordering::SeqCst)
+}
+
+/// Gives the machine-readable kind of an error, e.g. `NoActiveProfile`.
+pub trait ErrorKind {
+    fn kind(&self) -> String;
+}
+
+/// Returns the variant name from the `Debug` output of an error enum.
+fn variant_name(error: &impl fmt::Debug) -> String {
+    format!("{:?}", error)
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+impl ErrorKind for GuidError {
+    fn kind(&self) -> String {
+        variant_name(self)
+    }
+}
+
+impl ErrorKind for ConfigError {
+    fn kind(&self) -> String {
+        variant_name(self)
+    }
+}
+
+impl ErrorKind for DncError {
+    fn kind(&self) -> String {
+        variant_name(self)
+    }
+}
+
+impl ErrorKind for ModError {
+    fn kind(&self) -> String {
+        variant_name(self)
+    }
+}
+
+impl ErrorKind for CredentialError {
+    fn kind(&self) -> String {
+        variant_name(self)
+    }
+}
+
+impl ErrorKind for io::Error {
+    fn kind(&self) -> String {
+        format!("{:?}", io::Error::kind(self))
+    }
+}
+
+/// The context of a failed command, included in its `JsonError`.
+#[derive(Default, Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorContext {
+    pub config_path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+/// A failed command as printed in JSON error mode.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JsonError {
+    /// The exit code of the process.
+    pub code: i32,
+    /// The variant of the error, e.g. `NoActiveProfile`.
+    pub kind: String,
+    pub message: String,
+    pub context: ErrorContext,
+}
+
+impl JsonError {
+    pub fn new<E: ErrorKind + fmt::Display>(
+        message: &str,
+        error: &E,
+        context: &ErrorContext,
+    ) -> Self {
+        JsonError {
+            code: 1,
+            kind: error.kind(),
+            message: format!("{}: {}", message, error),
+            context: context.clone(),
+        }
+    }
+}
+
+lazy_static! {
+    static ref CANCELLED: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+}
+
+/// Requests the cancellation of the running operation.
+///
+/// Long-running operations such as the mod comparison check the flag (see `is_cancelled`)
+/// and return early with an `Interrupted` error.
+pub fn request_cancel() {
+    CANCELLED.store(true, Ordering::SeqCst);
+}
+
+/// Returns whether the cancellation of the running operation was requested.
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::SeqCst)
+}
+
+/// Returns the global cancellation flag, e.g. to pass it to functions taking a flag.
+pub fn cancel_flag() -> &'static AtomicBool {
+    &CANCELLED
+}
+
+/// Installs a Ctrl-C handler that requests cancellation instead of killing the process.
+///
+/// Pressing Ctrl-C a second time terminates the process immediately, so operations that
+/// do not check the flag can still be aborted.
+pub fn install_cancel_handler() -> io::Result<()> {
+    use signal_hook::{consts::SIGINT, flag};
+
+    flag::register_conditional_shutdown(SIGINT, 1, Arc::clone(&CANCELLED))?;
+    flag::register(SIGINT, Arc::clone(&CANCELLED))?;
+    Ok(())
+}
+
+/// The most worker threads the shared pool starts, regardless of the reported CPU count.
+pub const MAX_POOL_SIZE: usize = 64;
+
+/// Clamps the reported CPU count to a pool size between 1 and `MAX_POOL_SIZE`.
+///
+/// `ThreadPool::new` panics on 0, and a bogus large count from a constrained cgroup would
+/// exhaust the threads of the process.
+pub fn pool_size(cpus: usize) -> usize {
+    cpus.clamp(1, MAX_POOL_SIZE)
 }
 
 pub struct ThreadPool {
     workers: Vec<Worker>,
-    sender: mpsc::Sender<Box<dyn FnOnce() + Send>>,
+    sender: Option<mpsc::Sender<Job>>,
     job_count: Arc<AtomicUsize>,
 }
 
@@ -136,7 +565,7 @@ impl ThreadPool {
 
         ThreadPool {
             workers,
-            sender,
+            sender: Some(sender),
             job_count,
         }
     }
@@ -151,7 +580,7 @@ impl ThreadPool {
             task();
             job_count.fetch_sub(1, Ordering::SeqCst);
         });
-        self.sender.send(task).unwrap();
+        self.sender.as_ref().unwrap().send(task).unwrap();
     }
 
     pub fn wait(&self) {
@@ -163,9 +592,7 @@ impl ThreadPool {
 
 impl Drop for ThreadPool {
     fn drop(&mut self) {
-        for _ in &self.workers {
-            self.sender.send(Box::new(|| {})).unwrap();
-        }
+        drop(self.sender.take());
 
         for worker in &mut self.workers {
             if let Some(thread) = worker.thread.take() {
@@ -200,6 +627,63 @@ impl Worker {
     }
 }
 
+/// The size from which a mod is considered large for copy scheduling (1 GiB).
+pub const LARGE_MOD_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// Limits how many large mods are copied at the same time.
+///
+/// Copying several huge mods in parallel thrashes the disk, especially on spinning
+/// disks. Copies of mods of at least `threshold` bytes acquire one of `limit` slots
+/// first, while smaller mods are copied without restriction.
+pub struct CopyLimiter {
+    limit: usize,
+    threshold: u64,
+    active: Mutex<usize>,
+    released: Condvar,
+}
+
+impl CopyLimiter {
+    pub fn new(limit: usize, threshold: u64) -> CopyLimiter {
+        assert!(limit > 0);
+
+        CopyLimiter {
+            limit,
+            threshold,
+            active: Mutex::new(0),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Blocks until a copy of `size` bytes may start.
+    ///
+    /// Returns a permit for large copies that frees the slot when dropped, or `None`
+    /// for small copies.
+    pub fn acquire(&self, size: u64) -> Option<CopyPermit<'_>> {
+        if size < self.threshold {
+            return None;
+        }
+
+        let mut active = self.active.lock().unwrap();
+        while *active >= self.limit {
+            active = self.released.wait(active).unwrap();
+        }
+        *active += 1;
+
+        Some(CopyPermit { limiter: self })
+    }
+}
+
+pub struct CopyPermit<'a> {
+    limiter: &'a CopyLimiter,
+}
+
+impl Drop for CopyPermit<'_> {
+    fn drop(&mut self) {
+        *self.limiter.active.lock().unwrap() -= 1;
+        self.limiter.released.notify_one();
+    }
+}
+
 pub struct Mod {
     name: String,
 }
@@ -215,6 +699,329 @@ impl Mod {
     }
 }
 
+/// The update state of an installed mod compared to its workshop copy.
+///
+/// `Available` marks a workshop mod that is not installed, see `get_workshop_statuses`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModStatus {
+    UpToDate,
+    Outdated,
+    MissingFromWorkshop,
+    MissingFromWorkdir,
+    Available,
+}
+
+impl ModStatus {
+    /// Returns whether the installed copy differs from the workshop and an update would
+    /// replace it.
+    pub fn needs_update(&self) -> bool {
+        matches!(self, ModStatus::Outdated | ModStatus::MissingFromWorkdir)
+    }
+}
+
+impl fmt::Display for ModStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModStatus::UpToDate => write!(f, "up to date"),
+            ModStatus::Outdated => write!(f, "outdated"),
+            ModStatus::MissingFromWorkshop => write!(f, "missing from workshop"),
+            ModStatus::MissingFromWorkdir => write!(f, "missing from workdir"),
+            ModStatus::Available => write!(f, "available"),
+        }
+    }
+}
+
+/// How much detail the summary of `install_mods` shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum SummaryVerbosity {
+    /// A single line with the totals.
+    #[default]
+    Total,
+    /// The totals and one line per mod.
+    PerMod,
+    /// The totals and one line per mod with the number of CE entries.
+    Full,
+}
+
+/// How `install_mods` installs the selected mods.
+///
+/// `install_mod` only uses `map`, `register_ce` and `extract_types`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstallOptions {
+    /// The workshop folders of the mods to install. The user selects them if empty.
+    pub mods: Vec<String>,
+    /// Installs the selected mod under this folder name instead of its workshop name.
+    pub rename: Option<String>,
+    /// The mission folder for the CE files, detected from `serverDZ.cfg` if not set.
+    pub map: Option<String>,
+    /// Registers the extracted CE files in `cfgeconomycore.xml`.
+    pub register_ce: bool,
+    /// Extracts the CE files of the mods at all.
+    pub extract_types: bool,
+    /// Writes the startup parameters to `mod_params.txt`, see `write_mod_params`.
+    pub write_params: bool,
+    /// Compares the installed copies with the workshop, see `verify_installed_copies`.
+    pub verify: bool,
+    /// How many folder levels of the workshop path are searched, see `find_workshop_mods`.
+    pub scan_depth: usize,
+    /// How much detail the summary after the installation shows.
+    pub summary: SummaryVerbosity,
+}
+
+impl Default for InstallOptions {
+    fn default() -> Self {
+        InstallOptions {
+            mods: Vec::new(),
+            rename: None,
+            map: None,
+            register_ce: true,
+            extract_types: true,
+            write_params: false,
+            verify: false,
+            scan_depth: 1,
+            summary: SummaryVerbosity::default(),
+        }
+    }
+}
+
+/// What was installed for a single mod, see `InstallSummary`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstalledModSummary {
+    pub name: String,
+    pub keys: usize,
+    pub types: usize,
+    pub spawnable_types: usize,
+    pub events: usize,
+}
+
+impl InstalledModSummary {
+    /// Returns whether any CE data was extracted for the mod.
+    pub fn has_ce_data(&self) -> bool {
+        self.types + self.spawnable_types + self.events > 0
+    }
+}
+
+/// The mods installed by `install_mods`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InstallSummary {
+    pub mods: Vec<InstalledModSummary>,
+}
+
+impl InstallSummary {
+    /// Formats the summary with the given amount of detail.
+    ///
+    /// The first line always holds the totals, e.g.
+    /// `Installed 5 mods, 3 with CE data, 12 keys`.
+    pub fn format(&self, verbosity: SummaryVerbosity) -> String {
+        let mut lines = vec![format!(
+            "Installed {} mods, {} with CE data, {} keys",
+            self.mods.len(),
+            self.mods.iter().filter(|m| m.has_ce_data()).count(),
+            self.mods.iter().map(|m| m.keys).sum::<usize>()
+        )];
+
+        for installed in &self.mods {
+            match verbosity {
+                SummaryVerbosity::Total => break,
+                SummaryVerbosity::PerMod => lines.push(format!(
+                    "- {}: {} keys{}",
+                    installed.name,
+                    installed.keys,
+                    if installed.has_ce_data() {
+                        ", CE data"
+                    } else {
+                        ""
+                    }
+                )),
+                SummaryVerbosity::Full => lines.push(format!(
+                    "- {}: {} keys, {} types, {} spawnable types, {} events",
+                    installed.name,
+                    installed.keys,
+                    installed.types,
+                    installed.spawnable_types,
+                    installed.events
+                )),
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// The combined loot types of the installed mods, see `economy_preview`.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct EconomyPreview {
+    /// The number of type entries read from all files.
+    pub total_entries: usize,
+    /// The number of distinct type names.
+    pub unique_items: usize,
+    /// Types defined by more than one source, with the sources in load order.
+    pub overrides: Vec<TypeOverride>,
+    /// The number of distinct types per category, by the definition that wins.
+    pub categories: BTreeMap<String, usize>,
+}
+
+/// A type defined by several sources. The last source wins.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeOverride {
+    pub name: String,
+    pub sources: Vec<String>,
+}
+
+impl fmt::Display for EconomyPreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} unique items from {} entries, {} overridden",
+            self.unique_items,
+            self.total_entries,
+            self.overrides.len()
+        )?;
+        for (category, count) in &self.categories {
+            writeln!(f, "  {}: {}", category, count)?;
+        }
+        for type_override in &self.overrides {
+            writeln!(
+                f,
+                "  {} defined by {}",
+                type_override.name,
+                type_override.sources.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The time the install and update jobs spent on each mod.
+#[derive(Debug, Default)]
+pub struct TimingReport {
+    timings: Mutex<BTreeMap<String, Duration>>,
+}
+
+impl TimingReport {
+    /// Adds `elapsed` to the time spent on `mod_name`.
+    pub fn record(&self, mod_name: &str, elapsed: Duration) {
+        *self
+            .timings
+            .lock()
+            .unwrap()
+            .entry(mod_name.to_string())
+            .or_default() += elapsed;
+    }
+
+    /// Returns whether no time was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.timings.lock().unwrap().is_empty()
+    }
+
+    /// Returns the time spent per mod, the slowest first.
+    pub fn timings(&self) -> Vec<(String, Duration)> {
+        let mut timings: Vec<(String, Duration)> = self
+            .timings
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, elapsed)| (name.clone(), *elapsed))
+            .collect();
+        timings.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        timings
+    }
+
+    /// Formats the report with one line per mod, the slowest first.
+    pub fn format(&self) -> String {
+        let timings = self.timings();
+        let width = timings
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0);
+        let mut report = "Time per mod:".to_string();
+        for (name, elapsed) in timings {
+            report.push_str(&format!(
+                "\n  {:<width$}  {:.2}s",
+                name,
+                elapsed.as_secs_f64(),
+                width = width
+            ));
+        }
+        report
+    }
+}
+
+/// The size and hash of a file in a mod folder, see `mod_file_checksums`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileChecksum {
+    pub size: u64,
+    pub hash: String,
+}
+
+/// The file checksums of the installed mods at the time of `mod freeze`.
+///
+/// `mod check-drift` compares the installed mods against it.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModBaseline {
+    pub created: String,
+    pub mods: BTreeMap<String, BTreeMap<PathBuf, FileChecksum>>,
+}
+
+/// The files that differ between the workshop and the workdir copy of a mod.
+///
+/// Paths are relative to the mod folder. `added` files exist only in the newer state
+/// (e.g. the workshop) and `removed` files only in the older one (e.g. the workdir).
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ModDiff {
+    pub added: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+    pub changed: Vec<PathBuf>,
+}
+
+impl ModDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for ModDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} added, {} removed, {} changed",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )
+    }
+}
+
+/// The outcome of `update_mods`, with the mod names of each outcome in processing order.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct UpdateSummary {
+    /// Mods that were re-copied from the workshop.
+    pub updated: Vec<String>,
+    /// Mods that were skipped because they are up to date.
+    pub up_to_date: Vec<String>,
+    /// Mods that were skipped because their workshop folder does not exist.
+    pub missing_from_workshop: Vec<String>,
+    /// Mods that were skipped because they are excluded for this run.
+    pub excluded: Vec<String>,
+    /// Mods whose check, removal or copy failed.
+    pub failed: Vec<String>,
+}
+
+impl fmt::Display for UpdateSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} updated, {} up to date, {} missing from workshop, {} excluded, {} failed",
+            self.updated.len(),
+            self.up_to_date.len(),
+            self.missing_from_workshop.len(),
+            self.excluded.len(),
+            self.failed.len()
+        )
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct Types {
     #[serde(rename = "type")]
@@ -415,6 +1222,49 @@ pub struct ModChecksum {
     pub path: PathBuf,
     pub size: u64,
     pub hash: String,
+    /// The modification time, `UNIX_EPOCH` if it could not be read.
+    pub modified: SystemTime,
+    /// Whether the entry is a directory. Directories have no size and hash.
+    pub is_dir: bool,
+}
+
+/// The file hashes calculated by the mod comparison, shared by all profiles.
+///
+/// Entries are keyed by the canonical file path and only used while the size and
+/// modification time of the file are unchanged. Profiles sharing a workshop path thus
+/// reuse the hashes of its mods.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChecksumCache {
+    pub entries: BTreeMap<PathBuf, CachedChecksum>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedChecksum {
+    pub size: u64,
+    pub modified: u64,
+    pub hash: String,
+}
+
+impl ChecksumCache {
+    /// Returns the cached hash of a file, if its size and modification time still match.
+    pub fn get(&self, path: &PathBuf, size: u64, modified: u64) -> Option<&str> {
+        self.entries
+            .get(path)
+            .filter(|entry| entry.size == size && entry.modified == modified)
+            .map(|entry| entry.hash.as_str())
+    }
+
+    /// Stores the hash of a file, replacing an outdated entry.
+    pub fn insert(&mut self, path: PathBuf, size: u64, modified: u64, hash: String) {
+        self.entries.insert(
+            path,
+            CachedChecksum {
+                size,
+                modified,
+                hash,
+            },
+        );
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -477,9 +1327,17 @@ pub struct ProgressBar {
     width: usize,
     description: String,
     theme: Arc<Theme>,
+    count_items: bool,
+    interactive: bool,
+    reported_step: Arc<AtomicU64>,
+    finished: Arc<AtomicBool>,
 }
 
 impl ProgressBar {
+    /// Creates a progress bar for `total` units.
+    ///
+    /// If stdout is not a terminal (e.g. in CI logs), the bar is not redrawn in place.
+    /// Instead, a plain line is printed each time another 10% is reached.
     pub fn new(total: u64, width: usize, description: &str, theme: Arc<Theme>) -> Self {
         ProgressBar {
             progress: Arc::new(AtomicU64::new(0)),
@@ -487,9 +1345,34 @@ impl ProgressBar {
             width,
             description: description.to_string(),
             theme,
+            count_items: false,
+            interactive: io::stdout().is_terminal(),
+            reported_step: Arc::new(AtomicU64::new(0)),
+            finished: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Displays the progress as a plain item count instead of a byte size.
+    pub fn with_item_count(mut self) -> Self {
+        self.count_items = true;
+        self
+    }
+
+    /// Overrides whether the bar is redrawn in place or printed as separate lines.
+    pub fn with_interactive(mut self, interactive: bool) -> Self {
+        self.interactive = interactive;
+        self
+    }
+
+    /// Returns whether the bar counts items rather than bytes, see `with_item_count`.
+    pub fn counts_items(&self) -> bool {
+        self.count_items
+    }
+
+    pub fn position(&self) -> u64 {
+        self.progress.load(Ordering::Relaxed)
+    }
+
     pub fn inc(&self, delta: u64) {
         self.progress.fetch_add(delta, Ordering::Relaxed);
         self.draw();
@@ -502,10 +1385,17 @@ impl ProgressBar {
 
     fn calculate_precentage(&self) -> f64 {
         let current = self.progress.load(Ordering::Relaxed);
-        (current as f64 / self.total as f64) * 100.0
+        if self.total == 0 {
+            return 100.0;
+        }
+        ((current as f64 / self.total as f64) * 100.0).min(100.0)
     }
 
     fn format_size(&self, bytes: u64) -> String {
+        if self.count_items {
+            return bytes.to_string();
+        }
+
         const KB: u64 = 1024;
         const MB: u64 = KB * 1024;
         const GB: u64 = MB * 1024;
@@ -522,12 +1412,43 @@ impl ProgressBar {
     }
 
     fn draw(&self) {
-        let precentage = self.calculate_precentage();
-        let filled_width = ((self.width as f64) * (precentage / 100.0)) as usize;
-        let empty_width = self.width - filled_width;
+        if is_summary_only() {
+            return;
+        }
 
+        if let Some(output) = self.render() {
+            print!("{}", output);
+            io::stdout().flush().unwrap();
+        }
+    }
+
+    /// Returns the output for the current progress, or `None` if nothing is to be printed.
+    ///
+    /// Interactive bars start with `\r` to redraw the line and end with a newline once
+    /// complete. The newline is added only to the first output after completion, even if
+    /// several jobs finish concurrently. Otherwise, a newline-terminated line is returned
+    /// for every 10% step that was not reported yet.
+    fn render(&self) -> Option<String> {
+        let precentage = self.calculate_precentage();
         let current = self.progress.load(Ordering::Relaxed);
 
+        if !self.interactive {
+            let step = (precentage / 10.0) as u64;
+            if step == 0 || self.reported_step.fetch_max(step, Ordering::Relaxed) >= step {
+                return None;
+            }
+            return Some(format!(
+                "{}: {:.0}% ({}/{})\n",
+                self.description,
+                precentage,
+                self.format_size(current),
+                self.format_size(self.total)
+            ));
+        }
+
+        let filled_width = ((self.width as f64) * (precentage / 100.0)) as usize;
+        let empty_width = self.width.saturating_sub(filled_width);
+
         let description = self.theme.label(&self.description);
         let progress_bar = format!(
             "{}{}",
@@ -541,11 +1462,89 @@ impl ProgressBar {
             self.format_size(self.total)
         ));
 
-        print!("\r{}: [{}] {}", description, progress_bar, stats);
-        io::stdout().flush().unwrap();
+        let line_end = if current >= self.total && !self.finished.swap(true, Ordering::Relaxed) {
+            "\n"
+        } else {
+            ""
+        };
+        Some(format!(
+            "\r{}: [{}] {}{}",
+            description, progress_bar, stats, line_end
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_size_is_clamped() {
+        assert_eq!(pool_size(0), 1);
+        assert_eq!(pool_size(1), 1);
+        assert_eq!(pool_size(8), 8);
+        assert_eq!(pool_size(MAX_POOL_SIZE), MAX_POOL_SIZE);
+        assert_eq!(pool_size(usize::MAX), MAX_POOL_SIZE);
+
+        let pool = ThreadPool::new(pool_size(0));
+        assert_eq!(pool.workers.len(), 1);
+    }
+
+    #[test]
+    fn test_progress_bar_ends_line_once() {
+        let progress = ProgressBar::new(10, 30, "Installing mods", Arc::new(THEME.clone()))
+            .with_interactive(true);
+
+        progress.progress.store(5, Ordering::Relaxed);
+        assert!(!progress.render().unwrap().ends_with('\n'));
+        progress.progress.store(10, Ordering::Relaxed);
+        assert!(progress.render().unwrap().ends_with('\n'));
+        progress.progress.store(12, Ordering::Relaxed);
+        assert!(!progress.render().unwrap().ends_with('\n'));
+    }
+
+    #[test]
+    fn test_timing_report_sorts_by_duration() {
+        let report = TimingReport::default();
+        report.record("@CF", Duration::from_millis(300));
+        report.record("@Expansion", Duration::from_secs(2));
+        report.record("@CF", Duration::from_millis(200));
+
+        assert_eq!(
+            report.timings(),
+            vec![
+                ("@Expansion".to_string(), Duration::from_secs(2)),
+                ("@CF".to_string(), Duration::from_millis(500)),
+            ]
+        );
+        assert_eq!(
+            report.format(),
+            "Time per mod:\n  @Expansion  2.00s\n  @CF         0.50s"
+        );
+    }
+
+    #[test]
+    fn test_progress_bar_prints_lines_without_terminal() {
+        let progress = ProgressBar::new(20, 30, "Installing mods", Arc::new(THEME.clone()))
+            .with_item_count()
+            .with_interactive(false);
 
-        if current >= self.total {
-            println!();
+        let mut output = Vec::new();
+        for _ in 0..20 {
+            progress.progress.fetch_add(1, Ordering::Relaxed);
+            output.extend(progress.render());
         }
+
+        assert_eq!(output.len(), 10);
+        assert_eq!(output[0], "Installing mods: 10% (2/20)\n");
+        assert_eq!(output[9], "Installing mods: 100% (20/20)\n");
+        assert!(output.iter().all(|line| !line.contains('\r')));
+
+        let interactive = ProgressBar::new(2, 30, "Installing mods", Arc::new(THEME.clone()))
+            .with_item_count()
+            .with_interactive(true);
+        interactive.progress.fetch_add(1, Ordering::Relaxed);
+        let line = interactive.render().unwrap();
+        assert!(line.starts_with('\r') && !line.ends_with('\n'));
     }
 }
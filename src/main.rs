@@ -1,14 +1,30 @@
 use clap::{Parser, Subcommand};
 use dayz_tool_cli::commands::{
-    calculate_dnc, create_profile, delete_profile, generate_guid, generate_startup_script,
-    install_mods, list_installed_mods, list_profiles, show_profile, switch_profile, uninstall_mods,
-    update_mods, update_profile,
+    backup_mods, calculate_dnc, calculate_dnc_reverse, check_ce, check_drift, check_signatures,
+    check_updates, clean_backups, create_profile, current_profile, delete_profile, dnc_table,
+    economy_preview, fetch_mods, format_config, format_dependency_graph, format_duration,
+    freeze_mods, generate_admins, generate_guid, generate_guid_pairs, generate_startup_script,
+    get_config_value, import_profiles, install_keys, install_loadout, install_mods,
+    list_installed_mods, list_profiles, move_load_order, rebuild_ce, recompute_short_names,
+    restore_mods, restore_profile, reverse_dnc, scale_mod_types, show_mod_status, show_profile,
+    store_steamcmd_password, swap_mod, switch_profile, tag_mod, uninstall_mods, update_mods,
+    update_profile, validate_xml, verify_mods, verify_startup_script, write_dnc, write_guid_pairs,
+    write_script_hash,
 };
 use dayz_tool_cli::utils::{
-    create_initial_profile, get_config_path, get_profile, get_render_config, init_logger,
+    create_initial_profile, get_config_path, get_log_dir, get_profile, get_render_config,
+    init_logger, report_error, resolve_profile_paths, save_checksum_cache, SUMMARY_TARGET,
+};
+use dayz_tool_cli::{
+    install_cancel_handler, is_dry_run, set_dry_run, set_interactive, set_json_errors,
+    set_summary_only, ErrorContext, GuidError, InstallOptions, SummaryVerbosity, THEME,
+    THREAD_POOL,
 };
-use dayz_tool_cli::{THEME, THREAD_POOL};
 use log::{debug, error, info};
+use std::{
+    io::IsTerminal,
+    path::{Path, PathBuf},
+};
 
 /// A command-line tool for simplifying DayZ server administration.
 ///
@@ -24,6 +40,20 @@ use log::{debug, error, info};
 #[derive(Parser)]
 #[command(author = "KarnesTH", version, about, long_about = None)]
 struct Cli {
+    /// Logs the intended filesystem and config changes without performing them.
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Prints failures as JSON to stderr (code, kind, message, context) and exits non-zero.
+    #[arg(long, global = true)]
+    json_errors: bool,
+    /// Shows only errors and the final summary of bulk operations such as `mod update`.
+    #[arg(long, global = true)]
+    summary_only: bool,
+    /// Allows prompting for input. With `false`, commands that need input fail instead.
+    ///
+    /// Defaults to `true` when stdin is a terminal and `false` otherwise.
+    #[arg(long, global = true, value_name = "BOOL")]
+    interactive: Option<bool>,
     #[command(subcommand)]
     commands: Commands,
 }
@@ -53,9 +83,12 @@ enum Commands {
     /// # Usage
     ///
     /// ```bash
-    /// dayz-tool-cli mod <subcommand>
+    /// dayz-tool-cli mod <subcommand> [--map <folder>]
     /// ```
     Mods {
+        /// Uses the given mpmissions folder instead of the map detected from serverDZ.cfg.
+        #[arg(long, global = true, value_name = "FOLDER")]
+        map: Option<String>,
         #[command(subcommand)]
         subcommands: ModCommands,
     },
@@ -74,6 +107,96 @@ enum Commands {
         #[command(subcommand)]
         subcommands: ProfileCommands,
     },
+
+    /// Maintains the configuration file.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli config <subcommand>
+    /// ```
+    Config {
+        #[command(subcommand)]
+        subcommands: ConfigCommands,
+    },
+
+    /// Lists the backups created by the tool and removes old ones.
+    ///
+    /// The backups are the mod snapshots of `mod backup` in the `.dayz-backups` folder
+    /// of the active profile's workdir. Other files are never removed.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli clean-backups [--older-than <days>] [--yes]
+    /// ```
+    CleanBackups {
+        /// Removes backups older than the given number of days.
+        #[arg(long, value_name = "DAYS", default_value_t = 30)]
+        older_than: u64,
+        /// Removes the old backups without asking for confirmation.
+        #[arg(long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommands {
+    /// Rewrites the configuration file with canonical formatting.
+    ///
+    /// Trims whitespace around profile names, paths and mod names left by manual edits.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli config format
+    /// ```
+    Format,
+    /// Restores a single profile from a configuration backup.
+    ///
+    /// The profile is added next to the existing ones. On a name collision it is
+    /// restored as `<name> (restored)`.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli config restore-profile <backup_file> <name>
+    /// ```
+    RestoreProfile {
+        /// Path to the configuration backup.
+        backup_file: PathBuf,
+        /// Name of the profile to restore.
+        name: String,
+    },
+    /// Prints a single value from the configuration.
+    ///
+    /// The key is `<profile>.<field>`; use `active` for the active profile.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli config get active.workdir_path
+    /// ```
+    Get {
+        /// The `<profile>.<field>` key to print.
+        key: String,
+    },
+    /// Stores the SteamCMD password in the OS keyring.
+    ///
+    /// Only a reference to the keyring is written to the configuration; `mod fetch`
+    /// reads the password at runtime.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli config steamcmd-password
+    /// echo "$PASSWORD" | dayz-tool-cli config steamcmd-password --password-stdin
+    /// ```
+    SteamcmdPassword {
+        /// Reads the password from the first line of stdin instead of prompting.
+        #[arg(long)]
+        password_stdin: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -90,10 +213,41 @@ enum GenerateCommands {
     ///
     /// ```bash
     /// dayz-tool-cli generate guid 76561198039479170
+    /// dayz-tool-cli generate guid --file ids.txt --output guids.txt
+    /// cat ids.txt | dayz-tool-cli generate guid
     /// ```
+    ///
+    /// Without an ID, newline-separated IDs are read from `--file` or from piped stdin.
+    /// Invalid IDs are reported and skipped, and the command exits with code 1 at the end.
     Guid {
         /// The Steam64 ID to generate the GUID from.
+        #[arg(conflicts_with = "file")]
         id: Option<String>,
+        /// The file with one Steam64 ID per line.
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Writes the ID and GUID pairs as two tab-separated columns to this file.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Generates the GUIDs of an admin list of Steam64 IDs.
+    ///
+    /// The file holds one Steam64 ID per line. A trailing `//` comment (e.g. the player's
+    /// name) is kept next to the GUID.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli generate admins --file ids.txt --output admins.txt
+    /// ```
+    Admins {
+        /// The file with one Steam64 ID per line.
+        #[arg(long)]
+        file: PathBuf,
+        /// The file to write the GUIDs to.
+        #[arg(long)]
+        output: PathBuf,
     },
 
     /// Converts hours and minutes into DayZ server settings for Day Night Cycle.
@@ -102,14 +256,37 @@ enum GenerateCommands {
     ///
     /// ```bash
     /// dayz-tool-cli generate dnc -d "8h" -n "10min"
+    /// dayz-tool-cli generate dnc --table
+    /// dayz-tool-cli generate dnc -d "8h" -n "10min" --write --dry-run
+    /// dayz-tool-cli generate dnc --reverse --time-accel 1.5 --night-accel 48
     /// ```
     Dnc {
-        /// The amount of time the server should be in day time. (e.g. 8h, 10min)
+        /// The amount of time the server should be in day time. (e.g. 8h, 1h30min, 1.5h)
         #[arg(short = 'd', long)]
         day: Option<String>,
-        /// The amount of time the server should be in night time. (e.g. 8h, 10min)
+        /// The amount of time the server should be in night time. (e.g. 10min, 0.25h)
         #[arg(short = 'n', long)]
         night: Option<String>,
+        /// Calculates the day and night length from existing acceleration values instead.
+        #[arg(
+            long,
+            requires_all = ["time_accel", "night_accel"],
+            conflicts_with_all = ["day", "night", "table", "write"]
+        )]
+        reverse: bool,
+        /// The `serverTimeAcceleration` value for `--reverse`.
+        #[arg(long, requires = "reverse")]
+        time_accel: Option<f32>,
+        /// The `serverNightTimeAcceleration` value for `--reverse`.
+        #[arg(long, requires = "reverse")]
+        night_accel: Option<f32>,
+        /// Prints a reference chart of common day/night length combinations.
+        #[arg(long)]
+        table: bool,
+        /// Writes the values into the profile's `serverDZ.cfg`. Combine with `--dry-run`
+        /// to preview the changed lines.
+        #[arg(long)]
+        write: bool,
     },
 
     /// Generates a server_start script for the DayZ server.
@@ -118,8 +295,26 @@ enum GenerateCommands {
     ///
     /// ```bash
     /// dayz-tool-cli generate start-up
+    /// dayz-tool-cli generate start-up --output <dir>
+    /// dayz-tool-cli generate start-up --port 2302
+    /// dayz-tool-cli generate start-up --sidecar
+    /// dayz-tool-cli generate start-up --verify
     /// ```
-    StartUp,
+    StartUp {
+        /// The server port. Together with a `startupTemplate` in the profile nothing is asked.
+        #[arg(long)]
+        port: Option<u16>,
+        /// Writes the script to this directory instead of the profile's script directory
+        /// or workdir.
+        #[arg(long)]
+        output: Option<PathBuf>,
+        /// Writes the SHA256 of the script to a `.sha256` file next to it.
+        #[arg(long)]
+        sidecar: bool,
+        /// Checks the existing script against its `.sha256` file instead of generating one.
+        #[arg(long, conflicts_with = "sidecar")]
+        verify: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -133,9 +328,113 @@ enum ModCommands {
     /// # Usage
     ///
     /// ```bash
-    /// dayz-tool-cli mod install
+    /// dayz-tool-cli mod install [<mod>...] [--as <newName>] [--no-ce-register | --no-types] [--write-params] [--verify]
+    /// dayz-tool-cli mod install --loadout mods.json [--prune [--yes]] [--write-params]
+    /// ```
+    Install {
+        /// The workshop folders of the mods to install, selected interactively if omitted.
+        #[arg(conflicts_with = "loadout")]
+        mods: Vec<String>,
+        /// Installs the selected mod under a different folder name in the workdir.
+        #[arg(long = "as", alias = "rename", value_name = "NEW_NAME")]
+        rename: Option<String>,
+        /// Installs the mods of a loadout file and updates the profile to match it.
+        #[arg(long, value_name = "FILE", conflicts_with = "rename")]
+        loadout: Option<PathBuf>,
+        /// Uninstalls installed mods that are not listed in the loadout (asks for confirmation).
+        #[arg(long, requires = "loadout")]
+        prune: bool,
+        /// Uninstalls the mods for `--prune` without asking for confirmation.
+        #[arg(long, requires = "prune")]
+        yes: bool,
+        /// Extracts the CE files but does not register them in cfgeconomycore.xml.
+        #[arg(long, conflicts_with = "loadout")]
+        no_ce_register: bool,
+        /// Installs only the mod files and keys, without extracting any CE data.
+        #[arg(long, conflicts_with_all = ["loadout", "no_ce_register"])]
+        no_types: bool,
+        /// Writes the startup parameters to mod_params.txt in the workdir.
+        #[arg(long)]
+        write_params: bool,
+        /// Compares the installed copies with the workshop after copying.
+        #[arg(long, conflicts_with = "loadout")]
+        verify: bool,
+        /// How many folder levels of the workshop path are searched for mods.
+        #[arg(
+            long,
+            value_name = "DEPTH",
+            default_value_t = 1,
+            conflicts_with = "loadout"
+        )]
+        workshop_scan_depth: usize,
+        /// How much detail the summary after the installation shows.
+        #[arg(long, value_enum, default_value_t, conflicts_with = "loadout")]
+        summary: SummaryVerbosity,
+    },
+
+    /// Downloads workshop items with SteamCMD and installs them.
+    ///
+    /// Requires the `steamcmd` settings (path, login and command template) in the profile.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod fetch <id>...
+    /// ```
+    Fetch {
+        /// The workshop IDs of the mods to download.
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+
+    /// Installs only the signing keys of a mod.
+    ///
+    /// Copies the `.bikey` files of the given workshop mod into the server's keys folder
+    /// without touching the mod files or CE data.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod install-keys <modName>
+    /// ```
+    InstallKeys {
+        /// The name of the mod folder (e.g. @CF).
+        name: String,
+    },
+
+    /// Replaces an installed mod with another workshop mod.
+    ///
+    /// The new mod takes over the load order position of the old one. If installing the
+    /// new mod fails, the old mod is kept.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod swap <oldMod> <newMod>
     /// ```
-    Install,
+    Swap {
+        /// The name of the installed mod folder to replace (e.g. @Trader).
+        old: String,
+        /// The workshop folder of the replacement mod (e.g. @TraderPlus).
+        new: String,
+    },
+
+    /// Moves an installed mod to a position in the load order.
+    ///
+    /// The index is zero-based, so `--to 0` loads the mod first.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod move-load-order <modName> --to <index>
+    /// ```
+    MoveLoadOrder {
+        /// The name of the installed mod folder (e.g. @CF).
+        name: String,
+        /// The zero-based position in the load order.
+        #[arg(long)]
+        to: usize,
+    },
 
     /// Uninstalls a mod from the server.
     ///
@@ -144,7 +443,10 @@ enum ModCommands {
     /// ```bash
     /// dayz-tool-cli mod uninstall <modName>
     /// ```
-    Uninstall,
+    Uninstall {
+        /// The installed mods to uninstall, selected interactively if omitted.
+        mods: Vec<String>,
+    },
 
     /// Lists all installed mods.
     ///
@@ -152,8 +454,60 @@ enum ModCommands {
     ///
     /// ```bash
     /// dayz-tool-cli mod list
+    /// dayz-tool-cli mod list --outdated
+    /// dayz-tool-cli mod list --markdown
+    /// dayz-tool-cli mod list --with-keys
+    /// dayz-tool-cli mod list --tag <tag>
     /// ```
-    List,
+    List {
+        /// Annotates each mod with whether it is up to date with the workshop.
+        #[arg(long)]
+        outdated: bool,
+
+        /// Prints a Markdown list of the mods with their Steam Workshop links.
+        #[arg(long, conflicts_with = "outdated")]
+        markdown: bool,
+
+        /// Shows whether the keys of each mod are in the server's keys folder.
+        #[arg(long, conflicts_with_all = ["outdated", "markdown"])]
+        with_keys: bool,
+
+        /// Only lists the mods carrying this tag.
+        #[arg(long)]
+        tag: Option<String>,
+    },
+
+    /// Shows every installed and available workshop mod with its status.
+    ///
+    /// Installed mods are marked as up to date, outdated or missing from the workshop,
+    /// workshop mods that are not installed as available.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod status
+    /// ```
+    Status {
+        /// How many folder levels of the workshop path are searched for mods.
+        #[arg(long, value_name = "DEPTH", default_value_t = 1)]
+        workshop_scan_depth: usize,
+    },
+
+    /// Assigns tags to an installed mod, replacing its previous tags.
+    ///
+    /// Without tags, the mod's tags are removed.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod tag <modName> <tag>...
+    /// ```
+    Tag {
+        /// The name of the installed mod folder (e.g. @CF).
+        name: String,
+        /// The tags to assign (e.g. weapons vehicles).
+        tags: Vec<String>,
+    },
 
     /// Updates all installed mods.
     ///
@@ -161,8 +515,199 @@ enum ModCommands {
     ///
     /// ```bash
     /// dayz-tool-cli mod update
+    /// dayz-tool-cli mod update --exclude @CF --exclude @Trader
+    /// dayz-tool-cli mod update --fail-fast
+    /// dayz-tool-cli mod update --check --verbose
     /// ```
-    Update,
+    Update {
+        /// Skips the given mod during this update. Can be used multiple times.
+        #[arg(long, value_name = "NAME")]
+        exclude: Vec<String>,
+
+        /// Stops the update at the first failing mod and exits with an error.
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Lists the mods that would be updated without updating them.
+        ///
+        /// Exits with code 1 if any mod would be updated.
+        #[arg(long)]
+        check: bool,
+
+        /// With `--check`, shows the added, removed and changed files of each mod.
+        #[arg(long, requires = "check")]
+        verbose: bool,
+    },
+
+    /// Validates all XML files generated by the tool.
+    ///
+    /// Parses every types, spawnable types and events file in the `_ce` folders
+    /// of the current map and reports any files that fail to parse. Exits with code 1 if
+    /// any file fails, for use in scripts.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod validate-xml
+    /// ```
+    ValidateXml,
+
+    /// Moves CE data stored under an outdated short name to the current one.
+    ///
+    /// Matches the `_ce` folders that belong to no installed mod with the CE entries of
+    /// the installed mods and renames them, including their cfgeconomycore.xml entries.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod recompute-shortnames
+    /// ```
+    RecomputeShortnames,
+
+    /// Checks that all CE files referenced in cfgeconomycore.xml exist.
+    ///
+    /// Reports every `<file>` of a `<ce>` folder that is missing on disk, as the server
+    /// fails to load its economy for it. Exits with code 1 if any file is missing.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod check-ce
+    /// ```
+    CheckCe,
+
+    /// Copies the installed mods into a snapshot in the workdir's `.dayz-backups` folder.
+    ///
+    /// Take a snapshot before `mod update` to be able to roll back with `mod restore`.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod backup
+    /// ```
+    Backup,
+
+    /// Checks the installed mods against the workshop without updating them.
+    ///
+    /// Prints each mod with `OK`, `OUTDATED`, `MISSING IN WORKSHOP` or
+    /// `MISSING IN WORKDIR`. Exits with code 1 if any mod is outdated or its folder was
+    /// deleted from the workdir, for use in scheduled health checks.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod verify
+    /// ```
+    Verify,
+
+    /// Stores the file checksums of the installed mods as a known-good baseline.
+    ///
+    /// The baseline is written to `.dayz-baseline.json` in the workdir and replaces the
+    /// previous one. Compare against it with `mod check-drift`.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod freeze
+    /// ```
+    Freeze,
+
+    /// Reports the installed mods whose files changed since `mod freeze`.
+    ///
+    /// Lists the added, removed and changed files of each mod and exits with code 1 if
+    /// any mod changed, to detect tampering or manual edits.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod check-drift
+    /// ```
+    CheckDrift,
+
+    /// Copies the mods of a snapshot created by `mod backup` back into the workdir.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod restore [<snapshot>]
+    /// ```
+    Restore {
+        /// The snapshot folder in `.dayz-backups` to restore, selected interactively if omitted.
+        snapshot: Option<String>,
+    },
+
+    /// Checks that the keys of all installed mods are present in the server's keys folder.
+    ///
+    /// Reads `verifySignatures` from `serverDZ.cfg` and reports the mods whose clients
+    /// would be kicked for a signature mismatch. Exits with code 1 if any mod is reported.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod check-signatures
+    /// ```
+    CheckSignatures,
+
+    /// Prints the dependencies between the installed mods as a DOT graph.
+    ///
+    /// Dependencies are read from the profile's `modDependencies` (or the `dependsOn`
+    /// entries of a loadout). Missing dependencies are highlighted in red.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod deps-graph | dot -Tsvg -o mods.svg
+    /// ```
+    DepsGraph,
+
+    /// Scales the nominal and lifetime values in the types file of a mod.
+    ///
+    /// The values are multiplied by the given factors, rounded and clamped to valid
+    /// ranges. Updating or reinstalling the mod restores its original types.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod scale-types <name> --nominal 2.0 --lifetime 0.5
+    /// ```
+    ScaleTypes {
+        /// The installed mod whose types are scaled.
+        name: String,
+        /// The factor for the nominal values.
+        #[arg(long, value_name = "FACTOR", required_unless_present = "lifetime")]
+        nominal: Option<f64>,
+        /// The factor for the lifetime values.
+        #[arg(long, value_name = "FACTOR")]
+        lifetime: Option<f64>,
+    },
+
+    /// Shows the loot types of all installed mods combined.
+    ///
+    /// Reports the number of unique items, the types defined by more than one mod and
+    /// the number of items per category. Nothing is written.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod economy-preview [--vanilla <types.xml>]
+    /// ```
+    EconomyPreview {
+        /// The vanilla types file to include, loaded before the mods.
+        #[arg(long, value_name = "FILE")]
+        vanilla: Option<PathBuf>,
+    },
+
+    /// Rebuilds the CE entries of all installed mods in cfgeconomycore.xml.
+    ///
+    /// Removes all CE blocks managed by the tool and re-adds them based on the installed
+    /// mods and their existing `_ce` folders. Vanilla and manual entries are preserved.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli mod rebuild-ce
+    /// ```
+    RebuildCe,
 }
 
 #[derive(Subcommand)]
@@ -176,6 +721,17 @@ enum ProfileCommands {
     /// ```
     Show,
 
+    /// Prints the name of the active profile.
+    ///
+    /// Exits with a non-zero status if no profile is active.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli profile current
+    /// ```
+    Current,
+
     /// Updates the profile settings.
     ///
     /// # Usage
@@ -220,31 +776,86 @@ enum ProfileCommands {
     /// dayz-tool-cli profile use <profileName>
     /// ```
     Use,
+
+    /// Imports profiles from a JSON file.
+    ///
+    /// The file may contain a single profile or a whole configuration. All profiles
+    /// are validated before they are imported.
+    ///
+    /// # Usage
+    ///
+    /// ```bash
+    /// dayz-tool-cli profile import <path>
+    /// ```
+    Import {
+        /// The path to the JSON file to import.
+        path: PathBuf,
+    },
 }
 
 fn main() {
     inquire::set_global_render_config(get_render_config());
 
-    if let Err(e) = init_logger() {
+    // The arguments are parsed once the config exists, but errors before that point
+    // must already honor `--json-errors`.
+    set_json_errors(std::env::args().any(|arg| arg == "--json-errors"));
+    let config_path = match get_config_path() {
+        Ok(path) => path,
+        Err(e) => {
+            report_error(
+                "Failed to find the configuration directory",
+                &e,
+                &ErrorContext::default(),
+            );
+            eprintln!("Failed to find the configuration directory: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let stored_profile = get_profile(&config_path);
+    let profile = stored_profile.clone().and_then(resolve_profile_paths);
+
+    let log_dir = match get_log_dir(profile.as_ref().ok()) {
+        Ok(dir) => dir,
+        Err(e) => {
+            eprintln!("Failed to initialize logger: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = init_logger(&log_dir) {
         eprintln!("Failed to initialize logger: {}", e);
         std::process::exit(1);
     }
 
-    let config_path = get_config_path();
-    let profile = get_profile(&config_path);
+    set_interactive(std::io::stdin().is_terminal());
 
     if !config_path.exists() {
         match create_initial_profile(&config_path) {
             Ok(_) => info!("Initial profile created successfully! You can now use the CLI. Run `dayz-tool-cli --help` for more information."),
-            Err(_) => error!("Failed creating initial profile"),
+            Err(e) => error!("Failed creating initial profile: {}", e),
         }
     } else {
         let args = Cli::parse();
+        set_dry_run(args.dry_run);
+        set_json_errors(args.json_errors);
+        set_summary_only(args.summary_only);
+        if let Some(interactive) = args.interactive {
+            set_interactive(interactive);
+        }
+        let context = ErrorContext {
+            config_path: config_path.clone(),
+            profile: stored_profile.as_ref().ok().map(|p| p.name.clone()),
+        };
+        if let Err(e) = install_cancel_handler() {
+            debug!("Failed to install the Ctrl-C handler: {}", e);
+        }
         match &args.commands {
             Commands::Generate { subcommands } => match subcommands {
-                GenerateCommands::Guid { id } => match id {
-                    Some(id) => {
-                        let guid = generate_guid(id);
+                GenerateCommands::Guid {
+                    id: Some(id),
+                    output: None,
+                    ..
+                } => match generate_guid(id) {
+                    Ok(guid) => {
                         debug!("The GUID form {} is: {}", id, guid);
                         println!(
                             "The GUID from {} is: {}",
@@ -252,98 +863,578 @@ fn main() {
                             THEME.value_bold(guid)
                         )
                     }
-                    None => error!("No ID provided"),
+                    Err(e) => {
+                        report_error("Failed to generate the GUID", &e, &context);
+                        std::process::exit(1);
+                    }
                 },
-                GenerateCommands::Dnc { day, night } => {
-                    if let (Some(day), Some(night)) = (day, night) {
+                GenerateCommands::Guid { id, file, output } => {
+                    let content = match (id, file) {
+                        (Some(id), _) => Ok(id.clone()),
+                        (None, Some(file)) => {
+                            std::fs::read_to_string(file).map_err(|_| GuidError::ReadError)
+                        }
+                        (None, None) if !std::io::stdin().is_terminal() => {
+                            std::io::read_to_string(std::io::stdin())
+                                .map_err(|_| GuidError::ReadError)
+                        }
+                        (None, None) => {
+                            error!("No ID provided");
+                            std::process::exit(1);
+                        }
+                    };
+                    let content = match content {
+                        Ok(content) => content,
+                        Err(e) => {
+                            report_error("Failed to read the IDs", &e, &context);
+                            std::process::exit(1);
+                        }
+                    };
+
+                    let (pairs, errors) = generate_guid_pairs(&content);
+                    for (id, guid) in &pairs {
+                        println!("{} -> {}", THEME.value_italic(id), THEME.value_bold(guid));
+                    }
+                    for e in &errors {
+                        error!("Skipping invalid ID: {}", e);
+                    }
+                    if let Some(output) = output {
+                        if let Err(e) = write_guid_pairs(output, &pairs) {
+                            report_error("Failed to write the GUIDs", &e, &context);
+                            std::process::exit(1);
+                        }
+                    }
+                    if !errors.is_empty() {
+                        std::process::exit(1);
+                    }
+                }
+                GenerateCommands::Admins { file, output } => {
+                    if let Err(e) = generate_admins(file, output) {
+                        report_error("Failed to generate the admin list", &e, &context);
+                        std::process::exit(1);
+                    }
+                }
+                GenerateCommands::Dnc {
+                    day,
+                    night,
+                    table,
+                    write,
+                    reverse,
+                    time_accel,
+                    night_accel,
+                } => {
+                    if *reverse {
+                        let (time_accel, night_accel) = (
+                            time_accel.unwrap_or_default(),
+                            night_accel.unwrap_or_default(),
+                        );
+                        match calculate_dnc_reverse(time_accel, night_accel) {
+                            Ok((day_length, night_length)) => {
+                                info!("Day length = {}", day_length);
+                                info!("Night length = {}", night_length);
+                            }
+                            Err(e) => report_error(
+                                "Failed to calculate the day and night length",
+                                &e,
+                                &context,
+                            ),
+                        }
+                    } else if *table {
+                        println!("{}", dnc_table());
+                    } else if let (Some(day), Some(night)) = (day, night) {
                         match calculate_dnc(day, night) {
                             Ok((day_duration, night_duration)) => {
                                 info!("serverTimeAcceleration = {}", day_duration);
                                 info!("serverNightTimeAcceleration = {}", night_duration);
+
+                                let (day_time, night_time) =
+                                    reverse_dnc(day_duration, night_duration);
+                                info!(
+                                    "Verified: {} day / {} night",
+                                    format_duration(day_time),
+                                    format_duration(night_time)
+                                );
+
+                                if *write {
+                                    match &profile {
+                                        Ok(profile) => {
+                                            let cfg_path = Path::new(&profile.workdir_path)
+                                                .join("serverDZ.cfg");
+                                            match write_dnc(&cfg_path, day_duration, night_duration)
+                                            {
+                                                Ok(_) if is_dry_run() => {}
+                                                Ok(changes) if changes.is_empty() => info!(
+                                                    "{} is already up to date",
+                                                    cfg_path.display()
+                                                ),
+                                                Ok(changes) => info!(
+                                                    "Updated {} line(s) in {}",
+                                                    changes.len(),
+                                                    cfg_path.display()
+                                                ),
+                                                Err(e) => report_error(
+                                                    "Failed to write serverDZ.cfg",
+                                                    &e,
+                                                    &context,
+                                                ),
+                                            }
+                                        }
+                                        Err(e) => report_error("No profile found", e, &context),
+                                    }
+                                }
                             }
-                            Err(e) => error!("{}", e),
+                            Err(e) => report_error(
+                                "Failed to calculate the Day/Night cycle",
+                                &e,
+                                &context,
+                            ),
                         }
                     } else {
                         error!("Please enter both the day and night length.");
                     }
                 }
-                GenerateCommands::StartUp => match profile {
-                    Ok(profile) => match generate_startup_script(profile) {
-                        Ok(_) => info!("Startup script generated successfully!"),
-                        Err(_) => error!("Failed to generate startup script"),
+                GenerateCommands::StartUp {
+                    output,
+                    verify: true,
+                    ..
+                } => match profile {
+                    Ok(profile) => match verify_startup_script(&profile, output.as_deref()) {
+                        Ok(true) => info!("Startup script matches its recorded hash"),
+                        Ok(false) => {
+                            error!("Startup script was modified since its hash was recorded");
+                            std::process::exit(1);
+                        }
+                        Err(e) => {
+                            report_error("Failed to verify startup script", &e, &context);
+                            std::process::exit(1);
+                        }
                     },
-                    Err(_) => error!("No profile found"),
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                GenerateCommands::StartUp {
+                    port,
+                    output,
+                    sidecar,
+                    ..
+                } => match profile {
+                    Ok(profile) => match generate_startup_script(profile, *port, output.as_deref())
+                    {
+                        Ok(script) => {
+                            info!(
+                                "Startup script generated successfully: {}",
+                                script.path.display()
+                            );
+                            if *sidecar {
+                                match write_script_hash(&script) {
+                                    Ok(path) => info!("Script hash written to {}", path.display()),
+                                    Err(e) => {
+                                        report_error("Failed to write script hash", &e, &context)
+                                    }
+                                }
+                            }
+                        }
+                        Err(e) => report_error("Failed to generate startup script", &e, &context),
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
                 },
             },
-            Commands::Mods { subcommands } => match subcommands {
-                ModCommands::Install => match profile {
+            Commands::Mods { map, subcommands } => match subcommands {
+                ModCommands::Install {
+                    mods,
+                    rename,
+                    loadout,
+                    prune,
+                    yes,
+                    no_ce_register,
+                    no_types,
+                    write_params,
+                    verify,
+                    workshop_scan_depth,
+                    summary,
+                } => match profile {
                     Ok(profile) => {
-                        match install_mods(&THREAD_POOL, profile) {
+                        let result = match loadout {
+                            Some(loadout) => install_loadout(
+                                &THREAD_POOL,
+                                profile,
+                                loadout,
+                                map.as_deref(),
+                                *prune,
+                                *yes,
+                                *write_params,
+                            ),
+                            None => install_mods(
+                                &THREAD_POOL,
+                                profile,
+                                &InstallOptions {
+                                    mods: mods.clone(),
+                                    rename: rename.clone(),
+                                    map: map.clone(),
+                                    register_ce: !*no_ce_register,
+                                    extract_types: !*no_types,
+                                    write_params: *write_params,
+                                    verify: *verify,
+                                    scan_depth: *workshop_scan_depth,
+                                    summary: *summary,
+                                },
+                            ),
+                        };
+                        match result {
                             Ok(mods) => {
                                 println!(
                                     "Please add this: {} to your startup parameters",
                                     THEME.value_bold(mods)
                                 )
                             }
-                            Err(_) => error!("Failed to install mods"),
+                            Err(e) => report_error("Failed to install mods", &e, &context),
                         };
                     }
-                    Err(_) => error!("No profile found"),
+                    Err(e) => report_error("No profile found", &e, &context),
                 },
-                ModCommands::Uninstall => match profile {
-                    Ok(profile) => match uninstall_mods(profile, &THREAD_POOL) {
-                        Ok(mods) => mods,
-                        Err(_) => error!("Failed to uninstall mods"),
+                ModCommands::Swap { old, new } => match profile {
+                    Ok(profile) => {
+                        match swap_mod(&THREAD_POOL, profile, old, new, map.as_deref()) {
+                            Ok(mods) => println!(
+                                "Please add this: {} to your startup parameters",
+                                THEME.value_bold(mods)
+                            ),
+                            Err(e) => report_error("Failed to swap mods", &e, &context),
+                        }
+                    }
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::MoveLoadOrder { name, to } => match profile {
+                    Ok(profile) => match move_load_order(profile, name, *to) {
+                        Ok(mods) => println!(
+                            "Please add this: {} to your startup parameters",
+                            THEME.value_bold(mods)
+                        ),
+                        Err(e) => report_error("Failed to move the mod", &e, &context),
                     },
-                    Err(_) => error!("No profile found"),
+                    Err(e) => report_error("No profile found", &e, &context),
                 },
-                ModCommands::List => match profile {
-                    Ok(profile) => match list_installed_mods(profile) {
-                        Ok(mods) => mods,
-                        Err(_) => error!("No mods found"),
+                ModCommands::InstallKeys { name } => match profile {
+                    Ok(profile) => match install_keys(profile, name) {
+                        Ok(_) => (),
+                        Err(e) => report_error("Failed to install keys", &e, &context),
                     },
-                    Err(_) => error!("No profile found"),
+                    Err(e) => report_error("No profile found", &e, &context),
                 },
-                ModCommands::Update => match profile {
-                    Ok(profile) => match update_mods(profile, &THREAD_POOL) {
-                        Ok(mods) => mods,
-                        Err(_) => error!("Failed to update mods"),
+                ModCommands::Uninstall { mods } => match profile {
+                    Ok(profile) => {
+                        match uninstall_mods(profile, &THREAD_POOL, map.as_deref(), mods) {
+                            Ok(mods) => mods,
+                            Err(e) => report_error("Failed to uninstall mods", &e, &context),
+                        }
+                    }
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::Fetch { ids } => match profile {
+                    Ok(profile) => match fetch_mods(&THREAD_POOL, profile, ids, map.as_deref()) {
+                        Ok(mods) => println!(
+                            "Please add this: {} to your startup parameters",
+                            THEME.value_bold(mods)
+                        ),
+                        Err(e) => report_error("Failed to fetch mods", &e, &context),
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::List {
+                    outdated,
+                    markdown,
+                    with_keys,
+                    tag,
+                } => match profile {
+                    Ok(profile) => {
+                        match list_installed_mods(
+                            profile,
+                            *outdated,
+                            *markdown,
+                            *with_keys,
+                            tag.as_deref(),
+                        ) {
+                            Ok(mods) => mods,
+                            Err(e) => report_error("No mods found", &e, &context),
+                        }
+                    }
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::Status {
+                    workshop_scan_depth,
+                } => match profile {
+                    Ok(profile) => match show_mod_status(&profile, *workshop_scan_depth) {
+                        Ok(_) => (),
+                        Err(e) => report_error("Failed to show the mod status", &e, &context),
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::Tag { name, tags } => match profile {
+                    Ok(profile) => match tag_mod(profile, name, tags) {
+                        Ok(_) => (),
+                        Err(e) => report_error("Failed to tag the mod", &e, &context),
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::Update {
+                    exclude,
+                    check: true,
+                    verbose,
+                    ..
+                } => match profile {
+                    Ok(profile) => match check_updates(&profile, &THREAD_POOL, exclude, *verbose) {
+                        Ok(outdated) => {
+                            if !outdated.is_empty() {
+                                std::process::exit(1);
+                            }
+                        }
+                        Err(e) => {
+                            report_error("Failed to check for updates", &e, &context);
+                            std::process::exit(1);
+                        }
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::Update {
+                    exclude, fail_fast, ..
+                } => match profile {
+                    Ok(profile) => {
+                        match update_mods(
+                            profile,
+                            &THREAD_POOL,
+                            map.as_deref(),
+                            exclude,
+                            *fail_fast,
+                        ) {
+                            Ok(summary) => {
+                                info!(target: SUMMARY_TARGET, "Update finished: {}", summary)
+                            }
+                            Err(e) => report_error("Failed to update mods", &e, &context),
+                        }
+                    }
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::CheckSignatures => match profile {
+                    Ok(profile) => match check_signatures(&profile) {
+                        Ok(rejected) if rejected.is_empty() => {
+                            info!("All mods are signed with a key in keys/")
+                        }
+                        Ok(rejected) => {
+                            error!(
+                                "{} mod(s) would be rejected: {}",
+                                rejected.len(),
+                                rejected.join(", ")
+                            );
+                            std::process::exit(1);
+                        }
+                        Err(e) => report_error("Failed to check signatures", &e, &context),
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::EconomyPreview { vanilla } => match profile {
+                    Ok(profile) => {
+                        match economy_preview(&profile, map.as_deref(), vanilla.as_deref()) {
+                            Ok(preview) => print!("{}", preview),
+                            Err(e) => report_error("Failed to preview the economy", &e, &context),
+                        }
+                    }
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::ScaleTypes {
+                    name,
+                    nominal,
+                    lifetime,
+                } => match profile {
+                    Ok(profile) => {
+                        match scale_mod_types(&profile, name, map.as_deref(), *nominal, *lifetime) {
+                            Ok(count) => info!("Scaled {} types of {}", count, name),
+                            Err(e) => report_error("Failed to scale the types", &e, &context),
+                        }
+                    }
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::DepsGraph => match profile {
+                    Ok(profile) => println!("{}", format_dependency_graph(&profile)),
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::RebuildCe => match profile {
+                    Ok(profile) => match rebuild_ce(profile, map.as_deref()) {
+                        Ok(_) => (),
+                        Err(e) => {
+                            report_error("Failed to rebuild cfgeconomycore.xml", &e, &context)
+                        }
                     },
-                    Err(_) => error!("No profile found"),
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::ValidateXml => match profile {
+                    Ok(profile) => match validate_xml(profile, map.as_deref()) {
+                        Ok(failures) => {
+                            if !failures.is_empty() {
+                                std::process::exit(1);
+                            }
+                        }
+                        Err(e) => {
+                            report_error("Failed to validate XML files", &e, &context);
+                            std::process::exit(1);
+                        }
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::RecomputeShortnames => match profile {
+                    Ok(profile) => match recompute_short_names(&profile, map.as_deref()) {
+                        Ok(migrated) if migrated.is_empty() => {
+                            info!("No CE data needs to be migrated")
+                        }
+                        Ok(migrated) => info!("Migrated the CE data of {} mod(s)", migrated.len()),
+                        Err(e) => report_error("Failed to migrate the CE data", &e, &context),
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::CheckCe => match profile {
+                    Ok(profile) => match check_ce(&profile, map.as_deref()) {
+                        Ok(dangling) if dangling.is_empty() => {
+                            info!("All referenced CE files exist")
+                        }
+                        Ok(dangling) => {
+                            error!("{} referenced CE file(s) are missing", dangling.len());
+                            std::process::exit(1);
+                        }
+                        Err(e) => report_error("Failed to check the CE files", &e, &context),
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::Verify => match profile {
+                    Ok(profile) => match verify_mods(&profile, &THREAD_POOL) {
+                        Ok(statuses) => {
+                            if statuses.iter().any(|(_, status)| status.needs_update()) {
+                                std::process::exit(1);
+                            }
+                        }
+                        Err(e) => {
+                            report_error("Failed to verify the mods", &e, &context);
+                            std::process::exit(1);
+                        }
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::Freeze => match profile {
+                    Ok(profile) => {
+                        if let Err(e) = freeze_mods(&profile, &THREAD_POOL) {
+                            report_error("Failed to freeze the mods", &e, &context);
+                        }
+                    }
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::CheckDrift => match profile {
+                    Ok(profile) => match check_drift(&profile, &THREAD_POOL) {
+                        Ok(drifted) => {
+                            if !drifted.is_empty() {
+                                std::process::exit(1);
+                            }
+                        }
+                        Err(e) => {
+                            report_error("Failed to check for drift", &e, &context);
+                            std::process::exit(1);
+                        }
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::Backup => match profile {
+                    Ok(profile) => match backup_mods(&profile) {
+                        Ok(snapshot) => info!("Mods backed up to {}", snapshot.display()),
+                        Err(e) => report_error("Failed to back up the mods", &e, &context),
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ModCommands::Restore { snapshot } => match profile {
+                    Ok(profile) => match restore_mods(&profile, snapshot.as_deref()) {
+                        Ok(restored) => info!("Restored {} mod(s)", restored.len()),
+                        Err(e) => report_error("Failed to restore the mods", &e, &context),
+                    },
+                    Err(e) => report_error("No profile found", &e, &context),
                 },
             },
             Commands::Profile { subcommands } => match subcommands {
                 ProfileCommands::Show => match profile {
                     Ok(profile) => match show_profile(profile) {
                         Ok(_) => (),
-                        Err(_) => error!("Failed to show profile"),
+                        Err(e) => report_error("Failed to show profile", &e, &context),
                     },
-                    Err(_) => error!("No profile found"),
+                    Err(e) => report_error("No profile found", &e, &context),
+                },
+                ProfileCommands::Current => match current_profile(&config_path) {
+                    Ok(name) => println!("{}", name),
+                    Err(e) => {
+                        report_error("Failed to get the active profile", &e, &context);
+                        std::process::exit(1);
+                    }
                 },
-                ProfileCommands::Update => match profile {
+                ProfileCommands::Update => match stored_profile {
                     Ok(profile) => match update_profile(profile) {
                         Ok(_) => (),
-                        Err(_) => error!("Failed to update profile"),
+                        Err(e) => report_error("Failed to update profile", &e, &context),
                     },
-                    Err(_) => error!("No profile found"),
+                    Err(e) => report_error("No profile found", &e, &context),
                 },
                 ProfileCommands::Delete => match delete_profile(&config_path) {
                     Ok(_) => info!("Profile deleted successfully"),
-                    Err(_) => error!("Failed to delete profile"),
+                    Err(e) => report_error("Failed to delete profile", &e, &context),
                 },
                 ProfileCommands::Add => match create_profile(&config_path) {
                     Ok(_) => info!("Profile created successfully"),
-                    Err(_) => error!("Failed to create profile"),
+                    Err(e) => report_error("Failed to create profile", &e, &context),
                 },
                 ProfileCommands::List => match list_profiles(&config_path) {
                     Ok(_) => (),
-                    Err(_) => error!("Failed to list profiles"),
+                    Err(e) => report_error("Failed to list profiles", &e, &context),
                 },
                 ProfileCommands::Use => match switch_profile(&config_path) {
                     Ok(_) => info!("Profile switched successfully"),
-                    Err(_) => error!("Failed to switch profile"),
+                    Err(e) => report_error("Failed to switch profile", &e, &context),
+                },
+                ProfileCommands::Import { path } => match import_profiles(&config_path, path) {
+                    Ok(_) => info!("Profiles imported successfully"),
+                    Err(e) => report_error("Failed to import profiles", &e, &context),
                 },
             },
+            Commands::Config { subcommands } => match subcommands {
+                ConfigCommands::Format => match format_config(&config_path) {
+                    Ok(_) => info!("Configuration formatted: {}", config_path.display()),
+                    Err(e) => report_error("Failed to format the configuration", &e, &context),
+                },
+                ConfigCommands::RestoreProfile { backup_file, name } => {
+                    match restore_profile(&config_path, backup_file, name) {
+                        Ok(restored) => info!("Profile restored as '{}'", restored),
+                        Err(e) => report_error("Failed to restore the profile", &e, &context),
+                    }
+                }
+                ConfigCommands::SteamcmdPassword { password_stdin } => {
+                    match store_steamcmd_password(&config_path, *password_stdin) {
+                        Ok(_) => info!("SteamCMD password stored in the keyring"),
+                        Err(e) => {
+                            report_error("Failed to store the SteamCMD password", &e, &context)
+                        }
+                    }
+                }
+                ConfigCommands::Get { key } => match get_config_value(&config_path, key) {
+                    Ok(value) => println!("{}", value),
+                    Err(e) => {
+                        report_error("Failed to read the configuration value", &e, &context);
+                        std::process::exit(1);
+                    }
+                },
+            },
+            Commands::CleanBackups { older_than, yes } => match profile {
+                Ok(profile) => {
+                    if let Err(e) = clean_backups(&profile, *older_than, *yes) {
+                        report_error("Failed to clean backups", &e, &context);
+                    }
+                }
+                Err(e) => report_error("No profile found", &e, &context),
+            },
+        }
+
+        if let Err(e) = save_checksum_cache() {
+            debug!("Failed to save the checksum cache: {}", e);
         }
     }
 }
@@ -1,63 +1,241 @@
-use crate::{ConfigError, Profile, Root};
+use crate::{is_dry_run, require_interactive, ConfigError, Profile, Root};
 use inquire::ui::{Attributes, Color, RenderConfig, StyleSheet, Styled};
-use inquire::Text;
+use inquire::{Confirm, Text};
+use log::info;
+use regex::Regex;
 use serde_json::{to_string_pretty, Value};
 use std::env;
-use std::fs::{create_dir_all, File};
+use std::fs::{create_dir_all, read_to_string, rename, File};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 
 /// Returns the path to the configuration file.
 ///
-/// The configuration file is located in the `.dayz-tool` directory in the user's home directory.
+/// The configuration directory is searched for with `find_config_dir`. Inside it,
+/// `config.json` is used unless only a `config.toml` exists.
 ///
 /// # Example
 ///
 /// ```rust
-/// use std::path::PathBuf;
 /// use dayz_tool_cli::utils::get_config_path;
 ///
-/// let config_path = get_config_path();
+/// let config_path = get_config_path().unwrap();
 /// ```
-pub fn get_config_path() -> PathBuf {
-    let home_dir = match env::var("HOME") {
-        Ok(path) => PathBuf::from(path),
-        Err(_) => match env::var("USERPROFILE") {
-            Ok(path) => PathBuf::from(path),
-            Err(_) => {
-                panic!("Failed to get the user's home directory.");
+pub fn get_config_path() -> Result<PathBuf, ConfigError> {
+    let config_dir = find_config_dir(
+        |name| env::var(name).ok(),
+        cfg!(target_os = "linux"),
+        env::current_dir().ok(),
+    )
+    .ok_or(ConfigError::NoConfigDir)?;
+
+    let json_path = config_dir.join("config.json");
+    let toml_path = config_dir.join("config.toml");
+
+    if !json_path.exists() && toml_path.exists() {
+        return Ok(toml_path);
+    }
+
+    Ok(json_path)
+}
+
+/// Finds the configuration directory, looking up environment variables with `lookup`.
+///
+/// The directories are tried in this order:
+///
+/// 1. The directory set in `DAYZ_TOOL_CONFIG`.
+/// 2. `$XDG_CONFIG_HOME/dayz-tool` on Linux, unless it does not exist yet and a
+///    `.dayz-tool` directory in the home directory does, so existing setups keep working.
+/// 3. `.dayz-tool` in the home directory (`HOME` or `USERPROFILE`).
+/// 4. `.dayz-tool` in `current_dir`.
+fn find_config_dir(
+    lookup: impl Fn(&str) -> Option<String>,
+    use_xdg: bool,
+    current_dir: Option<PathBuf>,
+) -> Option<PathBuf> {
+    let non_empty = |name: &str| lookup(name).filter(|value| !value.is_empty());
+
+    if let Some(dir) = non_empty("DAYZ_TOOL_CONFIG") {
+        return Some(PathBuf::from(dir));
+    }
+
+    let home_dir = non_empty("HOME")
+        .or_else(|| non_empty("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(".dayz-tool"));
+
+    if use_xdg {
+        if let Some(xdg_dir) = non_empty("XDG_CONFIG_HOME") {
+            let xdg_dir = PathBuf::from(xdg_dir).join("dayz-tool");
+            if xdg_dir.is_dir() || !home_dir.as_ref().is_some_and(|dir| dir.is_dir()) {
+                return Some(xdg_dir);
             }
-        },
-    };
+        }
+    }
 
-    let mut config_path = home_dir;
-    config_path.push(".dayz-tool");
-    config_path.push("config.json");
+    home_dir.or_else(|| current_dir.map(|dir| dir.join(".dayz-tool")))
+}
 
+/// Returns whether the configuration file is written in TOML instead of JSON.
+fn is_toml(config_path: &Path) -> bool {
     config_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"))
+}
+
+/// Returns the user's home directory from the `HOME` or `USERPROFILE` environment variable.
+fn get_home_dir() -> Option<PathBuf> {
+    env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .ok()
+        .map(PathBuf::from)
+}
+
+/// Resolves the workdir and workshop paths of a profile.
+///
+/// Environment variables (`$VAR`, `${VAR}` or `%VAR%`) are expanded, paths starting
+/// with `~` are expanded to the user's home directory and relative paths (e.g. `.` or
+/// `./server`) are resolved against the current working directory, so relative configs
+/// behave predictably. Absolute paths are left unchanged. The script directory is
+/// resolved the same way, if set.
+///
+/// The resolved profile is meant for working with the files only; the profile stored
+/// in the configuration file keeps the paths as entered.
+///
+/// # Errors
+/// Returns `ConfigError::UnknownEnvVar` if a path uses an environment variable that is
+/// not set.
+pub fn resolve_profile_paths(mut profile: Profile) -> Result<Profile, ConfigError> {
+    let cwd = env::current_dir().unwrap_or_default();
+    let home = get_home_dir();
+
+    profile.workdir_path = resolve_path(&profile.workdir_path, &cwd, home.as_deref())?;
+    profile.workshop_path = resolve_path(&profile.workshop_path, &cwd, home.as_deref())?;
+    if let Some(script_dir) = &profile.script_dir {
+        profile.script_dir = Some(resolve_path(script_dir, &cwd, home.as_deref())?);
+    }
+    Ok(profile)
+}
+
+/// Resolves a single path against the given working and home directory.
+///
+/// Environment variables are expanded first. The `.` and `..` components of the
+/// resulting path are normalized lexically, so the path does not need to exist.
+fn resolve_path(path: &str, cwd: &Path, home: Option<&Path>) -> Result<String, ConfigError> {
+    if path.is_empty() {
+        return Ok(path.to_string());
+    }
+
+    let path = expand_env_vars(path, |name| env::var(name).ok())?;
+    let path = path.as_str();
+
+    let expanded = match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+            home.join(rest.trim_start_matches(['/', '\\']))
+        }
+        _ => PathBuf::from(path),
+    };
+
+    if expanded.is_absolute() {
+        return Ok(expanded.to_string_lossy().to_string());
+    }
+
+    let mut resolved = cwd.to_path_buf();
+    for component in expanded.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => resolved.push(other),
+        }
+    }
+
+    Ok(resolved.to_string_lossy().to_string())
+}
+
+/// Expands the `$VAR`, `${VAR}` and `%VAR%` environment variables in a path.
+///
+/// The values are looked up with `lookup`. A `$` or `%` that does not start a variable
+/// is kept as is.
+fn expand_env_vars(
+    path: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, ConfigError> {
+    let re = Regex::new(r"\$\{([^}]+)\}|\$([A-Za-z_][A-Za-z0-9_]*)|%([^%\s/\\]+)%").unwrap();
+    let mut expanded = String::with_capacity(path.len());
+    let mut last = 0;
+
+    for caps in re.captures_iter(path) {
+        let whole = caps.get(0).unwrap();
+        let name = caps
+            .get(1)
+            .or_else(|| caps.get(2))
+            .or_else(|| caps.get(3))
+            .unwrap()
+            .as_str();
+        let value = lookup(name).ok_or_else(|| ConfigError::UnknownEnvVar(name.to_string()))?;
+
+        expanded.push_str(&path[last..whole.start()]);
+        expanded.push_str(&value);
+        last = whole.end();
+    }
+
+    expanded.push_str(&path[last..]);
+    Ok(expanded)
+}
+
+/// The environment variable naming the profile to use instead of the active one.
+const PROFILE_ENV_VAR: &str = "DAYZ_TOOL_PROFILE";
+
+/// Returns the profile name set in `DAYZ_TOOL_PROFILE`, if any.
+fn profile_override() -> Option<String> {
+    env::var(PROFILE_ENV_VAR)
+        .ok()
+        .filter(|name| !name.trim().is_empty())
+}
+
+/// Returns the index of the profile commands work on.
+///
+/// This is the profile named by `override_name` if given, or the active profile otherwise.
+/// The override does not change the stored `is_active` flags.
+fn selected_profile_index(
+    profiles: &[Profile],
+    override_name: Option<&str>,
+) -> Result<usize, ConfigError> {
+    match override_name {
+        Some(name) => profiles
+            .iter()
+            .position(|p| p.name == name)
+            .ok_or_else(|| ConfigError::UnknownProfileOverride(name.to_string())),
+        None => profiles
+            .iter()
+            .position(|p| p.is_active)
+            .ok_or(ConfigError::NoActiveProfile),
+    }
 }
 
 /// Retrieves the active profile from the configuration file.
 ///
 /// This function reads the configuration file from the given path and returns the active profile.
-/// If the configuration file cannot be read or parsed, or if no active profile is found, an appropriate
+/// If `DAYZ_TOOL_PROFILE` is set, the profile of that name is returned instead, without
+/// changing which profile is stored as active.
+/// If the configuration file cannot be read or parsed, or if no matching profile is found, an appropriate
 /// `ConfigError` is returned.
 ///
 /// # Example
 ///
 /// ```rust
-/// use std::path::PathBuf;
 /// use dayz_tool_cli::utils::{get_profile, get_config_path};
 ///
-/// let profile = get_profile(&get_config_path());
+/// let profile = get_profile(&get_config_path().unwrap());
 /// ```
 pub fn get_profile(config_path: &PathBuf) -> Result<Profile, ConfigError> {
     let config = read_config_file(config_path)?;
 
-    let profiles = config.profiles;
-    let active_profile = profiles.iter().find(|profile| profile.is_active);
+    let mut profiles = config.profiles;
+    let index = selected_profile_index(&profiles, profile_override().as_deref())?;
 
-    Ok(active_profile.unwrap().clone())
+    Ok(profiles.swap_remove(index))
 }
 
 /// Retrieves all profiles from the DayZ configuration file.
@@ -90,10 +268,7 @@ pub fn remove_profile(config_path: &PathBuf, profile: &Profile) -> Result<(), Co
         if p.name == profile.name {
             let mut config = read_config_file(config_path)?;
             config.profiles.remove(i);
-            let json = to_string_pretty(&config).unwrap();
-            let mut file = File::create(config_path).unwrap();
-            file.write_all(json.as_bytes()).unwrap();
-            return Ok(());
+            return write_config_file(config_path, &config);
         }
     }
 
@@ -126,10 +301,7 @@ pub fn switch_active_profile(config_path: &PathBuf, profile: &Profile) -> Result
                 p.is_active = false;
             }
             config.profiles[i].is_active = true;
-            let json = to_string_pretty(&config).unwrap();
-            let mut file = File::create(config_path).unwrap();
-            file.write_all(json.as_bytes()).unwrap();
-            return Ok(());
+            return write_config_file(config_path, &config);
         }
     }
 
@@ -153,41 +325,64 @@ pub fn add_profile(config_path: &PathBuf, profile: &Profile) -> Result<(), Confi
 
     config.profiles.push(profile.clone());
 
-    let json = to_string_pretty(&config).unwrap();
-
-    if let Err(e) = create_dir_all(config_path.parent().unwrap()) {
-        eprintln!("Failed to create directory: {}", e);
-        return Err(ConfigError::CreateFileError);
-    }
-
-    let mut config_file = match File::create(config_path) {
-        Ok(file) => file,
-        Err(_) => return Err(ConfigError::CreateFileError),
-    };
-
-    if config_file.write_all(json.as_bytes()).is_err() {
-        return Err(ConfigError::WriteFileError);
-    }
-
-    Ok(())
+    write_config_file(config_path, &config)
 }
 
 /// Reads the configuration file and returns the parsed configuration.
 ///
 /// This function takes a path to the configuration file, reads its contents, and parses it into a `Root` object.
+/// Files with a `.toml` extension are parsed as TOML, all others as JSON.
 /// If the configuration file cannot be opened or parsed, an appropriate `ConfigError` is returned.
 pub fn read_config_file(config_path: &PathBuf) -> Result<Root, ConfigError> {
-    let config_file = match File::open(config_path) {
-        Ok(file) => file,
+    let content = match read_to_string(config_path) {
+        Ok(content) => content,
         Err(_) => return Err(ConfigError::OpenFileError),
     };
 
-    let config: Root = match serde_json::from_reader(config_file) {
-        Ok(config) => config,
-        Err(_) => return Err(ConfigError::ParseError),
+    if is_toml(config_path) {
+        toml::from_str(&content).map_err(|_| ConfigError::ParseError)
+    } else {
+        serde_json::from_str(&content).map_err(|_| ConfigError::ParseError)
+    }
+}
+
+/// Writes the given configuration to the configuration file.
+///
+/// This function serializes the `Root` object and writes it to the given path, creating the
+/// parent directory if necessary. The format follows the extension of the path, like in
+/// `read_config_file`. The content is written to a temporary file first and then renamed
+/// over the configuration file, so an interrupted write never leaves a truncated config.
+/// In dry-run mode the intended change is only logged.
+/// If any error occurs during the process, an appropriate `ConfigError` is returned.
+pub fn write_config_file(config_path: &PathBuf, config: &Root) -> Result<(), ConfigError> {
+    let content = if is_toml(config_path) {
+        toml::to_string_pretty(config).map_err(|_| ConfigError::SerializeError)?
+    } else {
+        to_string_pretty(config).map_err(|_| ConfigError::SerializeError)?
     };
 
-    Ok(config)
+    if is_dry_run() {
+        info!("[dry-run] Would write {}", config_path.display());
+        return Ok(());
+    }
+
+    if let Err(e) = create_dir_all(config_path.parent().unwrap()) {
+        eprintln!("Failed to create directory: {}", e);
+        return Err(ConfigError::CreateFileError);
+    }
+
+    let mut temp_path = config_path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut config_file = File::create(&temp_path).map_err(|_| ConfigError::CreateFileError)?;
+    config_file
+        .write_all(content.as_bytes())
+        .and_then(|_| config_file.sync_all())
+        .map_err(|_| ConfigError::WriteFileError)?;
+    rename(&temp_path, config_path).map_err(|_| ConfigError::WriteFileError)?;
+
+    Ok(())
 }
 
 /// Creates an initial profile by prompting the user for profile details.
@@ -197,6 +392,7 @@ pub fn read_config_file(config_path: &PathBuf) -> Result<Root, ConfigError> {
 /// to the configuration file. If any error occurs during the process, an appropriate `ConfigError`
 /// is returned.
 pub fn create_initial_profile(config_path: &PathBuf) -> Result<(), ConfigError> {
+    require_interactive("a configuration file or DAYZ_TOOL_CONFIG")?;
     println!("It's looks like this is your first time using dayz-tool-cli!");
     println!("Let's create your first profile");
     let name = Text::new("Please enter a name.")
@@ -204,9 +400,9 @@ pub fn create_initial_profile(config_path: &PathBuf) -> Result<(), ConfigError>
         .prompt()
         .expect("Failed to get name");
 
-    let workdir_path = Text::new("What's your workdir path?").with_help_message("Please enter the path to your DayZ server's working directory. (e.g. /home/user/DayZServer)").prompt().expect("Failed to get workdir path");
+    let workdir_path = prompt_path("What's your workdir path?", "Please enter the path to your DayZ server's working directory. (e.g. /home/user/DayZServer)");
 
-    let workshop_path = Text::new("What's your !Workshop path?").with_help_message("Please enter the path to your DayZ server's workshop directory. (e.g. for the DayZ Standalone Launcher /path/to/steam/steamapps/common/DayZ/!Workshop)").prompt().expect("Failed to get workshop path");
+    let workshop_path = prompt_path("What's your !Workshop path?", "Please enter the path to your DayZ server's workshop directory. (e.g. for the DayZ Standalone Launcher /path/to/steam/steamapps/common/DayZ/!Workshop)");
 
     let profile = Profile {
         name,
@@ -215,6 +411,7 @@ pub fn create_initial_profile(config_path: &PathBuf) -> Result<(), ConfigError>
         installed_mods: vec![],
         start_parameters: Some("".to_string()),
         is_active: true,
+        ..Default::default()
     };
 
     add_profile(config_path, &profile)?;
@@ -222,25 +419,59 @@ pub fn create_initial_profile(config_path: &PathBuf) -> Result<(), ConfigError>
     Ok(())
 }
 
+/// Prompts for a directory path of a new profile.
+///
+/// If the entered path does not exist, the user is asked whether to keep it anyway (e.g.
+/// for a server that is not set up yet) or to enter it again.
+pub fn prompt_path(message: &str, help_message: &str) -> String {
+    read_existing_path(
+        || {
+            Text::new(message)
+                .with_help_message(help_message)
+                .prompt()
+                .expect("Failed to get path")
+        },
+        |path| {
+            Confirm::new(&format!("{} does not exist. Use it anyway?", path))
+                .with_default(false)
+                .prompt()
+                .unwrap_or(false)
+        },
+    )
+}
+
+/// Reads paths until one exists or `accept_missing` accepts a missing one.
+///
+/// The paths are checked after resolving them like `resolve_profile_paths` does.
+fn read_existing_path(
+    mut read: impl FnMut() -> String,
+    mut accept_missing: impl FnMut(&str) -> bool,
+) -> String {
+    let cwd = env::current_dir().unwrap_or_default();
+    let home = get_home_dir();
+
+    loop {
+        let path = read();
+        let exists = resolve_path(&path, &cwd, home.as_deref())
+            .is_ok_and(|resolved| Path::new(&resolved).exists());
+        if exists || accept_missing(&path) {
+            return path;
+        }
+    }
+}
+
 /// Saves a given profile to the configuration file.
 ///
 /// This function updates the active profile in the configuration file with the
 /// data from the provided profile. The profile to be updated is identified by
-/// the `is_active` flag.
+/// the `is_active` flag, or by `DAYZ_TOOL_PROFILE` if it is set.
 pub fn save_profile(profile: &Profile) -> Result<(), ConfigError> {
-    let config_path = get_config_path();
+    let config_path = get_config_path()?;
     let mut config = read_config_file(&config_path)?;
 
-    if let Some(existing_profile) = config.profiles.iter_mut().find(|p| p.is_active) {
-        *existing_profile = profile.clone();
-        let json = to_string_pretty(&config).map_err(|_| ConfigError::SerializeError)?;
-        let mut file = File::create(&config_path).map_err(|_| ConfigError::CreateFileError)?;
-        file.write_all(json.as_bytes())
-            .map_err(|_| ConfigError::WriteFileError)?;
-        Ok(())
-    } else {
-        Err(ConfigError::NoActiveProfile)
-    }
+    let index = selected_profile_index(&config.profiles, profile_override().as_deref())?;
+    config.profiles[index] = profile.clone();
+    write_config_file(&config_path, &config)
 }
 
 /// Adds a list of mods to the active profile in the configuration file.
@@ -249,29 +480,18 @@ pub fn save_profile(profile: &Profile) -> Result<(), ConfigError> {
 /// to the active profile's list of installed mods. If any error occurs during the process,
 /// an appropriate `ConfigError` is returned.
 pub fn add_mods_to_profile(mods: Vec<String>) -> Result<(), ConfigError> {
-    let config_path = get_config_path();
+    let config_path = get_config_path()?;
 
     let mut config = read_config_file(&config_path)?;
 
-    let active_profile = config
-        .profiles
-        .iter_mut()
-        .find(|p| p.is_active)
-        .ok_or(ConfigError::NoActiveProfile)?;
+    let index = selected_profile_index(&config.profiles, profile_override().as_deref())?;
+    let active_profile = &mut config.profiles[index];
 
     let mods_as_values: Vec<Value> = mods.into_iter().map(Value::String).collect();
 
     active_profile.installed_mods.extend(mods_as_values);
 
-    let json = to_string_pretty(&config).map_err(|_| ConfigError::SerializeError)?;
-
-    let mut config_file = File::create(&config_path).map_err(|_| ConfigError::CreateFileError)?;
-
-    config_file
-        .write_all(json.as_bytes())
-        .map_err(|_| ConfigError::WriteFileError)?;
-
-    Ok(())
+    write_config_file(&config_path, &config)
 }
 
 /// Removes specified mods from the active profile's installed mods list in the configuration file.
@@ -280,26 +500,105 @@ pub fn add_mods_to_profile(mods: Vec<String>) -> Result<(), ConfigError> {
 /// array of the active profile. The function handles the entire process of reading the current
 /// configuration, modifying it, and writing it back to disk.
 pub fn remove_mods_from_profile(mods_to_remove: &[String]) -> Result<(), ConfigError> {
-    let config_path = get_config_path();
+    let config_path = get_config_path()?;
     let mut config = read_config_file(&config_path)?;
 
-    let active_profile = config
-        .profiles
-        .iter_mut()
-        .find(|p| p.is_active)
-        .ok_or(ConfigError::NoActiveProfile)?;
+    let index = selected_profile_index(&config.profiles, profile_override().as_deref())?;
+    forget_mods(&mut config.profiles[index], mods_to_remove);
 
-    active_profile.installed_mods.retain(|mod_entry| {
+    write_config_file(&config_path, &config)
+}
+
+/// Removes the given mods and every per-mod setting of them from a profile.
+///
+/// Besides `installed_mods`, the mods are dropped from the sources, server-only mods,
+/// load order, manual CE mods, tags and dependencies.
+pub fn forget_mods(profile: &mut Profile, mods_to_remove: &[String]) {
+    profile.installed_mods.retain(|mod_entry| {
         !mods_to_remove.contains(&mod_entry.as_str().unwrap_or("").to_string())
     });
+    profile
+        .mod_sources
+        .retain(|name, _| !mods_to_remove.contains(name));
+    profile
+        .server_mods
+        .retain(|name| !mods_to_remove.contains(name));
+    profile
+        .load_order
+        .retain(|name| !mods_to_remove.contains(name));
+    profile
+        .manual_ce_mods
+        .retain(|name| !mods_to_remove.contains(name));
+    profile
+        .mod_tags
+        .retain(|name, _| !mods_to_remove.contains(name));
+    profile
+        .mod_dependencies
+        .retain(|name, _| !mods_to_remove.contains(name));
+}
 
-    let json = to_string_pretty(&config).map_err(|_| ConfigError::SerializeError)?;
-    let mut config_file = File::create(&config_path).map_err(|_| ConfigError::CreateFileError)?;
-    config_file
-        .write_all(json.as_bytes())
-        .map_err(|_| ConfigError::WriteFileError)?;
+/// Validates an imported configuration file and parses it into a `Root` object.
+///
+/// `serde` accepts many degenerate-but-valid JSON documents, so the raw JSON value is
+/// checked first. Every profile must have a non-empty name, both paths and an
+/// `installedMods` array of mod names. The first violation is returned as a detailed
+/// `ConfigError` naming the offending profile and field.
+pub fn validate_config(value: &Value) -> Result<Root, ConfigError> {
+    let profiles = value
+        .get("profiles")
+        .and_then(Value::as_array)
+        .ok_or_else(|| ConfigError::InvalidShape("profiles".to_string(), "an array".to_string()))?;
+
+    for (index, profile) in profiles.iter().enumerate() {
+        validate_profile(profile, index)?;
+    }
 
-    Ok(())
+    serde_json::from_value(value.clone()).map_err(|_| ConfigError::ParseError)
+}
+
+/// Validates a single imported profile and parses it into a `Profile` object.
+///
+/// See `validate_config` for the applied rules. `index` is the position of the profile
+/// in the imported file and is used for error reporting.
+pub fn validate_profile(value: &Value, index: usize) -> Result<Profile, ConfigError> {
+    if !value.is_object() {
+        return Err(ConfigError::InvalidShape(
+            format!("profile {}", index),
+            "an object".to_string(),
+        ));
+    }
+
+    let name = value
+        .get("name")
+        .and_then(Value::as_str)
+        .filter(|name| !name.trim().is_empty())
+        .ok_or(ConfigError::EmptyProfileName(index))?;
+
+    for (field, label) in [("workdirPath", "workdir"), ("workshopPath", "workshop")] {
+        let path = value.get(field).and_then(Value::as_str).unwrap_or("");
+        if path.trim().is_empty() {
+            return Err(ConfigError::MissingPath(
+                name.to_string(),
+                label.to_string(),
+            ));
+        }
+    }
+
+    let installed_mods = value
+        .get("installedMods")
+        .and_then(Value::as_array)
+        .ok_or_else(|| {
+            ConfigError::InvalidShape(
+                format!("installedMods of profile '{}'", name),
+                "an array".to_string(),
+            )
+        })?;
+
+    if let Some(position) = installed_mods.iter().position(|m| !m.is_string()) {
+        return Err(ConfigError::InvalidModEntry(name.to_string(), position));
+    }
+
+    serde_json::from_value(value.clone()).map_err(|_| ConfigError::ParseError)
 }
 
 /// Returns a customized render configuration for prompts.
@@ -328,3 +627,244 @@ pub fn get_render_config() -> RenderConfig<'static> {
 
     render_config
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StartupTemplate, SteamCmdConfig};
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn valid_profile() -> Value {
+        json!({
+            "name": "DayZTestServer",
+            "workdirPath": "/home/karnes/Servers/DayZTestServer",
+            "workshopPath": "/home/karnes/Servers/!Workshop",
+            "startParameters": "",
+            "installedMods": ["@CF"],
+            "isActive": true
+        })
+    }
+
+    #[test]
+    fn test_validate_config_valid() {
+        let config = validate_config(&json!({ "profiles": [valid_profile()] })).unwrap();
+        assert_eq!(config.profiles[0].name, "DayZTestServer");
+    }
+
+    #[test]
+    fn test_validate_profile_empty_name() {
+        let mut profile = valid_profile();
+        profile["name"] = json!("  ");
+        assert_eq!(
+            validate_config(&json!({ "profiles": [profile] })),
+            Err(ConfigError::EmptyProfileName(0))
+        );
+    }
+
+    #[test]
+    fn test_validate_profile_missing_path() {
+        let mut profile = valid_profile();
+        profile.as_object_mut().unwrap().remove("workshopPath");
+        assert_eq!(
+            validate_profile(&profile, 0),
+            Err(ConfigError::MissingPath(
+                "DayZTestServer".to_string(),
+                "workshop".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_validate_profile_wrong_mod_entry_type() {
+        let mut profile = valid_profile();
+        profile["installedMods"] = json!(["@CF", 42]);
+        assert_eq!(
+            validate_profile(&profile, 0),
+            Err(ConfigError::InvalidModEntry(
+                "DayZTestServer".to_string(),
+                1
+            ))
+        );
+    }
+
+    #[test]
+    fn test_validate_config_profiles_not_an_array() {
+        assert_eq!(
+            validate_config(&json!({ "profiles": {} })),
+            Err(ConfigError::InvalidShape(
+                "profiles".to_string(),
+                "an array".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_resolve_path_relative_to_cwd() {
+        let cwd = Path::new("/srv/dayz");
+        let home = Path::new("/home/karnes");
+
+        assert_eq!(resolve_path(".", cwd, Some(home)).unwrap(), "/srv/dayz");
+        assert_eq!(
+            resolve_path("./server", cwd, Some(home)).unwrap(),
+            "/srv/dayz/server"
+        );
+        assert_eq!(
+            resolve_path("../!Workshop", cwd, Some(home)).unwrap(),
+            "/srv/!Workshop"
+        );
+        assert_eq!(
+            resolve_path("~/Servers/DayZ", cwd, Some(home)).unwrap(),
+            "/home/karnes/Servers/DayZ"
+        );
+        assert_eq!(
+            resolve_path("/opt/dayz", cwd, Some(home)).unwrap(),
+            "/opt/dayz"
+        );
+    }
+
+    #[test]
+    fn test_find_config_dir() {
+        let base = std::env::temp_dir().join("dayz_tool_find_config_dir");
+        let _ = std::fs::remove_dir_all(&base);
+        let home = base.join("home");
+        let xdg = base.join("xdg");
+        let cwd = base.join("cwd");
+        let vars = |names: &[(&str, &Path)]| {
+            names
+                .iter()
+                .map(|(name, path)| (name.to_string(), path.to_str().unwrap().to_string()))
+                .collect::<BTreeMap<String, String>>()
+        };
+        let find = |vars: &BTreeMap<String, String>, use_xdg: bool| {
+            find_config_dir(|name| vars.get(name).cloned(), use_xdg, Some(cwd.clone()))
+        };
+
+        let all = vars(&[
+            ("DAYZ_TOOL_CONFIG", &base.join("custom")),
+            ("XDG_CONFIG_HOME", &xdg),
+            ("HOME", &home),
+        ]);
+        assert_eq!(find(&all, true), Some(base.join("custom")));
+
+        let xdg_and_home = vars(&[("XDG_CONFIG_HOME", &xdg), ("HOME", &home)]);
+        assert_eq!(find(&xdg_and_home, true), Some(xdg.join("dayz-tool")));
+        assert_eq!(find(&xdg_and_home, false), Some(home.join(".dayz-tool")));
+
+        std::fs::create_dir_all(home.join(".dayz-tool")).unwrap();
+        assert_eq!(find(&xdg_and_home, true), Some(home.join(".dayz-tool")));
+        std::fs::create_dir_all(xdg.join("dayz-tool")).unwrap();
+        assert_eq!(find(&xdg_and_home, true), Some(xdg.join("dayz-tool")));
+
+        let user_profile = vars(&[("USERPROFILE", &home)]);
+        assert_eq!(find(&user_profile, true), Some(home.join(".dayz-tool")));
+
+        assert_eq!(find(&BTreeMap::new(), true), Some(cwd.join(".dayz-tool")));
+        assert_eq!(find_config_dir(|_| None, true, None), None);
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_expand_env_vars() {
+        let lookup = |name: &str| match name {
+            "HOME" => Some("/home/karnes".to_string()),
+            "USERPROFILE" => Some("C:\\Users\\Karnes".to_string()),
+            _ => None,
+        };
+
+        assert_eq!(
+            expand_env_vars("$HOME/Servers/DayZ", lookup).unwrap(),
+            "/home/karnes/Servers/DayZ"
+        );
+        assert_eq!(
+            expand_env_vars("${HOME}/!Workshop", lookup).unwrap(),
+            "/home/karnes/!Workshop"
+        );
+        assert_eq!(
+            expand_env_vars("%USERPROFILE%\\Servers\\DayZ", lookup).unwrap(),
+            "C:\\Users\\Karnes\\Servers\\DayZ"
+        );
+        assert_eq!(
+            expand_env_vars("/srv/100%/$", lookup).unwrap(),
+            "/srv/100%/$"
+        );
+        assert_eq!(
+            expand_env_vars("$DAYZ_ROOT/server", lookup),
+            Err(ConfigError::UnknownEnvVar("DAYZ_ROOT".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_read_existing_path_retries_missing_path() {
+        let good = env::temp_dir().join("dayz_tool_existing_path");
+        std::fs::create_dir_all(&good).unwrap();
+        let bad = env::temp_dir().join("dayz_tool_existing_path_typo");
+        let _ = std::fs::remove_dir_all(&bad);
+
+        let mut entries = vec![
+            good.to_str().unwrap().to_string(),
+            bad.to_str().unwrap().to_string(),
+        ];
+        let mut rejected = vec![];
+
+        let path = read_existing_path(
+            || entries.pop().unwrap(),
+            |path| {
+                rejected.push(path.to_string());
+                false
+            },
+        );
+
+        assert_eq!(path, good.to_str().unwrap());
+        assert_eq!(rejected, vec![bad.to_str().unwrap().to_string()]);
+
+        let accepted = read_existing_path(|| bad.to_str().unwrap().to_string(), |_| true);
+        assert_eq!(accepted, bad.to_str().unwrap());
+
+        std::fs::remove_dir_all(&good).unwrap();
+    }
+
+    #[test]
+    fn test_toml_config_round_trip() {
+        let dir = env::temp_dir().join("dayz_tool_toml_config");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let profile = Profile {
+            name: "DayZTestServer".to_string(),
+            workdir_path: "/srv/dayz".to_string(),
+            workshop_path: "/srv/!Workshop".to_string(),
+            start_parameters: Some("-mod=@CF".to_string()),
+            installed_mods: vec![json!("@CF"), json!("@AdminTools")],
+            is_active: true,
+            mod_sources: BTreeMap::from([("@AdminTools".to_string(), "@Admin Tools".to_string())]),
+            load_order: vec!["@CF".to_string()],
+            steamcmd: Some(SteamCmdConfig {
+                path: "/opt/steamcmd/steamcmd.sh".to_string(),
+                login: "anonymous".to_string(),
+                command: "{steamcmd} {items} +quit".to_string(),
+                keyring: Some("dayz-tool-cli".to_string()),
+            }),
+            startup_template: Some(StartupTemplate::Builtin),
+            ..Default::default()
+        };
+        let config = Root {
+            profiles: vec![profile],
+        };
+
+        let toml_path = dir.join("config.toml");
+        let json_path = dir.join("config.json");
+        write_config_file(&toml_path, &config).unwrap();
+        write_config_file(&json_path, &config).unwrap();
+
+        let toml_content = std::fs::read_to_string(&toml_path).unwrap();
+        assert!(toml_content.contains("[[profiles]]"));
+        assert_eq!(read_config_file(&toml_path).unwrap(), config);
+        assert_eq!(
+            read_config_file(&toml_path).unwrap(),
+            read_config_file(&json_path).unwrap()
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,229 @@
+use std::{
+    io::{self, Write},
+    process::{Command, ExitStatus, Stdio},
+};
+
+use crate::{CredentialError, SteamCmdConfig};
+
+/// The keyring service the SteamCMD password is stored under.
+pub const KEYRING_SERVICE: &str = "dayz-tool-cli";
+
+/// Loads the WinRT password vault into PowerShell and opens it as `$vault`.
+const POWERSHELL_VAULT: &str = "[void][Windows.Security.Credentials.PasswordVault, \
+    Windows.Security.Credentials, ContentType = WindowsRuntime]; \
+    $vault = New-Object Windows.Security.Credentials.PasswordVault;";
+
+/// Stores and retrieves passwords by service and username.
+pub trait CredentialStore {
+    fn get_password(&self, service: &str, username: &str) -> Result<String, CredentialError>;
+    fn set_password(
+        &self,
+        service: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<(), CredentialError>;
+}
+
+/// The keyring of the operating system.
+///
+/// The keyring is accessed through the command-line tool of the platform: `secret-tool`
+/// (libsecret) on Linux, `security` on macOS and the password vault of the Windows
+/// Credential Manager through PowerShell. Other platforms are not supported. Passwords
+/// are always passed on stdin, never as arguments visible in the process list.
+pub struct SystemKeyring;
+
+impl CredentialStore for SystemKeyring {
+    fn get_password(&self, service: &str, username: &str) -> Result<String, CredentialError> {
+        let mut command = if cfg!(target_os = "macos") {
+            let mut command = Command::new("security");
+            command.args(["find-generic-password", "-s", service, "-a", username, "-w"]);
+            command
+        } else if cfg!(target_os = "linux") {
+            let mut command = Command::new("secret-tool");
+            command.args(["lookup", "service", service, "username", username]);
+            command
+        } else if cfg!(target_os = "windows") {
+            let script = format!(
+                "{} $credential = $vault.Retrieve($env:DAYZ_TOOL_KEYRING_SERVICE, \
+                 $env:DAYZ_TOOL_KEYRING_USER); $credential.RetrieveProcessPassword(); \
+                 [Console]::Out.Write($credential.Password)",
+                POWERSHELL_VAULT
+            );
+            powershell(&script, service, username)
+        } else {
+            return Err(unsupported_platform());
+        };
+
+        let output = command
+            .output()
+            .map_err(|e| CredentialError::Unavailable(e.to_string()))?;
+        let password = String::from_utf8_lossy(&output.stdout)
+            .trim_end_matches(['\r', '\n'])
+            .to_string();
+        if !output.status.success() || password.is_empty() {
+            return Err(CredentialError::NotFound(username.to_string()));
+        }
+
+        Ok(password)
+    }
+
+    fn set_password(
+        &self,
+        service: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<(), CredentialError> {
+        let status = if cfg!(target_os = "macos") {
+            // `security -i` reads the command from stdin, keeping the password out of argv.
+            let input = format!(
+                "add-generic-password -U -s {} -a {} -w {}\n",
+                security_quote(service),
+                security_quote(username),
+                security_quote(password)
+            );
+            let mut command = Command::new("security");
+            command.arg("-i");
+            run_with_stdin(command, &input)
+        } else if cfg!(target_os = "linux") {
+            let label = format!("{} ({})", service, username);
+            let mut command = Command::new("secret-tool");
+            command
+                .args(["store", "--label", &label, "service", service])
+                .args(["username", username]);
+            run_with_stdin(command, password)
+        } else if cfg!(target_os = "windows") {
+            let script = format!(
+                "{} $vault.Add((New-Object Windows.Security.Credentials.PasswordCredential(\
+                 $env:DAYZ_TOOL_KEYRING_SERVICE, $env:DAYZ_TOOL_KEYRING_USER, \
+                 [Console]::In.ReadLine())))",
+                POWERSHELL_VAULT
+            );
+            run_with_stdin(powershell(&script, service, username), password)
+        } else {
+            return Err(unsupported_platform());
+        }
+        .map_err(|e| CredentialError::Unavailable(e.to_string()))?;
+
+        if !status.success() {
+            return Err(CredentialError::Unavailable(format!(
+                "the keyring tool exited with {}",
+                status
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs `command` with `input` written to its stdin and waits for it to exit.
+fn run_with_stdin(mut command: Command, input: &str) -> io::Result<ExitStatus> {
+    let mut child = command.stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    child.wait()
+}
+
+/// Builds a PowerShell command running `script` with the service and username in the
+/// environment, so neither needs quoting inside the script.
+fn powershell(script: &str, service: &str, username: &str) -> Command {
+    let mut command = Command::new("powershell");
+    command
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .env("DAYZ_TOOL_KEYRING_SERVICE", service)
+        .env("DAYZ_TOOL_KEYRING_USER", username);
+    command
+}
+
+/// Quotes an argument for the command parser of `security -i`.
+fn security_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn unsupported_platform() -> CredentialError {
+    CredentialError::Unavailable("no supported keyring on this platform".to_string())
+}
+
+/// Retrieves the SteamCMD password of `config.login` from the keyring.
+///
+/// Returns `None` if the settings reference no keyring, i.e. SteamCMD logs in without a
+/// password or with cached credentials.
+pub fn steamcmd_password(
+    config: &SteamCmdConfig,
+    store: &dyn CredentialStore,
+) -> Result<Option<String>, CredentialError> {
+    match &config.keyring {
+        Some(service) => store.get_password(service, &config.login).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::RefCell, collections::HashMap};
+
+    #[derive(Default)]
+    struct MemoryKeyring {
+        passwords: RefCell<HashMap<(String, String), String>>,
+    }
+
+    impl CredentialStore for MemoryKeyring {
+        fn get_password(&self, service: &str, username: &str) -> Result<String, CredentialError> {
+            self.passwords
+                .borrow()
+                .get(&(service.to_string(), username.to_string()))
+                .cloned()
+                .ok_or_else(|| CredentialError::NotFound(username.to_string()))
+        }
+
+        fn set_password(
+            &self,
+            service: &str,
+            username: &str,
+            password: &str,
+        ) -> Result<(), CredentialError> {
+            self.passwords.borrow_mut().insert(
+                (service.to_string(), username.to_string()),
+                password.to_string(),
+            );
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_steamcmd_password_is_retrieved_by_username() {
+        let keyring = MemoryKeyring::default();
+        keyring
+            .set_password(KEYRING_SERVICE, "server_admin", "hunter2")
+            .unwrap();
+        keyring
+            .set_password(KEYRING_SERVICE, "other_admin", "swordfish")
+            .unwrap();
+
+        let mut config = SteamCmdConfig {
+            login: "server_admin".to_string(),
+            keyring: Some(KEYRING_SERVICE.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            steamcmd_password(&config, &keyring).unwrap().as_deref(),
+            Some("hunter2")
+        );
+
+        config.login = "unknown_admin".to_string();
+        assert_eq!(
+            steamcmd_password(&config, &keyring),
+            Err(CredentialError::NotFound("unknown_admin".to_string()))
+        );
+
+        config.keyring = None;
+        assert_eq!(steamcmd_password(&config, &keyring), Ok(None));
+    }
+
+    #[test]
+    fn test_security_quote() {
+        assert_eq!(security_quote("hunter2"), "\"hunter2\"");
+        assert_eq!(security_quote(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+    }
+}
@@ -1,35 +1,164 @@
-use crate::utils::get_config_path;
+use crate::{
+    is_json_errors, is_summary_only, utils::get_config_path, ConfigError, ErrorContext, ErrorKind,
+    JsonError, Profile,
+};
+use log::{error, Log, Metadata, Record};
 use simplelog::*;
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+/// The log target of the final summary of a bulk operation.
+///
+/// Entries with this target are shown on the terminal in summary-only mode (see
+/// `set_summary_only`).
+pub const SUMMARY_TARGET: &str = "summary";
 
 /// Initializes the application's logging system with both terminal and file output.
 ///
 /// Sets up a combined logging system that writes:
-/// - Info level logs to the terminal with colored output
-/// - Debug level logs to a daily rotating file in the application's logs directory
+/// - Info level logs to the terminal with colored output, or only errors and summaries
+///   in summary-only mode
+/// - Debug level logs to a daily rotating file in the given log directory
 ///
-/// The log files are created in a 'logs' directory alongside the config directory,
+/// The log files are created in the given `log_dir` (see `get_log_dir`),
 /// with the naming pattern: `dayz-tool_YYYY-MM-DD.log`
-pub fn init_logger() -> Result<(), Box<dyn std::error::Error>> {
-    let config_path = get_config_path();
-    let log_path = &config_path.parent().unwrap().join("logs");
-    if !log_path.exists() {
-        std::fs::create_dir_all(log_path)?;
-    }
-
-    let log_file = std::fs::File::create(log_path.join(format!(
-        "dayz-tool_{}.log",
-        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
-    )))?;
+pub fn init_logger(log_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let log_file = create_log_file(log_dir)?;
 
     CombinedLogger::init(vec![
-        TermLogger::new(
-            LevelFilter::Info,
-            Config::default(),
-            TerminalMode::Mixed,
-            ColorChoice::Auto,
-        ),
+        Box::new(TerminalLogger {
+            inner: TermLogger::new(
+                LevelFilter::Info,
+                Config::default(),
+                TerminalMode::Mixed,
+                ColorChoice::Auto,
+            ),
+        }),
         WriteLogger::new(LevelFilter::Debug, Config::default(), log_file),
     ])?;
 
     Ok(())
 }
+
+/// The terminal logger, hiding progress entries in summary-only mode.
+struct TerminalLogger {
+    inner: Box<TermLogger>,
+}
+
+/// Returns whether an entry is shown on the terminal in summary-only mode.
+fn is_summary_entry(metadata: &Metadata) -> bool {
+    metadata.level() == Level::Error || metadata.target() == SUMMARY_TARGET
+}
+
+impl Log for TerminalLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        (!is_summary_only() || is_summary_entry(metadata)) && self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+impl SharedLogger for TerminalLogger {
+    fn level(&self) -> LevelFilter {
+        self.inner.level()
+    }
+
+    fn config(&self) -> Option<&Config> {
+        self.inner.config()
+    }
+
+    fn as_log(self: Box<Self>) -> Box<dyn Log> {
+        self
+    }
+}
+
+/// Returns the directory the file log should be written to.
+///
+/// By default the logs are stored in a 'logs' directory alongside the config directory.
+/// If the given profile has `log_to_workdir` enabled, the logs are written to
+/// `<workdir>/logs` instead, keeping each server's operation logs next to its files.
+pub fn get_log_dir(profile: Option<&Profile>) -> Result<PathBuf, ConfigError> {
+    match profile {
+        Some(profile) if profile.log_to_workdir => {
+            Ok(Path::new(&profile.workdir_path).join("logs"))
+        }
+        _ => Ok(get_config_path()?
+            .parent()
+            .ok_or(ConfigError::NoConfigDir)?
+            .join("logs")),
+    }
+}
+
+/// Reports a failed command.
+///
+/// The `message` is logged as an error followed by the `error` itself, so `message`
+/// should describe the failed action without repeating the error. In JSON error mode
+/// (see `set_json_errors`), a `JsonError` is printed to stderr instead and the process
+/// exits with its code.
+pub fn report_error<E: ErrorKind + std::fmt::Display>(
+    message: &str,
+    error: &E,
+    context: &ErrorContext,
+) {
+    if !is_json_errors() {
+        error!("{}: {}", message, error);
+        return;
+    }
+
+    let json_error = JsonError::new(message, error, context);
+    match serde_json::to_string(&json_error) {
+        Ok(json) => eprintln!("{}", json),
+        Err(_) => error!("{}", json_error.message),
+    }
+    std::process::exit(json_error.code);
+}
+
+/// Creates a new timestamped log file in the given directory.
+///
+/// The directory is created if it does not exist yet.
+fn create_log_file(log_dir: &Path) -> std::io::Result<File> {
+    if !log_dir.exists() {
+        std::fs::create_dir_all(log_dir)?;
+    }
+
+    File::create(log_dir.join(format!(
+        "dayz-tool_{}.log",
+        chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_file_created_in_workdir() {
+        let workdir = std::env::temp_dir().join("dayz_tool_log_workdir");
+        let _ = std::fs::remove_dir_all(&workdir);
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            log_to_workdir: true,
+            ..Default::default()
+        };
+
+        let log_dir = get_log_dir(Some(&profile)).unwrap();
+        assert_eq!(log_dir, workdir.join("logs"));
+
+        create_log_file(&log_dir).unwrap();
+        let entries: Vec<_> = std::fs::read_dir(&log_dir).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+
+        std::fs::remove_dir_all(&workdir).unwrap();
+    }
+}
@@ -1,17 +1,29 @@
 mod config;
+mod credentials;
 mod log;
 mod mods;
 
 pub use config::{
-    add_mods_to_profile, add_profile, create_initial_profile, get_config_path, get_profile,
-    get_profiles, get_render_config, remove_mods_from_profile, remove_profile, save_profile,
-    switch_active_profile,
+    add_mods_to_profile, add_profile, create_initial_profile, forget_mods, get_config_path,
+    get_profile, get_profiles, get_render_config, prompt_path, read_config_file,
+    remove_mods_from_profile, remove_profile, resolve_profile_paths, save_profile,
+    switch_active_profile, validate_config, validate_profile, write_config_file,
 };
 
-pub use log::init_logger;
+pub use credentials::{steamcmd_password, CredentialStore, SystemKeyring, KEYRING_SERVICE};
+
+pub use log::{get_log_dir, init_logger, report_error, SUMMARY_TARGET};
 
 pub use mods::{
-    analyze_types_folder, compare_mod_versions, copy_dir, copy_keys, find_keys_folder,
-    find_types_folder, get_installed_mod_list, get_map_name, parse_startup_parameter,
-    remove_ce_entries, remove_keys_for_mod, save_extracted_data, update_cfgeconomy,
+    analyze_types_folder, build_steamcmd_command, ce_base_path, ce_entry_names, ce_folder,
+    check_distinct_paths, check_mod_branch, compare_mod_versions, copy_dir, copy_keys,
+    count_ce_entries, diff_checksums, diff_mod_files, find_dangling_ce_files, find_keys_folder,
+    find_types_folder, find_workshop_mods, get_dir_size, get_downloaded_item_path,
+    get_installed_mod_list, get_map_name, list_mission_folders, load_checksum_cache,
+    merge_custom_types, missing_mod_folders, mod_file_checksums, normalize_tags,
+    order_by_load_order, parse_server_cfg, parse_startup_parameter, read_mod_branch, read_mod_name,
+    read_published_id, read_types_file, rebuild_cfgeconomy, remove_ce_entries, remove_dir,
+    remove_keys_for_mod, rename_ce_short_name, resolve_map_name, save_checksum_cache,
+    save_extracted_data, scale_types, steamcmd_runscript, update_cfgeconomy, validate_ce_files,
+    write_types_file, BACKUP_DIR, BACKUP_MANIFEST,
 };
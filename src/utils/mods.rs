@@ -1,21 +1,33 @@
 use crate::{
-    utils::{get_config_path, get_profile},
-    Event, EventsWrapper, ModChecksum, ModError, Profile, ProgressBar, SpawnableType,
-    SpawnableTypesWrapper, ThreadPool, Type, TypesWrapper, THEME,
+    cancel_flag, is_dry_run,
+    utils::{get_config_path, resolve_profile_paths},
+    ChecksumCache, Event, EventsWrapper, FileChecksum, ModChecksum, ModDiff, ModError, Profile,
+    ProgressBar, SpawnableType, SpawnableTypesWrapper, StartupParameters, SteamCmdConfig,
+    ThreadPool, Type, TypesWrapper, CHECKSUM_CACHE, THEME,
+};
+use log::{debug, error, info, warn};
+use quick_xml::{
+    events::{BytesStart, Event as XmlEvent},
+    se::to_string,
+    Reader,
 };
-use log::{debug, error, info};
-use quick_xml::se::to_string;
 use regex::Regex;
 use serde::Serialize;
 use serde_json::Value;
 use serde_xml_rs::from_str;
 use sha2::{Digest, Sha256};
 use std::{
-    collections::HashMap,
-    fs::{copy, create_dir_all, read_dir, read_to_string, remove_file, File},
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::{
+        copy, create_dir_all, read_dir, read_to_string, remove_dir_all, remove_file, rename, File,
+    },
     io::{Read, Write},
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{SystemTime, UNIX_EPOCH},
 };
 use walkdir::WalkDir;
 
@@ -25,9 +37,26 @@ use walkdir::WalkDir;
 /// recursively copies all files and subdirectories from the source to the target.
 /// For files larger than 100MB, it uses a chunked copying approach to optimize memory usage
 /// and provide progress tracking.
-pub fn copy_dir(source_dir: &Path, target_dir: &Path) -> Result<(), ModError> {
+///
+/// If a `progress` bar is given, it is advanced by the size of every copied file, so
+/// mods made of many small files show progress as well.
+pub fn copy_dir(
+    source_dir: &Path,
+    target_dir: &Path,
+    progress: Option<&ProgressBar>,
+) -> Result<(), ModError> {
+    if is_dry_run() {
+        info!(
+            "[dry-run] Would copy {} to {}",
+            source_dir.display(),
+            target_dir.display()
+        );
+        return Ok(());
+    }
+
     match create_dir_all(target_dir) {
         Ok(_) => (),
+        Err(e) if is_disk_full(&e) => return Err(copy_error(&e, target_dir)),
         Err(e) => {
             error!("Failed to create directory {}: {}", target_dir.display(), e);
             return Err(ModError::CreateDirError);
@@ -59,7 +88,7 @@ pub fn copy_dir(source_dir: &Path, target_dir: &Path) -> Result<(), ModError> {
         })?;
 
         if file_type.is_dir() {
-            copy_dir(&source_path, &target_path)?;
+            copy_dir(&source_path, &target_path, progress)?;
         } else {
             let metadata = entry.metadata().map_err(|e| {
                 error!(
@@ -80,20 +109,71 @@ pub fn copy_dir(source_dir: &Path, target_dir: &Path) -> Result<(), ModError> {
                 );
                 copy_large_file(&source_path, &target_path, CHUNK_SIZE).map_err(|e| {
                     error!("Failed to copy large file {}: {}", source_path.display(), e);
-                    ModError::CopyFileError
+                    copy_error(&e, target_dir)
                 })?;
             } else {
                 copy(&source_path, &target_path).map_err(|e| {
                     error!("Failed to copy file {}: {}", source_path.display(), e);
-                    ModError::CopyFileError
+                    copy_error(&e, target_dir)
                 })?;
             }
+
+            if let Some(progress) = progress {
+                progress.inc(file_size);
+            }
         }
     }
 
     Ok(())
 }
 
+/// Returns whether an IO error was caused by a full disk.
+///
+/// Besides `ErrorKind::StorageFull`, the raw OS errors are checked, as not every
+/// platform maps them to that kind (`ENOSPC` on Unix, `ERROR_DISK_FULL` and
+/// `ERROR_HANDLE_DISK_FULL` on Windows).
+fn is_disk_full(error: &std::io::Error) -> bool {
+    const ENOSPC: i32 = 28;
+    const ERROR_HANDLE_DISK_FULL: i32 = 39;
+    const ERROR_DISK_FULL: i32 = 112;
+
+    if error.kind() == std::io::ErrorKind::StorageFull {
+        return true;
+    }
+
+    match error.raw_os_error() {
+        Some(code) if cfg!(windows) => code == ERROR_DISK_FULL || code == ERROR_HANDLE_DISK_FULL,
+        Some(code) => code == ENOSPC,
+        None => false,
+    }
+}
+
+/// Maps an IO error of a copy into `target_dir` to a `ModError`.
+///
+/// A full disk is reported as `ModError::DiskFull` naming the destination, so it is not
+/// mistaken for a generic copy failure.
+fn copy_error(error: &std::io::Error, target_dir: &Path) -> ModError {
+    if is_disk_full(error) {
+        let error = ModError::DiskFull(target_dir.display().to_string());
+        error!("{}", error);
+        error
+    } else {
+        ModError::CopyFileError
+    }
+}
+
+/// Recursively removes a directory.
+///
+/// In dry-run mode the intended removal is only logged.
+pub fn remove_dir(path: &Path) -> std::io::Result<()> {
+    if is_dry_run() {
+        info!("[dry-run] Would remove {}", path.display());
+        return Ok(());
+    }
+
+    remove_dir_all(path)
+}
+
 /// Copies a large file in chunks with progress tracking.
 ///
 /// This function implements a memory-efficient copying mechanism for large files
@@ -133,66 +213,234 @@ fn copy_large_file(source: &Path, target: &Path, chunk_size: usize) -> std::io::
 /// This function walks through the mod directory and calculates checksums for all files,
 /// using a thread pool for parallel processing. It handles files differently based on their size:
 /// - Files > 1MB: Full SHA256 hash calculation
-/// - Files ≤ 1MB: Only size comparison ("small_file" marker)
+/// - Files ≤ 1MB: Only size comparison (`SMALL_FILE_HASH` marker), unless
+///   `hash_small_files` is set
+///
+/// A progress bar sized by the number of discovered files is shown while hashing.
+/// The subdirectories are added as entries marked with `is_dir`, so a new empty
+/// directory is detected as a difference as well.
 fn calculate_mod_checksums(
     mod_path: &Path,
     pool: &ThreadPool,
+    hash_small_files: bool,
 ) -> Result<Vec<ModChecksum>, std::io::Error> {
-    let checksums_mutex = Arc::new(Mutex::new(Vec::new()));
-    let error_mutex = Arc::new(Mutex::new(None));
+    let files = collect_mod_files(mod_path);
+
+    debug!("Found {} files to check", files.len());
+
+    let progress = ProgressBar::new(
+        files.len() as u64,
+        30,
+        &format!(
+            "Checking {}",
+            mod_path.file_name().unwrap_or_default().to_string_lossy()
+        ),
+        Arc::new(THEME.clone()),
+    )
+    .with_item_count();
+
+    let mut checksums = calculate_checksums(
+        mod_path,
+        files,
+        pool,
+        &progress,
+        cancel_flag(),
+        hash_small_files,
+    )?;
+    checksums.extend(collect_mod_dirs(mod_path));
+    Ok(checksums)
+}
 
-    let files: Vec<_> = WalkDir::new(mod_path)
+/// Returns the directory entries of a mod directory for the mod comparison.
+fn collect_mod_dirs(mod_path: &Path) -> Vec<ModChecksum> {
+    WalkDir::new(mod_path)
         .min_depth(1)
         .into_iter()
         .filter_entry(|e| !is_ignored_file(e))
         .filter_map(|entry| entry.ok())
+        .filter(|e| e.file_type().is_dir())
+        .map(|e| ModChecksum {
+            path: e
+                .path()
+                .strip_prefix(mod_path)
+                .unwrap_or(e.path())
+                .to_path_buf(),
+            size: 0,
+            hash: String::new(),
+            modified: modified_time(e.metadata().ok().as_ref()),
+            is_dir: true,
+        })
+        .collect()
+}
+
+/// Returns the modification time of the metadata, `UNIX_EPOCH` if unavailable.
+fn modified_time(metadata: Option<&std::fs::Metadata>) -> SystemTime {
+    metadata
+        .and_then(|metadata| metadata.modified().ok())
+        .unwrap_or(UNIX_EPOCH)
+}
+
+/// Returns the total size in bytes of all files in a directory.
+pub fn get_dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
         .filter(|e| e.file_type().is_file())
-        .collect();
+        .filter_map(|e| e.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
 
-    debug!("Found {} files to check", files.len());
+/// Finds the mod folders in the workshop path, descending up to `depth` levels.
+///
+/// With a depth of 1, every entry of the workshop path is a candidate. With a greater
+/// depth, entries that look like a mod (a name starting with '@', or an `addons` folder
+/// or `meta.cpp` inside) are candidates, and all other folders are searched one level
+/// deeper. Entries on the last level are always candidates. The candidates are sorted
+/// by path.
+pub fn find_workshop_mods(workshop_path: &Path, depth: usize) -> Vec<PathBuf> {
+    let Ok(entries) = workshop_path.read_dir() else {
+        error!(
+            "Failed to read the workshop path {}",
+            workshop_path.display()
+        );
+        return Vec::new();
+    };
+
+    let mut candidates = Vec::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if depth > 1 && path.is_dir() && !is_mod_folder(&path) {
+            candidates.extend(find_workshop_mods(&path, depth - 1));
+        } else {
+            candidates.push(path);
+        }
+    }
+
+    candidates.sort();
+    candidates
+}
+
+/// Returns whether a folder looks like a mod.
+fn is_mod_folder(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('@'))
+        || path.join("addons").is_dir()
+        || path.join("meta.cpp").is_file()
+}
+
+/// Collects all files of a mod directory that are relevant for the mod comparison.
+fn collect_mod_files(mod_path: &Path) -> Vec<walkdir::DirEntry> {
+    WalkDir::new(mod_path)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|e| !is_ignored_file(e))
+        .filter_map(|entry| entry.ok())
+        .filter(|e| e.file_type().is_file())
+        .collect()
+}
+
+/// The hash recorded for a file that could not be read during the checksum pass.
+const UNREADABLE_HASH: &str = "unreadable";
+
+/// The hash recorded for a file of at most 1MB that is compared by size only.
+const SMALL_FILE_HASH: &str = "small_file";
+
+/// The fraction of unreadable files above which the checksum pass fails.
+const MAX_UNREADABLE_FRACTION: f64 = 0.1;
+
+/// Calculates the checksums of the given files on the thread pool.
+///
+/// The shared `progress` bar is incremented as each file's checksum completes. The
+/// `cancel` flag is checked before each file; once it is set, the remaining files are
+/// skipped and an error of kind `Interrupted` is returned.
+///
+/// A file that cannot be read (e.g. while it is locked on Windows) is logged as a
+/// warning and recorded with the `UNREADABLE_HASH` marker, so `compare_mod_versions`
+/// skips it. The pass only fails if more than `MAX_UNREADABLE_FRACTION` of the files
+/// cannot be read.
+///
+/// Files of at most 1MB get the `SMALL_FILE_HASH` marker instead of a hash unless
+/// `hash_small_files` is set.
+fn calculate_checksums(
+    mod_path: &Path,
+    files: Vec<walkdir::DirEntry>,
+    pool: &ThreadPool,
+    progress: &ProgressBar,
+    cancel: &'static AtomicBool,
+    hash_small_files: bool,
+) -> Result<Vec<ModChecksum>, std::io::Error> {
+    let checksums_mutex = Arc::new(Mutex::new(Vec::new()));
+    let error_mutex = Arc::new(Mutex::new(Vec::new()));
+    let total = files.len();
 
     for entry in files {
         let checksums = Arc::clone(&checksums_mutex);
         let errors = Arc::clone(&error_mutex);
         let path = entry.path().to_path_buf();
         let mod_path = mod_path.to_path_buf();
+        let progress = progress.clone();
 
         pool.execute(move || {
-            let result: Result<(), std::io::Error> = (|| {
+            if cancel.load(Ordering::SeqCst) {
+                return;
+            }
+
+            let rel_path = path.strip_prefix(&mod_path).unwrap_or(&path).to_path_buf();
+            let metadata = entry.metadata().ok();
+            let result: Result<(u64, String), std::io::Error> = (|| {
                 let metadata = entry.metadata()?;
                 let size = metadata.len();
-                let hash = if size > 1024 * 1024 {
-                    calculate_file_hash(&path)?
+                let hash = if hash_small_files || size > 1024 * 1024 {
+                    cached_file_hash(&path, &metadata)?
                 } else {
-                    "small_file".to_string()
+                    SMALL_FILE_HASH.to_string()
                 };
-
-                let rel_path = path
-                    .strip_prefix(&mod_path)
-                    .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?
-                    .to_path_buf();
-
-                let mut checksums_guard = checksums.lock().unwrap();
-                checksums_guard.push(ModChecksum {
-                    path: rel_path,
-                    size,
-                    hash,
-                });
-                Ok(())
+                Ok((size, hash))
             })();
 
-            if let Err(e) = result {
-                let mut error_guard = errors.lock().unwrap();
-                *error_guard = Some(e);
-            }
+            let (size, hash) = match result {
+                Ok(checksum) => checksum,
+                Err(e) => {
+                    warn!("Skipping unreadable file {}: {}", path.display(), e);
+                    errors.lock().unwrap().push(e);
+                    (0, UNREADABLE_HASH.to_string())
+                }
+            };
+
+            checksums.lock().unwrap().push(ModChecksum {
+                path: rel_path,
+                size,
+                hash,
+                modified: modified_time(metadata.as_ref()),
+                is_dir: false,
+            });
+
+            progress.inc(1);
         });
     }
 
     pool.wait();
 
+    if cancel.load(Ordering::SeqCst) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Interrupted,
+            "Checksum calculation was cancelled",
+        ));
+    }
+
     let error_guard = error_mutex.lock().unwrap();
-    if let Some(e) = &*error_guard {
-        return Err(std::io::Error::new(e.kind(), e.to_string()));
+    if error_guard.len() as f64 > total as f64 * MAX_UNREADABLE_FRACTION {
+        let e = &error_guard[0];
+        return Err(std::io::Error::new(
+            e.kind(),
+            format!(
+                "{} of {} files could not be read, e.g.: {}",
+                error_guard.len(),
+                total,
+                e
+            ),
+        ));
     }
     drop(error_guard);
 
@@ -201,6 +449,68 @@ fn calculate_mod_checksums(
     Ok(result)
 }
 
+/// Returns the path of the checksum cache file, next to the configuration file.
+fn checksum_cache_path() -> Option<PathBuf> {
+    get_config_path()
+        .ok()
+        .and_then(|path| path.parent().map(|dir| dir.join("checksums.json")))
+}
+
+/// Loads the checksum cache, see `ChecksumCache`.
+///
+/// A missing or unreadable cache file results in an empty cache.
+pub fn load_checksum_cache() -> ChecksumCache {
+    checksum_cache_path()
+        .and_then(|path| read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Writes the checksum cache to disk.
+///
+/// Entries of files that no longer exist are dropped. Nothing is written in dry-run
+/// mode or if no hashes were calculated.
+pub fn save_checksum_cache() -> Result<(), ModError> {
+    let mut cache = CHECKSUM_CACHE.lock().unwrap();
+    if is_dry_run() || cache.entries.is_empty() {
+        return Ok(());
+    }
+    cache.entries.retain(|path, _| path.is_file());
+
+    let path = checksum_cache_path().ok_or(ModError::PathError)?;
+    if let Some(parent) = path.parent() {
+        create_dir_all(parent).map_err(|_| ModError::WriteError)?;
+    }
+    let content = serde_json::to_string(&*cache).map_err(|_| ModError::WriteError)?;
+    std::fs::write(&path, content).map_err(|_| ModError::WriteError)
+}
+
+/// Returns the SHA256 hash of a file, using `CHECKSUM_CACHE` if the file is unchanged.
+fn cached_file_hash(path: &Path, metadata: &std::fs::Metadata) -> std::io::Result<String> {
+    let key = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let size = metadata.len();
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos() as u64);
+
+    if let Some(modified) = modified {
+        if let Some(hash) = CHECKSUM_CACHE.lock().unwrap().get(&key, size, modified) {
+            return Ok(hash.to_string());
+        }
+    }
+
+    let hash = calculate_file_hash(path)?;
+    if let Some(modified) = modified {
+        CHECKSUM_CACHE
+            .lock()
+            .unwrap()
+            .insert(key, size, modified, hash.clone());
+    }
+    Ok(hash)
+}
+
 /// Calculates the SHA256 hash of a file.
 ///
 /// Reads the file in 1MB chunks and calculates a SHA256 hash of its contents.
@@ -223,17 +533,24 @@ fn calculate_file_hash(path: &Path) -> Result<String, std::io::Error> {
     Ok(format!("{:x}", hasher.finalize()))
 }
 
+/// The folder in the workdir that holds the snapshots of `backup_mods`.
+pub const BACKUP_DIR: &str = ".dayz-backups";
+
+/// The file listing the mods of a backup snapshot.
+pub const BACKUP_MANIFEST: &str = "manifest.json";
+
 /// Determines if a file should be ignored during mod comparison.
 ///
 /// Filters out system files and hidden files that should not be included
 /// in mod comparison calculations. Currently ignores:
 /// - Hidden files (starting with '.')
 /// - Windows system files ('desktop.ini', 'thumbs.db')
+/// - The mod backup folder (`BACKUP_DIR`)
 fn is_ignored_file(entry: &walkdir::DirEntry) -> bool {
     entry
         .file_name()
         .to_str()
-        .map(|s| s.starts_with('.') || s == "desktop.ini" || s == "thumbs.db")
+        .map(|s| s.starts_with('.') || s == "desktop.ini" || s == "thumbs.db" || s == BACKUP_DIR)
         .unwrap_or(false)
 }
 
@@ -241,20 +558,25 @@ fn is_ignored_file(entry: &walkdir::DirEntry) -> bool {
 ///
 /// This function performs a detailed comparison of mod files between the workshop and workdir
 /// directories using parallel checksum calculation. It checks for:
-/// - Different number of files
-/// - Missing files
+/// - Different number of files and directories
+/// - Missing files and directories
 /// - File size differences
 /// - Content differences (via hash comparison)
+///
+/// Files that could not be read on either side are skipped.
+///
+/// The comparison can be cancelled with `request_cancel`, in which case an error of kind
+/// `Interrupted` is returned.
 pub fn compare_mod_versions(
     workshop_path: &Path,
     workdir_path: &Path,
     pool: &ThreadPool,
 ) -> Result<bool, std::io::Error> {
     debug!("Calculating checksums for workshop version...");
-    let workshop_checksums = calculate_mod_checksums(workshop_path, pool)?;
+    let workshop_checksums = calculate_mod_checksums(workshop_path, pool, false)?;
 
     debug!("Calculating checksums for installed version...");
-    let workdir_checksums = calculate_mod_checksums(workdir_path, pool)?;
+    let workdir_checksums = calculate_mod_checksums(workdir_path, pool, false)?;
 
     if workshop_checksums.len() != workdir_checksums.len() {
         info!("Different number of files detected");
@@ -267,7 +589,18 @@ pub fn compare_mod_versions(
         .collect();
 
     for workshop_check in workshop_checksums {
-        if let Some((size, hash)) = workdir_map.get(&workshop_check.path) {
+        if workshop_check.is_dir {
+            if !workdir_map.contains_key(&workshop_check.path) {
+                info!(
+                    "Missing directory in workdir: {}",
+                    workshop_check.path.display()
+                );
+                return Ok(false);
+            }
+        } else if let Some((size, hash)) = workdir_map.get(&workshop_check.path) {
+            if hash == UNREADABLE_HASH || workshop_check.hash == UNREADABLE_HASH {
+                continue;
+            }
             if *size != workshop_check.size || *hash != workshop_check.hash {
                 info!(
                     "File {} has different size or hash",
@@ -284,15 +617,92 @@ pub fn compare_mod_versions(
     Ok(true)
 }
 
+/// Calculates the checksums of the files of a mod, keyed by their path in the mod folder.
+///
+/// Uses the same checksums as `compare_mod_versions`, so small files only record their
+/// size unless `hash_small_files` is set. A missing mod folder has no files.
+pub fn mod_file_checksums(
+    mod_path: &Path,
+    pool: &ThreadPool,
+    hash_small_files: bool,
+) -> Result<BTreeMap<PathBuf, FileChecksum>, std::io::Error> {
+    if !mod_path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(calculate_mod_checksums(mod_path, pool, hash_small_files)?
+        .into_iter()
+        .filter(|c| !c.is_dir)
+        .map(|c| {
+            (
+                c.path,
+                FileChecksum {
+                    size: c.size,
+                    hash: c.hash,
+                },
+            )
+        })
+        .collect())
+}
+
+/// Lists the files that differ between two sets of checksums from `mod_file_checksums`.
+///
+/// Files only in `new` are added, files only in `old` are removed. Files that could not
+/// be read on either side are skipped, and files without a hash on either side are
+/// compared by size.
+pub fn diff_checksums(
+    old: &BTreeMap<PathBuf, FileChecksum>,
+    new: &BTreeMap<PathBuf, FileChecksum>,
+) -> ModDiff {
+    let mut diff = ModDiff::default();
+    for (path, checksum) in new {
+        match old.get(path) {
+            None => diff.added.push(path.clone()),
+            Some(_) if checksum.hash == UNREADABLE_HASH => (),
+            Some(other) if other.hash == UNREADABLE_HASH => (),
+            Some(other) if other.hash == SMALL_FILE_HASH || checksum.hash == SMALL_FILE_HASH => {
+                if other.size != checksum.size {
+                    diff.changed.push(path.clone());
+                }
+            }
+            Some(other) => {
+                if other != checksum {
+                    diff.changed.push(path.clone());
+                }
+            }
+        }
+    }
+    diff.removed = old
+        .keys()
+        .filter(|path| !new.contains_key(*path))
+        .cloned()
+        .collect();
+    diff
+}
+
+/// Lists the files that differ between the workshop and the workdir copy of a mod.
+///
+/// Small files are compared by size only, see `mod_file_checksums`. A missing workdir
+/// folder reports every workshop file as added.
+pub fn diff_mod_files(
+    workshop_path: &Path,
+    workdir_path: &Path,
+    pool: &ThreadPool,
+) -> Result<ModDiff, std::io::Error> {
+    let workshop_files = mod_file_checksums(workshop_path, pool, false)?;
+    let workdir_files = mod_file_checksums(workdir_path, pool, false)?;
+    Ok(diff_checksums(&workdir_files, &workshop_files))
+}
+
 /// Searches for a subdirectory named "keys" in the specified mod directory.
 ///
 /// This function searches the given directory for a subdirectory named "keys"
 /// (case-insensitive). If such a directory is found, the path to this directory
-/// is returned. Otherwise, `None` is returned.
+/// is returned. Otherwise, `None` is returned, e.g. for mods that only carry
+/// server configuration.
 pub fn find_keys_folder(mod_path: &Path) -> Option<PathBuf> {
-    for entry in mod_path.read_dir().unwrap() {
-        let entry = entry.unwrap();
-        if entry.file_type().unwrap().is_dir() {
+    for entry in mod_path.read_dir().ok()? {
+        let entry = entry.ok()?;
+        if entry.file_type().ok()?.is_dir() {
             let folder_name = entry.file_name().to_string_lossy().to_lowercase();
             if folder_name == "keys" {
                 return Some(entry.path());
@@ -314,6 +724,10 @@ pub fn copy_keys(source_dir: &Path, target_dir: &Path) -> Result<(), ModError> {
         if source_path.extension().and_then(|s| s.to_str()) == Some("bikey") {
             let target_path = target_dir.join(source_path.file_name().unwrap());
             if !target_path.exists() {
+                if is_dry_run() {
+                    info!("[dry-run] Would copy key {}", target_path.display());
+                    continue;
+                }
                 match copy(&source_path, &target_path) {
                     Ok(_) => {}
                     Err(_) => {
@@ -326,29 +740,333 @@ pub fn copy_keys(source_dir: &Path, target_dir: &Path) -> Result<(), ModError> {
     Ok(())
 }
 
-/// Generates a startup parameter string for the installed mods.
+/// Generates the startup parameter strings for the installed mods of a profile.
+///
+/// This function formats the installed mods of the given profile into a `-mod=` parameter
+/// and the mods marked as server-only into a separate `-serverMod=` parameter, as used on
+/// the server command line. A warning is logged if the resulting parameters get close to
+/// the command-line length limit of the platform.
+///
+/// If the workdir exists, installed mods whose folder is missing from it (e.g. after a
+/// workshop cleanup) are left out with a warning, as the server does not start with a
+/// parameter naming a missing mod. The check is skipped in dry-run mode.
+pub fn parse_startup_parameter(profile: &Profile) -> Result<StartupParameters, ModError> {
+    let installed_mods = get_installed_mod_list(profile.clone())?;
+    let mut installed_mods_strings: Vec<String> = installed_mods
+        .iter()
+        .map(|v| v.as_str().map(String::from).ok_or(ModError::ParseError))
+        .collect::<Result<_, _>>()?;
+
+    if !is_dry_run() {
+        let missing = missing_mod_folders(profile, &installed_mods_strings);
+        for name in &missing {
+            warn!(
+                "Mod {} is installed but its folder is missing from the workdir, leaving it out of the startup parameter",
+                name
+            );
+        }
+        installed_mods_strings.retain(|name| !missing.contains(name));
+    }
+
+    let ordered_mods = order_by_load_order(&installed_mods_strings, &profile.load_order);
+    let startup_parameters = build_startup_parameters(&ordered_mods, &profile.server_mods);
+    let startup_parameter = startup_parameters.to_string();
+
+    if exceeds_command_line_limit(&startup_parameter) {
+        warn!(
+            "The startup parameter is {} characters long and approaches the command-line limit of {} characters. Consider using a mod loadout file instead.",
+            startup_parameter.len(),
+            MAX_COMMAND_LINE_LENGTH
+        );
+    }
+
+    Ok(startup_parameters)
+}
+
+/// Returns the mods whose folder is missing from the profile's workdir.
+///
+/// The workdir is resolved like `resolve_profile_paths` does. If it cannot be resolved
+/// or does not exist, nothing is reported, as the folders cannot be checked.
+pub fn missing_mod_folders(profile: &Profile, mods: &[String]) -> Vec<String> {
+    let workdir = match resolve_profile_paths(profile.clone()) {
+        Ok(resolved) => PathBuf::from(resolved.workdir_path),
+        Err(_) => return Vec::new(),
+    };
+    if profile.workdir_path.is_empty() || !workdir.is_dir() {
+        return Vec::new();
+    }
+
+    mods.iter()
+        .filter(|name| !workdir.join(name).is_dir())
+        .cloned()
+        .collect()
+}
+
+/// Ensures that the workshop path and the workdir of a profile do not overlap.
+///
+/// If both paths are the same or one is nested in the other, installing would copy
+/// mods onto themselves and updating would delete the source before copying it. This
+/// must be checked before any mutating mod operation.
+pub fn check_distinct_paths(profile: &Profile) -> Result<(), ModError> {
+    let normalize = |path: &str| {
+        let path = Path::new(path);
+        path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+    };
+    let workshop_path = normalize(&profile.workshop_path);
+    let workdir_path = normalize(&profile.workdir_path);
+
+    if workshop_path.starts_with(&workdir_path) || workdir_path.starts_with(&workshop_path) {
+        error!(
+            "The workshop path and the workdir overlap, please fix the profile: {} / {}",
+            profile.workshop_path, profile.workdir_path
+        );
+        return Err(ModError::OverlappingPaths(
+            profile.workshop_path.clone(),
+            profile.workdir_path.clone(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// The Steam app ID of DayZ, which owns the workshop items.
+const DAYZ_APP_ID: &str = "221100";
+
+/// The Steam app ID of DayZ Experimental.
+const DAYZ_EXPERIMENTAL_APP_ID: &str = "1024020";
+
+/// Builds the SteamCMD command line to download the given workshop items.
+///
+/// The command template of `config` is split on whitespace and its placeholders are
+/// substituted (see `SteamCmdConfig`), `{password}` with `password`. Tokens that end up
+/// empty, like `{password}` without a password, are dropped. Every ID must be numeric,
+/// otherwise `ModError::InvalidWorkshopId` is returned.
+pub fn build_steamcmd_command(
+    config: &SteamCmdConfig,
+    workshop_path: &str,
+    ids: &[String],
+    password: Option<&str>,
+) -> Result<Vec<String>, ModError> {
+    if let Some(id) = ids
+        .iter()
+        .find(|id| id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(ModError::InvalidWorkshopId(id.clone()));
+    }
+
+    let mut command = Vec::new();
+    for token in config.command.split_whitespace() {
+        if token == "{items}" {
+            for id in ids {
+                command.push("+workshop_download_item".to_string());
+                command.push(DAYZ_APP_ID.to_string());
+                command.push(id.clone());
+            }
+        } else {
+            let token = token
+                .replace("{steamcmd}", &config.path)
+                .replace("{login}", &config.login)
+                .replace("{workshop}", workshop_path)
+                .replace("{password}", password.unwrap_or_default());
+            if !token.is_empty() {
+                command.push(token);
+            }
+        }
+    }
+
+    Ok(command)
+}
+
+/// Splits a SteamCMD command line into its program arguments and a `+runscript` script.
+///
+/// Every `+command` argument starts a new script line without its `+`, followed by its
+/// arguments; arguments containing whitespace are quoted. Arguments before the first
+/// `+command`, starting with the executable, are returned as they are.
+pub fn steamcmd_runscript(command: &[String]) -> (Vec<String>, String) {
+    let mut program = Vec::new();
+    let mut lines: Vec<Vec<String>> = Vec::new();
+    for arg in command {
+        if let Some(name) = arg.strip_prefix('+') {
+            lines.push(vec![name.to_string()]);
+            continue;
+        }
+        match lines.last_mut() {
+            Some(line) if arg.contains(char::is_whitespace) => line.push(format!("\"{}\"", arg)),
+            Some(line) => line.push(arg.clone()),
+            None => program.push(arg.clone()),
+        }
+    }
+
+    let script = lines.iter().map(|line| line.join(" ") + "\n").collect();
+    (program, script)
+}
+
+/// Returns the folder SteamCMD downloads a workshop item to, relative to its install dir.
+pub fn get_downloaded_item_path(id: &str) -> PathBuf {
+    Path::new("steamapps/workshop/content")
+        .join(DAYZ_APP_ID)
+        .join(id)
+}
+
+/// Reads the mod name from the `meta.cpp` of a downloaded workshop item.
+///
+/// Returns the name prefixed with '@' as used for the folder in the workdir, or `None`
+/// if the mod has no readable `meta.cpp`.
+pub fn read_mod_name(mod_path: &Path) -> Option<String> {
+    read_meta_entry(mod_path, "name").map(|name| format!("@{}", name))
+}
+
+/// Reads the workshop ID from the `meta.cpp` of a mod.
+///
+/// Returns `None` if the mod has no readable `meta.cpp` or was not published.
+pub fn read_published_id(mod_path: &Path) -> Option<String> {
+    read_meta_entry(mod_path, "publishedid")
+        .filter(|id| id.chars().all(|c| c.is_ascii_digit()) && id != "0")
+}
+
+/// Reads a non-empty entry like `name = "CF";` or `publishedid = 1559212036;` from the
+/// `meta.cpp` of a mod.
+fn read_meta_entry(mod_path: &Path, key: &str) -> Option<String> {
+    let content = read_to_string(mod_path.join("meta.cpp")).ok()?;
+    let re = Regex::new(&format!(r#"(?m)^\s*{}\s*=\s*"?([^";\r\n]*)"?\s*;"#, key)).unwrap();
+    let value = re.captures(&content)?.get(1)?.as_str().trim();
+
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Detects the DayZ branch a mod was built for.
+///
+/// A `branch` entry in the mod's `meta.cpp` takes precedence. Otherwise the branch is
+/// derived from the workshop download folder, as SteamCMD stores items of DayZ Experimental
+/// under its own app ID. Returns `None` if neither is available.
+pub fn read_mod_branch(mod_path: &Path) -> Option<String> {
+    if let Some(branch) = read_meta_entry(mod_path, "branch") {
+        return Some(branch.to_lowercase());
+    }
+
+    mod_path
+        .components()
+        .find_map(|component| match component.as_os_str().to_str() {
+            Some(DAYZ_EXPERIMENTAL_APP_ID) => Some("experimental".to_string()),
+            Some(DAYZ_APP_ID) => Some("stable".to_string()),
+            _ => None,
+        })
+}
+
+/// Checks whether a mod was built for a different branch than the server.
 ///
-/// This function retrieves the configuration path and profile, then generates a list
-/// of installed mods. It formats these mods into a startup parameter string suitable
-pub fn parse_startup_parameter() -> Result<String, ModError> {
-    let config = get_config_path();
-    let updatet_profile = get_profile(&config).unwrap();
+/// # Arguments
+///
+/// * `branch` - The branch configured in the profile, if any
+/// * `mod_path` - The path of the mod in the workshop
+///
+/// # Returns
+///
+/// The branch of the mod if it differs from `branch`, or `None` if they match or either
+/// one is unknown.
+pub fn check_mod_branch(branch: Option<&str>, mod_path: &Path) -> Option<String> {
+    let branch = branch?;
+    let mod_branch = read_mod_branch(mod_path)?;
+
+    if mod_branch.eq_ignore_ascii_case(branch) {
+        None
+    } else {
+        Some(mod_branch)
+    }
+}
 
-    let installed_mods = get_installed_mod_list(updatet_profile).unwrap();
-    let installed_mods_strings: Vec<String> = installed_mods
+/// Sorts the installed mods by the configured load order.
+///
+/// Mods listed in `load_order` come first, in that order. Installed mods missing from the
+/// load order keep their storage order and are appended at the end, while entries of the
+/// load order that are not installed are ignored.
+pub fn order_by_load_order(installed_mods: &[String], load_order: &[String]) -> Vec<String> {
+    let mut ordered: Vec<String> = load_order
         .iter()
-        .map(|v| v.as_str().unwrap().to_string())
+        .filter(|name| installed_mods.contains(name))
+        .cloned()
         .collect();
-    let startup_parameter = format!("\"-mod={};\"", installed_mods_strings.join(";"));
-    Ok(startup_parameter)
+
+    for name in installed_mods {
+        if !ordered.contains(name) {
+            ordered.push(name.clone());
+        }
+    }
+
+    ordered
+}
+
+/// Trims mod tags and drops empty and duplicate ones, keeping the first spelling.
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !normalized.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+/// Splits the installed mods into the `-mod=` and `-serverMod=` parameters.
+///
+/// Mods listed in `server_mods` are only emitted in the `-serverMod=` parameter. Mod names
+/// are trimmed and blank names are skipped, so the list has no empty segments and no
+/// trailing separator. A parameter without mods is left empty.
+fn build_startup_parameters(
+    installed_mods: &[String],
+    server_mods: &[String],
+) -> StartupParameters {
+    let (server, client): (Vec<&String>, Vec<&String>) = installed_mods
+        .iter()
+        .filter(|name| !name.trim().is_empty())
+        .partition(|name| server_mods.contains(name));
+
+    let format_parameter = |name: &str, mods: Vec<&String>| {
+        if mods.is_empty() {
+            String::new()
+        } else {
+            let mods: Vec<&str> = mods.iter().map(|m| m.trim()).collect();
+            format!("\"-{}={}\"", name, mods.join(";"))
+        }
+    };
+
+    StartupParameters {
+        mods: format_parameter("mod", client),
+        server_mods: format_parameter("serverMod", server),
+    }
+}
+
+/// The maximum command-line length on Windows (the most restrictive supported platform).
+const MAX_COMMAND_LINE_LENGTH: usize = 8191;
+
+/// Checks whether a startup parameter approaches the command-line length limit.
+///
+/// The rest of the server command line (executable path, port, config, ...) also counts
+/// towards the limit, so a warning threshold of 90% of the limit is used.
+fn exceeds_command_line_limit(startup_parameter: &str) -> bool {
+    startup_parameter.len() > MAX_COMMAND_LINE_LENGTH * 9 / 10
+}
+
+/// Returns whether a file name is a DayZ CE file.
+///
+/// These are the types (`*types.xml`, including `cfgspawnabletypes.xml`) and events
+/// (`*events.xml`) files, compared case-insensitively. Other files that merely contain
+/// "types" in their name, like `archetypes.c`, are not CE files.
+fn is_ce_file(file_name: &str) -> bool {
+    let file_name = file_name.to_lowercase();
+    file_name.ends_with("types.xml") || file_name.ends_with("events.xml")
 }
 
-/// Recursively searches for a folder containing a file with "types" in its name.
+/// Recursively searches for a folder containing a CE file.
 ///
 /// This function starts at the given path and traverses directories recursively
-/// to find a folder that contains a file with "types" in its name. If such a folder
-/// is found, the path to the folder is returned. If no such folder is found, `None`
-/// is returned.
+/// to find a folder that contains a types or events file (see `is_ce_file`). If such
+/// a folder is found, the path to the folder is returned. If no such folder is found,
+/// `None` is returned.
 pub fn find_types_folder(path: &Path) -> Option<PathBuf> {
     fn visit_dirs(dir: &Path) -> Option<PathBuf> {
         if dir.is_dir() {
@@ -362,10 +1080,8 @@ pub fn find_types_folder(path: &Path) -> Option<PathBuf> {
                 } else if path.is_file()
                     && path
                         .file_name()
-                        .unwrap()
-                        .to_str()
-                        .unwrap()
-                        .contains("types")
+                        .and_then(|name| name.to_str())
+                        .is_some_and(is_ce_file)
                 {
                     return Some(path.parent().unwrap().to_path_buf());
                 }
@@ -383,10 +1099,28 @@ pub fn find_types_folder(path: &Path) -> Option<PathBuf> {
 /// of type `<type>` or `<event>`. It handles cases where the root tag might be missing
 /// and adds it if necessary. The function returns a vector of strings, each containing
 /// a complete XML element.
+///
+/// Files that are not valid UTF-8 are decoded as Windows-1252, which some mods ship
+/// their types in, and a warning is logged.
 fn extract_xml_data(file_path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let mut file = File::open(file_path)?;
-    let mut content = String::new();
-    file.read_to_string(&mut content)?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    let mut content = match String::from_utf8(bytes) {
+        Ok(content) => content,
+        Err(e) => {
+            warn!(
+                "{} is not valid UTF-8, reading it as Windows-1252",
+                file_path.display()
+            );
+            decode_windows_1252(e.as_bytes())
+        }
+    };
+
+    if let Some(stripped) = content.strip_prefix('\u{feff}') {
+        debug!("Stripping UTF-8 BOM from {}", file_path.display());
+        content = stripped.to_string();
+    }
 
     let content = if !content.contains("<types>")
         && !content.contains("<spawnabletypes>")
@@ -445,6 +1179,29 @@ fn extract_xml_data(file_path: &Path) -> Result<Vec<String>, Box<dyn std::error:
     Ok(data)
 }
 
+/// Decodes Windows-1252 bytes into a string.
+///
+/// The bytes `0x80` to `0x9F` map to the typographic characters of the code page, all
+/// others to the Unicode code point of the same value. Unassigned bytes are kept as the
+/// matching control characters.
+fn decode_windows_1252(bytes: &[u8]) -> String {
+    const HIGH_CONTROLS: [char; 32] = [
+        '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}',
+        '\u{2021}', '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}',
+        '\u{8F}', '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}',
+        '\u{2014}', '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}',
+        '\u{178}',
+    ];
+
+    bytes
+        .iter()
+        .map(|&byte| match byte {
+            0x80..=0x9F => HIGH_CONTROLS[(byte - 0x80) as usize],
+            _ => byte as char,
+        })
+        .collect()
+}
+
 /// Extracts `Type` elements from a given XML file.
 ///
 /// This function reads the content of the specified XML file and extracts elements
@@ -467,7 +1224,76 @@ fn extract_types(file_path: &Path) -> Result<Vec<Type>, Box<dyn std::error::Erro
     Ok(types)
 }
 
-/// Extracts `SpawnableType` elements from a given XML file.
+/// Reads the `Type` elements of a types file, see `extract_types`.
+pub fn read_types_file(file_path: &Path) -> Result<Vec<Type>, ModError> {
+    extract_types(file_path).map_err(|e| {
+        error!("Failed to read {}: {}", file_path.display(), e);
+        ModError::ReadError
+    })
+}
+
+/// Returns the number of types, spawnable types and events in the CE files of a mod.
+///
+/// The files are read from the mod's `_ce` folder in `mpmissions/<map_name>` (see
+/// `ce_folder`). Missing or unparsable files count as empty.
+pub fn count_ce_entries(
+    workdir: &str,
+    map_name: &str,
+    mod_short_name: &str,
+    ce_dir: Option<&str>,
+) -> (usize, usize, usize) {
+    let ce_path = Path::new(workdir)
+        .join("mpmissions")
+        .join(map_name)
+        .join(ce_folder(mod_short_name, ce_dir));
+    let file = |suffix: &str| ce_path.join(format!("{}_{}.xml", mod_short_name, suffix));
+
+    (
+        extract_types(&file("types")).map_or(0, |types| types.len()),
+        extract_cfgspawnabletypes(&file("cfgspawnabletypes")).map_or(0, |types| types.len()),
+        extract_events(&file("events")).map_or(0, |events| events.len()),
+    )
+}
+
+/// Writes `Type` elements to a types file, see `write_to_file`.
+///
+/// In dry-run mode the intended write is only logged.
+pub fn write_types_file(file_path: &Path, types: Vec<Type>) -> Result<(), ModError> {
+    if is_dry_run() {
+        info!("[dry-run] Would write {}", file_path.display());
+        return Ok(());
+    }
+
+    ensure_writable(file_path)?;
+    write_to_file(&TypesWrapper { types }, file_path).map_err(|e| {
+        error!("Failed to write {}: {}", file_path.display(), e);
+        ModError::WriteError
+    })
+}
+
+/// Multiplies the `nominal` and `lifetime` values of the types by the given factors.
+///
+/// The results are rounded and clamped to `0..=i32::MAX`. A `min` above the scaled
+/// nominal is lowered to it, as the economy never spawns more than the nominal. Types
+/// without a value for a field are left unchanged.
+pub fn scale_types(types: &mut [Type], nominal: Option<f64>, lifetime: Option<f64>) {
+    let scale = |value: i32, factor: f64| {
+        (value as f64 * factor).round().clamp(0.0, i32::MAX as f64) as i32
+    };
+
+    for item in types {
+        if let (Some(factor), Some(value)) = (nominal, item.nominal) {
+            let scaled = scale(value, factor);
+            item.nominal = Some(scaled);
+            item.min = item.min.map(|min| min.min(scaled));
+        }
+        if let (Some(factor), Some(value)) = (lifetime, item.lifetime) {
+            item.lifetime = Some(scale(value, factor));
+        }
+    }
+}
+
+/// Extracts `SpawnableType` elements from a given XML file.
 ///
 /// This function reads the content of the specified XML file and extracts elements
 /// of type `<type>`. It uses the `extract_xml_data` function to get the raw XML strings
@@ -554,6 +1380,9 @@ pub fn analyze_types_folder(folder_path: &Path) -> AnalyzeResult {
                 .to_lowercase();
 
             debug!("File found: {}", file_name);
+            if !is_ce_file(&file_name) {
+                continue;
+            }
 
             if file_name.contains("types") && !file_name.contains("spawnable") {
                 debug!("Processing types file");
@@ -574,12 +1403,120 @@ pub fn analyze_types_folder(folder_path: &Path) -> AnalyzeResult {
     Ok((Some(types), Some(spawnable_types), Some(events)))
 }
 
+/// Returns the names of all types, spawnable types and events in a CE folder.
+///
+/// The folder is read with `analyze_types_folder`. If it cannot be read, an empty set is
+/// returned.
+pub fn ce_entry_names(folder_path: &Path) -> BTreeSet<String> {
+    let Ok((types, spawnable_types, events)) = analyze_types_folder(folder_path) else {
+        return BTreeSet::new();
+    };
+
+    let mut names = BTreeSet::new();
+    names.extend(types.unwrap_or_default().into_iter().map(|t| t.name));
+    names.extend(
+        spawnable_types
+            .unwrap_or_default()
+            .into_iter()
+            .map(|t| t.name),
+    );
+    names.extend(events.unwrap_or_default().into_iter().map(|e| e.name));
+    names
+}
+
+/// Moves the CE data of a mod from one short name to another.
+///
+/// The `_ce` folder (see `ce_folder`) and the `<old>_*.xml` files in it are renamed.
+/// In cfgeconomycore.xml, the `<!-- old -->` comment, the `folder` of the `<ce>` block
+/// and its `<file>` names are changed to the new short name. Other entries are left
+/// untouched. In dry-run mode the intended changes are only logged.
+pub fn rename_ce_short_name(
+    workdir: &str,
+    map_name: &str,
+    old_short_name: &str,
+    new_short_name: &str,
+    ce_dir: Option<&str>,
+) -> Result<(), ModError> {
+    let mission_path = Path::new(workdir).join("mpmissions").join(map_name);
+    let old_folder = ce_folder(old_short_name, ce_dir);
+    let new_folder = ce_folder(new_short_name, ce_dir);
+    let old_path = mission_path.join(&old_folder);
+    let new_path = mission_path.join(&new_folder);
+
+    if new_path.exists() {
+        error!("{} already exists", new_path.display());
+        return Err(ModError::CreateDirError);
+    }
+    if is_dry_run() {
+        info!(
+            "[dry-run] Would rename {} to {}",
+            old_path.display(),
+            new_path.display()
+        );
+        return Ok(());
+    }
+
+    rename(&old_path, &new_path).map_err(|e| {
+        error!("Failed to rename {}: {}", old_path.display(), e);
+        ModError::CopyFileError
+    })?;
+    let old_prefix = format!("{}_", old_short_name);
+    for entry in read_dir(&new_path).map_err(|_| ModError::ReadError)? {
+        let file_path = entry.map_err(|_| ModError::ReadError)?.path();
+        let Some(suffix) = file_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(&old_prefix))
+        else {
+            continue;
+        };
+        let target = new_path.join(format!("{}_{}", new_short_name, suffix));
+        rename(&file_path, &target).map_err(|e| {
+            error!("Failed to rename {}: {}", file_path.display(), e);
+            ModError::CopyFileError
+        })?;
+    }
+
+    let config_path = mission_path.join("cfgeconomycore.xml");
+    if !config_path.is_file() {
+        return Ok(());
+    }
+    let content = read_to_string(&config_path).map_err(|_| ModError::ReadError)?;
+    ensure_writable(&config_path)?;
+
+    let old_block = format!(r#"<ce folder="{}">"#, old_folder);
+    let mut in_block = false;
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let mut line = line.to_string();
+        if line.trim() == format!("<!-- {} -->", old_short_name) {
+            line = line.replace(old_short_name, new_short_name);
+        } else if line.contains(&old_block) {
+            line = line.replace(&old_block, &format!(r#"<ce folder="{}">"#, new_folder));
+            in_block = true;
+        }
+        if in_block {
+            line = line.replace(
+                &format!(r#"name="{}"#, old_prefix),
+                &format!(r#"name="{}_"#, new_short_name),
+            );
+            if line.contains("</ce>") {
+                in_block = false;
+            }
+        }
+        lines.push(line);
+    }
+
+    std::fs::write(&config_path, lines.join("\n")).map_err(|_| ModError::WriteError)?;
+
+    Ok(())
+}
+
 /// Retrieves the map name from the `serverDZ.cfg` file in the specified working directory.
 ///
-/// This function searches for the `serverDZ.cfg` file in the given working directory and
-/// extracts the map name using a regular expression. The map name is expected to be in the
-/// format `word.word` (e.g., `chernarusplus.chernarus`). If the file is not found or the
-/// map name cannot be extracted, an error is returned.
+/// The map name is the `template` value of the mission class (e.g.
+/// `dayzOffline.chernarusplus`), read with `parse_server_cfg`. If the file is not found
+/// or has no `template` entry, an error is returned.
 pub fn get_map_name(workdir: &str) -> Result<String, ModError> {
     let cfg_path = Path::new(workdir).join("serverDZ.cfg");
 
@@ -587,16 +1524,130 @@ pub fn get_map_name(workdir: &str) -> Result<String, ModError> {
         return Err(ModError::NotFound);
     }
 
-    let mut file = File::open(cfg_path).map_err(|_| ModError::NotFound)?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .map_err(|_| ModError::NotFound)?;
+    parse_server_cfg(&cfg_path)?
+        .remove("template")
+        .filter(|template| !template.is_empty())
+        .ok_or(ModError::NotFound)
+}
 
-    let re = Regex::new(r"(\w+\.\w+)").unwrap();
+/// Parses the `key = value;` entries of a `serverDZ.cfg` file.
+///
+/// Values are trimmed and surrounding quotes are removed. `//` and `/* */` comments are
+/// skipped, as are the `class` headers and braces around nested entries, so the
+/// `template` of the mission class is returned like a top-level entry. Array values
+/// (e.g. `motd[] = {"Welcome"};`) are kept as written. If a key occurs more than once,
+/// the last value wins.
+pub fn parse_server_cfg(path: &Path) -> Result<HashMap<String, String>, ModError> {
+    let content = read_to_string(path).map_err(|_| ModError::ReadError)?;
+    Ok(parse_server_cfg_content(&content))
+}
 
-    re.captures(&contents)
-        .map(|cap| cap[1].to_string())
-        .ok_or(ModError::NotFound)
+/// Parses the content of a `serverDZ.cfg` file, see `parse_server_cfg`.
+fn parse_server_cfg_content(content: &str) -> HashMap<String, String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    let mut chars = content.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            current.push(c);
+            if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                current.push(c);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        current.push(c);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                current.push(' ');
+            }
+            ';' => statements.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+
+    let mut values = HashMap::new();
+    for statement in statements {
+        let Some((head, value)) = statement.split_once('=') else {
+            continue;
+        };
+        // Drop class headers and braces before the key of a nested entry.
+        let key = head
+            .rsplit(['{', '}'])
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+            .last()
+            .unwrap_or_default();
+        if key.is_empty() {
+            continue;
+        }
+
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        values.insert(key.to_string(), value.to_string());
+    }
+
+    values
+}
+
+/// Resolves the map (mission folder) name for the given working directory.
+///
+/// If `map_override` is given, it is used verbatim as the `mpmissions/<map>` folder
+/// name, bypassing the detection from `serverDZ.cfg`. The override is validated to
+/// exist in the `mpmissions` directory. Otherwise the map name is detected via
+/// `get_map_name`.
+pub fn resolve_map_name(workdir: &str, map_override: Option<&str>) -> Result<String, ModError> {
+    match map_override {
+        Some(map_name) => {
+            let mission_path = Path::new(workdir).join("mpmissions").join(map_name);
+            if !mission_path.is_dir() {
+                error!("Mission folder not found: {}", mission_path.display());
+                return Err(ModError::PathError);
+            }
+            Ok(map_name.to_string())
+        }
+        None => get_map_name(workdir),
+    }
+}
+
+/// Lists the mission folders in the `mpmissions` directory of the working directory.
+///
+/// The names are sorted, so the order is stable across runs.
+pub fn list_mission_folders(workdir: &str) -> Result<Vec<String>, ModError> {
+    let missions_path = Path::new(workdir).join("mpmissions");
+    let mut missions: Vec<String> = read_dir(&missions_path)
+        .map_err(|_| ModError::NotFound)?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(String::from))
+        .collect();
+    missions.sort();
+    Ok(missions)
 }
 
 /// Writes serialized data to an XML file with proper formatting.
@@ -701,6 +1752,9 @@ fn format_events(xml: &str) -> String {
 /// structure. The files are named based on the provided `mod_short_name` and are saved in a
 /// subdirectory under the specified `workdir` and `map_name`. The function creates the necessary
 /// directories if they do not exist.
+///
+/// If `ce_dir` is given, the `_ce` folder is placed in that subfolder of the mission
+/// (see `ce_folder`).
 pub fn save_extracted_data(
     workdir: &str,
     mod_short_name: &str,
@@ -708,11 +1762,18 @@ pub fn save_extracted_data(
     types: Vec<Type>,
     spawnable_types: Vec<SpawnableType>,
     events: Vec<Event>,
+    ce_dir: Option<&str>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let base_path = Path::new(workdir)
         .join("mpmissions")
         .join(map_name)
-        .join(format!("{}_ce", mod_short_name));
+        .join(ce_folder(mod_short_name, ce_dir));
+
+    if is_dry_run() {
+        info!("[dry-run] Would write CE files to {}", base_path.display());
+        return Ok(());
+    }
+
     create_dir_all(&base_path)?;
 
     if !types.is_empty() {
@@ -737,6 +1798,59 @@ pub fn save_extracted_data(
     Ok(())
 }
 
+/// Adds the admin-added types of an existing `<mod_short_name>_types.xml` to `types`.
+///
+/// Types in the existing file whose name is not among the freshly extracted `types` were
+/// added by an admin and are appended after them, so they survive a re-extraction. Types
+/// of the mod itself are taken from `types`. If the file does not exist or cannot be
+/// parsed, `types` is returned unchanged.
+pub fn merge_custom_types(
+    workdir: &str,
+    map_name: &str,
+    mod_short_name: &str,
+    ce_dir: Option<&str>,
+    mut types: Vec<Type>,
+) -> Vec<Type> {
+    let types_file_path = Path::new(workdir)
+        .join("mpmissions")
+        .join(map_name)
+        .join(ce_folder(mod_short_name, ce_dir))
+        .join(format!("{}_types.xml", mod_short_name));
+    if !types_file_path.is_file() {
+        return types;
+    }
+
+    let existing = match extract_types(&types_file_path) {
+        Ok(existing) => existing,
+        Err(e) => {
+            warn!(
+                "Failed to read {}, custom types are not kept: {}",
+                types_file_path.display(),
+                e
+            );
+            return types;
+        }
+    };
+
+    let custom: Vec<Type> = existing
+        .into_iter()
+        .filter(|existing| {
+            !types
+                .iter()
+                .any(|t| t.name.eq_ignore_ascii_case(&existing.name))
+        })
+        .collect();
+    if !custom.is_empty() {
+        info!(
+            "Keeping {} custom types in {}",
+            custom.len(),
+            types_file_path.display()
+        );
+    }
+    types.extend(custom);
+    types
+}
+
 /// Retrieves the list of installed mods from the given profile.
 ///
 /// This function takes a `Profile` as input and returns a list of installed mods
@@ -752,12 +1866,18 @@ pub fn get_installed_mod_list(profile: Profile) -> Result<Vec<Value>, ModError>
 ///
 /// This function adds XML entries for types, spawnable types, and events files that exist
 /// for the given mod. The entries are added just before the closing </economycore> tag.
+/// If a CE block for the mod is already registered, the file is left untouched.
+///
+/// The `folder` attribute of the block is the mission-relative `_ce` folder, including
+/// `ce_dir` if given (see `ce_folder`).
 pub fn update_cfgeconomy(
     workdir: &str,
+    map_name: &str,
     mod_short_name: &str,
     types: Vec<Type>,
     spawnable_types: Vec<SpawnableType>,
     events: Vec<Event>,
+    ce_dir: Option<&str>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if types.is_empty() && spawnable_types.is_empty() && events.is_empty() {
         return Ok(());
@@ -765,10 +1885,17 @@ pub fn update_cfgeconomy(
 
     let file_path = Path::new(workdir)
         .join("mpmissions")
-        .join(get_map_name(workdir)?)
+        .join(map_name)
         .join("cfgeconomycore.xml");
 
     let content = read_to_string(&file_path)?;
+    ensure_writable(&file_path)?;
+    let folder = ce_folder(mod_short_name, ce_dir);
+    if content.contains(&format!("<ce folder=\"{}\">", folder)) {
+        debug!("CE entries for {} are already registered", mod_short_name);
+        return Ok(());
+    }
+
     let mut lines: Vec<String> = content.lines().map(String::from).collect();
 
     let end_idx = lines
@@ -776,40 +1903,254 @@ pub fn update_cfgeconomy(
         .position(|line| line.trim() == "</economycore>")
         .ok_or("Could not find closing economycore tag")?;
 
-    let mut new_content = vec![
+    let new_content = ce_block(
+        mod_short_name,
+        &folder,
+        !types.is_empty(),
+        !spawnable_types.is_empty(),
+        !events.is_empty(),
+    );
+
+    if is_dry_run() {
+        info!(
+            "[dry-run] Would register CE entries for {} in {}:",
+            mod_short_name,
+            file_path.display()
+        );
+        for line in &new_content {
+            info!("[dry-run] + {}", line);
+        }
+        return Ok(());
+    }
+
+    lines.splice(end_idx..end_idx, new_content);
+
+    std::fs::write(&file_path, lines.join("\n"))?;
+
+    Ok(())
+}
+
+/// Ensures that a file the tool is about to rewrite is not read-only.
+///
+/// Returns a `ModError::ReadOnlyFile` with the exact path and an OS-specific hint on how
+/// to make the file writable, instead of a cryptic permission error on write.
+fn ensure_writable(file_path: &Path) -> Result<(), ModError> {
+    let readonly = file_path
+        .metadata()
+        .map(|metadata| metadata.permissions().readonly())
+        .unwrap_or(false);
+
+    if readonly {
+        let path = file_path.display().to_string();
+        let hint = if cfg!(windows) {
+            format!("Run: attrib -R \"{}\"", path)
+        } else {
+            format!("Run: chmod u+w \"{}\"", path)
+        };
+        error!("{} is read-only", path);
+        return Err(ModError::ReadOnlyFile(path, hint));
+    }
+
+    Ok(())
+}
+
+/// Returns the `_ce` folder of a mod relative to the mission folder.
+///
+/// This is `<mod_short_name>_ce`, or `<ce_dir>/<mod_short_name>_ce` if the profile keeps
+/// its CE data in a subfolder like `custom`. The path always uses '/', as it is also the
+/// `folder` attribute in cfgeconomycore.xml.
+pub fn ce_folder(mod_short_name: &str, ce_dir: Option<&str>) -> String {
+    match normalize_ce_dir(ce_dir) {
+        Some(dir) => format!("{}/{}_ce", dir, mod_short_name),
+        None => format!("{}_ce", mod_short_name),
+    }
+}
+
+/// Returns the folder holding the `_ce` folders of a mission.
+///
+/// This is the mission folder itself, or its `ce_dir` subfolder if given.
+pub fn ce_base_path(mission_path: &Path, ce_dir: Option<&str>) -> PathBuf {
+    match normalize_ce_dir(ce_dir) {
+        Some(dir) => mission_path.join(dir),
+        None => mission_path.to_path_buf(),
+    }
+}
+
+/// Normalizes the CE subfolder of a profile to a '/'-separated relative path.
+fn normalize_ce_dir(ce_dir: Option<&str>) -> Option<String> {
+    ce_dir
+        .map(|dir| dir.replace('\\', "/").trim_matches('/').to_string())
+        .filter(|dir| !dir.is_empty())
+}
+
+/// Builds the tool-managed CE block for a mod.
+///
+/// The block consists of a `<!-- mod_short_name -->` comment followed by a
+/// `<ce folder="...">` element for the given `_ce` folder with one `<file>` entry per
+/// existing CE file.
+fn ce_block(
+    mod_short_name: &str,
+    folder: &str,
+    has_types: bool,
+    has_spawnable_types: bool,
+    has_events: bool,
+) -> Vec<String> {
+    let mut block = vec![
         format!("\t<!-- {} -->", mod_short_name),
-        format!("\t<ce folder=\"{}_ce\">", mod_short_name),
+        format!("\t<ce folder=\"{}\">", folder),
     ];
 
-    if !types.is_empty() {
-        new_content.push(format!(
+    if has_types {
+        block.push(format!(
             "\t\t<file name=\"{}_types.xml\" type=\"types\" />",
             mod_short_name
         ));
     }
 
-    if !spawnable_types.is_empty() {
-        new_content.push(format!(
+    if has_spawnable_types {
+        block.push(format!(
             "\t\t<file name=\"{}_cfgspawnabletypes.xml\" type=\"spawnabletypes\" />",
             mod_short_name
         ));
     }
-    if !events.is_empty() {
-        new_content.push(format!(
+    if has_events {
+        block.push(format!(
             "\t\t<file name=\"{}_events.xml\" type=\"events\" />",
             mod_short_name
         ));
     }
 
-    new_content.push("\t</ce>".to_string());
+    block.push("\t</ce>".to_string());
+    block
+}
+
+/// Rebuilds all tool-managed CE entries in cfgeconomycore.xml from scratch.
+///
+/// This function removes every block the tool manages (a `<!-- name -->` comment directly
+/// followed by `<ce folder="name_ce">`, or a `<ce>` element for the `_ce` folder of one
+/// of the given mods) including duplicates and orphans. It then re-adds one fresh block
+/// for each of the given mods whose `_ce` folder exists, based on the CE files present.
+/// Vanilla and manually added CE entries are preserved.
+///
+/// The `_ce` folders are looked up in `ce_dir` if given (see `ce_folder`).
+pub fn rebuild_cfgeconomy(
+    workdir: &str,
+    map_name: &str,
+    mod_short_names: &[String],
+    ce_dir: Option<&str>,
+) -> Result<(), ModError> {
+    let mission_path = Path::new(workdir).join("mpmissions").join(map_name);
+    let file_path = mission_path.join("cfgeconomycore.xml");
+
+    let content = read_to_string(&file_path).map_err(|_| ModError::ReadError)?;
+    ensure_writable(&file_path)?;
+
+    let managed_folders: Vec<String> = mod_short_names
+        .iter()
+        .map(|short| ce_folder(short, ce_dir))
+        .collect();
+    let mut lines = strip_managed_ce_blocks(&content, &managed_folders);
+
+    let end_idx = lines
+        .iter()
+        .position(|line| line.trim() == "</economycore>")
+        .ok_or(ModError::ParseError)?;
+
+    let mut new_content = Vec::new();
+    for mod_short_name in mod_short_names {
+        let folder = ce_folder(mod_short_name, ce_dir);
+        let ce_path = mission_path.join(&folder);
+        if !ce_path.is_dir() {
+            debug!("No CE folder found for {}", mod_short_name);
+            continue;
+        }
+
+        let has_file = |suffix: &str| {
+            ce_path
+                .join(format!("{}_{}.xml", mod_short_name, suffix))
+                .is_file()
+        };
+        let (has_types, has_spawnable_types, has_events) = (
+            has_file("types"),
+            has_file("cfgspawnabletypes"),
+            has_file("events"),
+        );
+        if has_types || has_spawnable_types || has_events {
+            new_content.extend(ce_block(
+                mod_short_name,
+                &folder,
+                has_types,
+                has_spawnable_types,
+                has_events,
+            ));
+        }
+    }
 
     lines.splice(end_idx..end_idx, new_content);
 
-    std::fs::write(&file_path, lines.join("\n"))?;
+    if is_dry_run() {
+        info!("[dry-run] Would rebuild {}", file_path.display());
+        return Ok(());
+    }
+
+    std::fs::write(&file_path, lines.join("\n")).map_err(|_| ModError::WriteError)?;
 
     Ok(())
 }
 
+/// Removes all tool-managed CE blocks from the content of a cfgeconomycore.xml file.
+///
+/// See `rebuild_cfgeconomy` for how tool-managed blocks are detected.
+fn strip_managed_ce_blocks(content: &str, managed_folders: &[String]) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut new_lines: Vec<String> = Vec::new();
+    let mut skip_lines = false;
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim();
+        index += 1;
+
+        if skip_lines {
+            if trimmed == "</ce>" {
+                skip_lines = false;
+            }
+            continue;
+        }
+
+        let comment_name = trimmed
+            .strip_prefix("<!-- ")
+            .and_then(|rest| rest.strip_suffix(" -->"));
+        if let Some(name) = comment_name {
+            let next = lines.get(index).map(|l| l.trim()).unwrap_or("");
+            let next_folder = next
+                .strip_prefix("<ce folder=\"")
+                .and_then(|rest| rest.strip_suffix("\">"));
+            let managed_folder = format!("{}_ce", name);
+            if next_folder.is_some_and(|folder| {
+                folder == managed_folder || folder.ends_with(&format!("/{}", managed_folder))
+            }) {
+                index += 1;
+                skip_lines = true;
+                continue;
+            }
+        }
+
+        let folder = trimmed
+            .strip_prefix("<ce folder=\"")
+            .and_then(|rest| rest.strip_suffix("\">"));
+        if folder.is_some_and(|folder| managed_folders.iter().any(|f| f == folder)) {
+            skip_lines = true;
+            continue;
+        }
+
+        new_lines.push(line.to_string());
+    }
+
+    new_lines
+}
+
 /// Removes bikey files associated with a mod from the server's keys directory.
 ///
 /// This function searches for bikey files in the mod's keys folder and removes their
@@ -828,11 +2169,17 @@ pub fn remove_keys_for_mod(workdir: &str, mod_path: &Path) -> Result<(), ModErro
             let entry = entry.unwrap();
             let source_path = entry.path();
 
-            if source_path.is_file() && source_path.extension().map_or(false, |ext| ext == "bikey")
-            {
+            if source_path.is_file() && source_path.extension().is_some_and(|ext| ext == "bikey") {
                 if let Some(key_name) = source_path.file_name() {
                     let target_path = workdir_keys.join(key_name);
                     if target_path.exists() {
+                        if is_dry_run() {
+                            info!(
+                                "[dry-run] Would remove bikey: {}",
+                                key_name.to_string_lossy()
+                            );
+                            continue;
+                        }
                         info!("Removing bikey: {}", key_name.to_string_lossy());
                         if let Err(e) = remove_file(&target_path) {
                             error!(
@@ -861,7 +2208,14 @@ pub fn remove_keys_for_mod(workdir: &str, mod_path: &Path) -> Result<(), ModErro
 ///     ... (various CE entries)
 /// </ce>
 /// ```
-pub fn remove_ce_entries(workdir: &str, map_name: &str, mod_short: &str) -> Result<(), ModError> {
+///
+/// If `ce_dir` is given, the `folder` attribute is matched including it (see `ce_folder`).
+pub fn remove_ce_entries(
+    workdir: &str,
+    map_name: &str,
+    mod_short: &str,
+    ce_dir: Option<&str>,
+) -> Result<(), ModError> {
     let config_path = Path::new(workdir)
         .join("mpmissions")
         .join(map_name)
@@ -872,6 +2226,7 @@ pub fn remove_ce_entries(workdir: &str, map_name: &str, mod_short: &str) -> Resu
     }
 
     let content = std::fs::read_to_string(&config_path).map_err(|_| ModError::ReadError)?;
+    ensure_writable(&config_path)?;
 
     let lines: Vec<&str> = content.lines().collect();
     let mut new_lines: Vec<String> = Vec::new();
@@ -879,7 +2234,10 @@ pub fn remove_ce_entries(workdir: &str, map_name: &str, mod_short: &str) -> Resu
 
     for line in lines {
         if line.contains(&format!("<!-- {} -->", mod_short))
-            || line.contains(&format!(r#"<ce folder="{}_ce">"#, mod_short))
+            || line.contains(&format!(
+                r#"<ce folder="{}">"#,
+                ce_folder(mod_short, ce_dir)
+            ))
         {
             skip_lines = true;
             continue;
@@ -895,12 +2253,145 @@ pub fn remove_ce_entries(workdir: &str, map_name: &str, mod_short: &str) -> Resu
         }
     }
 
+    if is_dry_run() {
+        info!(
+            "[dry-run] Would remove CE entries for {} from {}",
+            mod_short,
+            config_path.display()
+        );
+        return Ok(());
+    }
+
     std::fs::write(&config_path, new_lines.join("\n")).map_err(|_| ModError::WriteError)?;
 
     debug!("Successfully removed CE entries for {}", mod_short);
     Ok(())
 }
 
+/// Returns the CE files referenced in cfgeconomycore.xml that do not exist.
+///
+/// Every `<file name="..."/>` inside a `<ce folder="...">` element is resolved to
+/// `mpmissions/<map_name>/<folder>/<name>`. The paths of the missing files are returned
+/// in the order they are referenced, as the server fails to load its economy for them.
+pub fn find_dangling_ce_files(workdir: &str, map_name: &str) -> Result<Vec<PathBuf>, ModError> {
+    let mission_path = Path::new(workdir).join("mpmissions").join(map_name);
+    let file_path = mission_path.join("cfgeconomycore.xml");
+    let content = read_to_string(&file_path).map_err(|_| ModError::ReadError)?;
+
+    let mut reader = Reader::from_str(&content);
+    let mut folder: Option<String> = None;
+    let mut dangling = Vec::new();
+
+    loop {
+        let event = reader.read_event().map_err(|e| {
+            error!("Failed to parse {}: {}", file_path.display(), e);
+            ModError::ParseError
+        })?;
+        match event {
+            XmlEvent::Start(element) if element.name().as_ref() == b"ce" => {
+                folder = xml_attribute(&element, b"folder");
+            }
+            XmlEvent::End(element) if element.name().as_ref() == b"ce" => {
+                folder = None;
+            }
+            XmlEvent::Start(element) | XmlEvent::Empty(element)
+                if element.name().as_ref() == b"file" =>
+            {
+                let (Some(folder), Some(name)) = (&folder, xml_attribute(&element, b"name")) else {
+                    continue;
+                };
+                let path = mission_path.join(folder).join(name);
+                if !path.is_file() {
+                    dangling.push(path);
+                }
+            }
+            XmlEvent::Eof => break,
+            _ => (),
+        }
+    }
+
+    Ok(dangling)
+}
+
+/// Returns the unescaped value of an attribute of an XML element.
+fn xml_attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .filter_map(|attribute| attribute.ok())
+        .find(|attribute| attribute.key.as_ref() == name)
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(|value| value.to_string())
+}
+
+/// Validates all CE XML files generated by the tool for the given map.
+///
+/// This function walks `mpmissions/<map_name>` for `_ce` folders and attempts to parse
+/// every `*_types.xml`, `*_cfgspawnabletypes.xml` and `*_events.xml` file using the same
+/// extraction functions used during installation. Every file that fails to parse is
+/// returned together with the parse error, so corruption from a previous crash or a bad
+/// merge can be reported.
+///
+/// If `ce_dir` is given, the `_ce` folders inside that subfolder are checked as well.
+pub fn validate_ce_files(
+    workdir: &str,
+    map_name: &str,
+    ce_dir: Option<&str>,
+) -> Result<Vec<(PathBuf, String)>, ModError> {
+    let mission_path = Path::new(workdir).join("mpmissions").join(map_name);
+    if !mission_path.is_dir() {
+        return Err(ModError::PathError);
+    }
+
+    let mut failures = Vec::new();
+    let mut entries: Vec<_> = read_dir(&mission_path)
+        .map_err(|_| ModError::ReadError)?
+        .collect();
+    if let Some(ce_dir) = normalize_ce_dir(ce_dir) {
+        let ce_base_path = mission_path.join(ce_dir);
+        if ce_base_path.is_dir() {
+            entries.extend(read_dir(&ce_base_path).map_err(|_| ModError::ReadError)?);
+        }
+    }
+
+    for entry in entries {
+        let ce_path = entry.map_err(|_| ModError::ReadError)?.path();
+        let is_ce_folder = ce_path.is_dir()
+            && ce_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with("_ce"));
+        if !is_ce_folder {
+            continue;
+        }
+
+        for file in read_dir(&ce_path).map_err(|_| ModError::ReadError)? {
+            let file_path = file.map_err(|_| ModError::ReadError)?.path();
+            let file_name = file_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("")
+                .to_string();
+
+            let result = if file_name.ends_with("_cfgspawnabletypes.xml") {
+                extract_cfgspawnabletypes(&file_path).map(|_| ())
+            } else if file_name.ends_with("_types.xml") {
+                extract_types(&file_path).map(|_| ())
+            } else if file_name.ends_with("_events.xml") {
+                extract_events(&file_path).map(|_| ())
+            } else {
+                continue;
+            };
+
+            debug!("Validated {}", file_path.display());
+            if let Err(e) = result {
+                failures.push((file_path, e.to_string()));
+            }
+        }
+    }
+
+    Ok(failures)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -922,7 +2413,7 @@ mod tests {
         let mut file2 = File::create(sub_dir.join("file2.txt")).unwrap();
         writeln!(file2, "This is another test file.").unwrap();
 
-        match copy_dir(&source_dir, &target_dir) {
+        match copy_dir(&source_dir, &target_dir, None) {
             Ok(_) => {
                 assert!(target_dir.exists());
                 assert!(target_dir.join("file1.txt").exists());
@@ -935,4 +2426,937 @@ mod tests {
         fs::remove_dir_all(&source_dir).unwrap();
         fs::remove_dir_all(&target_dir).unwrap();
     }
+
+    #[test]
+    fn test_find_types_folder_ignores_decoys() {
+        let mod_path = std::env::temp_dir().join("dayz_tool_types_folder_decoy");
+        let _ = fs::remove_dir_all(&mod_path);
+        for decoy in ["scripts/4_World", "data", "docs"] {
+            fs::create_dir_all(mod_path.join(decoy)).unwrap();
+        }
+        fs::write(mod_path.join("scripts/4_World/archetypes.c"), "class A {}").unwrap();
+        fs::write(mod_path.join("data/prototypes.txt"), "prototypes").unwrap();
+        fs::write(mod_path.join("docs/types.md"), "# Types").unwrap();
+        assert_eq!(find_types_folder(&mod_path), None);
+
+        fs::create_dir_all(mod_path.join("extras/ServerFiles")).unwrap();
+        fs::write(
+            mod_path.join("extras/ServerFiles/Mod_Types.xml"),
+            "<types></types>",
+        )
+        .unwrap();
+        assert_eq!(
+            find_types_folder(&mod_path),
+            Some(mod_path.join("extras/ServerFiles"))
+        );
+
+        fs::remove_dir_all(&mod_path).unwrap();
+    }
+
+    #[test]
+    fn test_find_workshop_mods() {
+        let workshop = std::env::temp_dir().join("dayz_tool_workshop_scan_depth");
+        let _ = fs::remove_dir_all(&workshop);
+        fs::create_dir_all(workshop.join("@CF/addons")).unwrap();
+        fs::create_dir_all(workshop.join("maps/namalsk/@Namalsk Island/addons")).unwrap();
+        fs::create_dir_all(workshop.join("maps/@Deerisle")).unwrap();
+        fs::create_dir_all(workshop.join("tools/Admin Tools")).unwrap();
+        fs::write(
+            workshop.join("tools/Admin Tools/meta.cpp"),
+            "name = \"Admin\";",
+        )
+        .unwrap();
+
+        assert_eq!(
+            find_workshop_mods(&workshop, 1),
+            vec![
+                workshop.join("@CF"),
+                workshop.join("maps"),
+                workshop.join("tools")
+            ]
+        );
+        assert_eq!(
+            find_workshop_mods(&workshop, 2),
+            vec![
+                workshop.join("@CF"),
+                workshop.join("maps/@Deerisle"),
+                workshop.join("maps/namalsk"),
+                workshop.join("tools/Admin Tools"),
+            ]
+        );
+        assert_eq!(
+            find_workshop_mods(&workshop, 3),
+            vec![
+                workshop.join("@CF"),
+                workshop.join("maps/@Deerisle"),
+                workshop.join("maps/namalsk/@Namalsk Island"),
+                workshop.join("tools/Admin Tools"),
+            ]
+        );
+
+        fs::remove_dir_all(&workshop).unwrap();
+    }
+
+    #[test]
+    fn test_find_dangling_ce_files() {
+        let workdir = std::env::temp_dir().join("dayz_tool_dangling_ce");
+        let _ = fs::remove_dir_all(&workdir);
+        let mission = workdir.join("mpmissions/dayzOffline.chernarusplus");
+        fs::create_dir_all(mission.join("We_ce")).unwrap();
+        fs::create_dir_all(mission.join("custom/Fo_ce")).unwrap();
+        fs::write(mission.join("We_ce/We_types.xml"), "<types></types>").unwrap();
+        fs::write(mission.join("custom/Fo_ce/Fo_types.xml"), "<types></types>").unwrap();
+        fs::write(
+            mission.join("cfgeconomycore.xml"),
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes" ?>
+<economycore>
+	<classes>
+		<rootclass name="DefaultWeapon" />
+	</classes>
+	<!-- We -->
+	<ce folder="We_ce">
+		<file name="We_types.xml" type="types" />
+		<file name="We_events.xml" type="events" />
+	</ce>
+	<ce folder="custom/Fo_ce">
+		<file name="Fo_types.xml" type="types"></file>
+	</ce>
+	<ce folder="Ba_ce"><file name="Ba_types.xml" type="types"/></ce>
+</economycore>
+"#,
+        )
+        .unwrap();
+
+        let dangling =
+            find_dangling_ce_files(workdir.to_str().unwrap(), "dayzOffline.chernarusplus").unwrap();
+        assert_eq!(
+            dangling,
+            vec![
+                mission.join("We_ce").join("We_events.xml"),
+                mission.join("Ba_ce").join("Ba_types.xml"),
+            ]
+        );
+
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_copy_dir_progress() {
+        let dir = std::env::temp_dir().join("dayz_tool_copy_dir_progress");
+        let _ = fs::remove_dir_all(&dir);
+        let source_dir = dir.join("@Small");
+        fs::create_dir_all(source_dir.join("data")).unwrap();
+        for i in 0..50 {
+            fs::write(source_dir.join(format!("data/file{}.paa", i)), "texture").unwrap();
+        }
+        fs::write(source_dir.join("meta.cpp"), "name = \"Small\";").unwrap();
+
+        let total = get_dir_size(&source_dir);
+        let progress = ProgressBar::new(total, 30, "Copying @Small", Arc::new(THEME.clone()))
+            .with_interactive(false);
+        copy_dir(&source_dir, &dir.join("workdir/@Small"), Some(&progress)).unwrap();
+
+        assert_eq!(progress.position(), total);
+        assert_eq!(total, 50 * 7 + 15);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate_ce_files_flags_malformed_file() {
+        let workdir = std::env::temp_dir().join("dayz_tool_validate_xml");
+        let ce_path = workdir.join("mpmissions/dayzOffline.chernarusplus/Tes_ce");
+        let _ = fs::remove_dir_all(&workdir);
+        fs::create_dir_all(&ce_path).unwrap();
+
+        fs::write(
+            ce_path.join("Tes_types.xml"),
+            "<types>\n<type name=\"Apple\">\n<nominal>10</nominal>\n</type>\n</types>\n",
+        )
+        .unwrap();
+        fs::write(
+            ce_path.join("Tes_events.xml"),
+            "<events>\n<event name=\"Broken\">\n<nominal>abc</nominal>\n</event>\n</events>\n",
+        )
+        .unwrap();
+
+        let failures =
+            validate_ce_files(workdir.to_str().unwrap(), "dayzOffline.chernarusplus", None)
+                .unwrap();
+
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, ce_path.join("Tes_events.xml"));
+
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_server_cfg() {
+        let content = r#"hostname = "Karnes' DayZ; PvE";  // Server name
+password = "";              // Password to connect
+passwordAdmin = "secret";
+maxPlayers = 60; /* Maximum amount of players */
+instanceId = 1;
+verifySignatures = 2;
+motd[] = {"Welcome", "Have fun"};
+/*
+template = "commented.out";
+*/
+class Missions
+{
+    class DayZ
+    {
+        template="dayzOffline.enoch"; // Mission to load on server startup
+    };
+};
+"#;
+
+        let values = parse_server_cfg_content(content);
+
+        assert_eq!(values["hostname"], "Karnes' DayZ; PvE");
+        assert_eq!(values["password"], "");
+        assert_eq!(values["maxPlayers"], "60");
+        assert_eq!(values["instanceId"], "1");
+        assert_eq!(values["verifySignatures"], "2");
+        assert_eq!(values["motd[]"], r#"{"Welcome", "Have fun"}"#);
+        assert_eq!(values["template"], "dayzOffline.enoch");
+        assert_eq!(values.len(), 8);
+    }
+
+    #[test]
+    fn test_get_map_name_reads_template() {
+        let workdir = std::env::temp_dir().join("dayz_tool_map_template");
+        let _ = fs::remove_dir_all(&workdir);
+        fs::create_dir_all(&workdir).unwrap();
+        let workdir_path = workdir.to_str().unwrap();
+
+        // The regex used before picked up the first dotted word instead of the template.
+        fs::write(
+            workdir.join("serverDZ.cfg"),
+            "hostname = \"play.example.com\";\nclass Missions\n{\n    class DayZ\n    {\n        template=\"dayzOffline.chernarusplus\";\n    };\n};\n",
+        )
+        .unwrap();
+        assert_eq!(
+            get_map_name(workdir_path).unwrap(),
+            "dayzOffline.chernarusplus"
+        );
+
+        fs::write(workdir.join("serverDZ.cfg"), "hostname = \"Server\";\n").unwrap();
+        assert_eq!(get_map_name(workdir_path), Err(ModError::NotFound));
+
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_checksum_progress_reaches_file_count() {
+        let mod_path = std::env::temp_dir().join("dayz_tool_checksum_progress");
+        let _ = fs::remove_dir_all(&mod_path);
+        fs::create_dir_all(mod_path.join("addons")).unwrap();
+        for i in 0..5 {
+            fs::write(mod_path.join(format!("addons/file{}.pbo", i)), "data").unwrap();
+        }
+
+        let files = collect_mod_files(&mod_path);
+        let progress =
+            ProgressBar::new(files.len() as u64, 30, "Checking", Arc::new(THEME.clone()));
+        let pool = ThreadPool::new(2);
+        static CANCEL: AtomicBool = AtomicBool::new(false);
+        let checksums =
+            calculate_checksums(&mod_path, files, &pool, &progress, &CANCEL, false).unwrap();
+
+        assert_eq!(checksums.len(), 5);
+        assert_eq!(progress.position(), 5);
+
+        fs::remove_dir_all(&mod_path).unwrap();
+    }
+
+    #[test]
+    fn test_mod_checksums_metadata() {
+        let mod_path = std::env::temp_dir().join("dayz_tool_checksum_metadata");
+        let _ = fs::remove_dir_all(&mod_path);
+        fs::create_dir_all(mod_path.join("addons")).unwrap();
+        fs::write(mod_path.join("addons/core.pbo"), "data").unwrap();
+
+        let pool = ThreadPool::new(2);
+        let checksums = calculate_mod_checksums(&mod_path, &pool, false).unwrap();
+        assert_eq!(checksums.len(), 2);
+
+        let file = checksums.iter().find(|c| !c.is_dir).unwrap();
+        assert_eq!(file.path, PathBuf::from("addons/core.pbo"));
+        assert_eq!(
+            file.modified,
+            fs::metadata(mod_path.join("addons/core.pbo"))
+                .unwrap()
+                .modified()
+                .unwrap()
+        );
+        let dir = checksums.iter().find(|c| c.is_dir).unwrap();
+        assert_eq!(dir.path, PathBuf::from("addons"));
+        assert_eq!(
+            dir.modified,
+            fs::metadata(mod_path.join("addons"))
+                .unwrap()
+                .modified()
+                .unwrap()
+        );
+
+        fs::remove_dir_all(&mod_path).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_map_name_uses_override() {
+        let workdir = std::env::temp_dir().join("dayz_tool_map_override");
+        let _ = fs::remove_dir_all(&workdir);
+        fs::create_dir_all(workdir.join("mpmissions/custom.enoch")).unwrap();
+        fs::write(
+            workdir.join("serverDZ.cfg"),
+            "template=\"dayzOffline.chernarusplus\";",
+        )
+        .unwrap();
+        let workdir_str = workdir.to_str().unwrap();
+
+        assert_eq!(
+            resolve_map_name(workdir_str, None).unwrap(),
+            "dayzOffline.chernarusplus"
+        );
+        assert_eq!(
+            resolve_map_name(workdir_str, Some("custom.enoch")).unwrap(),
+            "custom.enoch"
+        );
+        assert_eq!(
+            resolve_map_name(workdir_str, Some("missing.map")),
+            Err(ModError::PathError)
+        );
+
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_exceeds_command_line_limit() {
+        let few_mods: Vec<String> = (0..10).map(|i| format!("@Mod{}", i)).collect();
+        let many_mods: Vec<String> = (0..500)
+            .map(|i| format!("@Some Long Mod Name {}", i))
+            .collect();
+
+        assert!(!exceeds_command_line_limit(&format!(
+            "\"-mod={}\"",
+            few_mods.join(";")
+        )));
+        assert!(exceeds_command_line_limit(&format!(
+            "\"-mod={}\"",
+            many_mods.join(";")
+        )));
+    }
+
+    #[test]
+    fn test_rebuild_cfgeconomy_from_messy_file() {
+        let workdir = std::env::temp_dir().join("dayz_tool_rebuild_ce");
+        let mission = workdir.join("mpmissions/dayzOffline.chernarusplus");
+        let _ = fs::remove_dir_all(&workdir);
+        fs::create_dir_all(mission.join("CF_ce")).unwrap();
+        fs::create_dir_all(mission.join("BBP_ce")).unwrap();
+        fs::write(mission.join("CF_ce/CF_types.xml"), "").unwrap();
+        fs::write(mission.join("BBP_ce/BBP_types.xml"), "").unwrap();
+        fs::write(mission.join("BBP_ce/BBP_events.xml"), "").unwrap();
+
+        let messy = [
+            "<economycore>",
+            "\t<ce folder=\"custom\">",
+            "\t\t<file name=\"custom_types.xml\" type=\"types\" />",
+            "\t</ce>",
+            "\t<!-- CF -->",
+            "\t<ce folder=\"CF_ce\">",
+            "\t\t<file name=\"CF_types.xml\" type=\"types\" />",
+            "\t</ce>",
+            "\t<!-- CF -->",
+            "\t<ce folder=\"CF_ce\">",
+            "\t\t<file name=\"CF_types.xml\" type=\"types\" />",
+            "\t</ce>",
+            "\t<!-- Old -->",
+            "\t<ce folder=\"Old_ce\">",
+            "\t\t<file name=\"Old_types.xml\" type=\"types\" />",
+            "\t</ce>",
+            "\t<ce folder=\"BBP_ce\">",
+            "\t</ce>",
+            "</economycore>",
+        ]
+        .join("\n");
+        fs::write(mission.join("cfgeconomycore.xml"), messy).unwrap();
+
+        rebuild_cfgeconomy(
+            workdir.to_str().unwrap(),
+            "dayzOffline.chernarusplus",
+            &["CF".to_string(), "BBP".to_string()],
+            None,
+        )
+        .unwrap();
+
+        let expected = [
+            "<economycore>",
+            "\t<ce folder=\"custom\">",
+            "\t\t<file name=\"custom_types.xml\" type=\"types\" />",
+            "\t</ce>",
+            "\t<!-- CF -->",
+            "\t<ce folder=\"CF_ce\">",
+            "\t\t<file name=\"CF_types.xml\" type=\"types\" />",
+            "\t</ce>",
+            "\t<!-- BBP -->",
+            "\t<ce folder=\"BBP_ce\">",
+            "\t\t<file name=\"BBP_types.xml\" type=\"types\" />",
+            "\t\t<file name=\"BBP_events.xml\" type=\"events\" />",
+            "\t</ce>",
+            "</economycore>",
+        ]
+        .join("\n");
+        assert_eq!(
+            fs::read_to_string(mission.join("cfgeconomycore.xml")).unwrap(),
+            expected
+        );
+
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_startup_parameter_skips_missing_mod_folder() {
+        let workdir = std::env::temp_dir().join("dayz_tool_missing_mod_folder");
+        let _ = fs::remove_dir_all(&workdir);
+        fs::create_dir_all(workdir.join("@CF")).unwrap();
+        fs::create_dir_all(workdir.join("@Admin")).unwrap();
+
+        let profile = Profile {
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            installed_mods: vec![
+                serde_json::json!("@CF"),
+                serde_json::json!("@Deleted"),
+                serde_json::json!("@Admin"),
+            ],
+            server_mods: vec!["@Admin".to_string()],
+            ..Default::default()
+        };
+        let mods: Vec<String> = ["@CF", "@Deleted", "@Admin"].map(String::from).to_vec();
+
+        assert_eq!(missing_mod_folders(&profile, &mods), vec!["@Deleted"]);
+        let parameters = parse_startup_parameter(&profile).unwrap();
+        assert_eq!(parameters.mods, "\"-mod=@CF\"");
+        assert_eq!(parameters.server_mods, "\"-serverMod=@Admin\"");
+
+        fs::remove_dir_all(&workdir).unwrap();
+        assert!(missing_mod_folders(&profile, &mods).is_empty());
+    }
+
+    #[test]
+    fn test_build_startup_parameters_with_server_mod() {
+        let installed_mods = vec![
+            "@CF".to_string(),
+            "@AdminTools".to_string(),
+            "@Trader".to_string(),
+        ];
+        let server_mods = vec!["@AdminTools".to_string()];
+
+        let parameters = build_startup_parameters(&installed_mods, &server_mods);
+
+        assert_eq!(parameters.mods, "\"-mod=@CF;@Trader\"");
+        assert_eq!(parameters.server_mods, "\"-serverMod=@AdminTools\"");
+        assert_eq!(
+            parameters.to_string(),
+            "\"-mod=@CF;@Trader\" \"-serverMod=@AdminTools\""
+        );
+    }
+
+    #[test]
+    fn test_build_startup_parameters_without_mods() {
+        let parameters = build_startup_parameters(&[], &[]);
+        assert_eq!(parameters, StartupParameters::default());
+        assert_eq!(parameters.to_string(), "");
+
+        let blank = vec!["".to_string(), "  ".to_string()];
+        assert_eq!(
+            build_startup_parameters(&blank, &[]),
+            StartupParameters::default()
+        );
+    }
+
+    #[test]
+    fn test_build_startup_parameters_single_mod() {
+        let parameters = build_startup_parameters(&["@CF".to_string()], &[]);
+        assert_eq!(parameters.mods, "\"-mod=@CF\"");
+        assert_eq!(parameters.server_mods, "");
+    }
+
+    #[test]
+    fn test_build_startup_parameters_skips_blank_names() {
+        let installed_mods = vec![
+            "@CF".to_string(),
+            "".to_string(),
+            " @Trader ".to_string(),
+            "   ".to_string(),
+            "@Expansion".to_string(),
+        ];
+
+        let parameters = build_startup_parameters(&installed_mods, &[]);
+
+        assert_eq!(parameters.mods, "\"-mod=@CF;@Trader;@Expansion\"");
+        assert!(!parameters.mods.contains(";;"));
+        assert!(!parameters.mods.ends_with(";\""));
+    }
+
+    #[test]
+    fn test_remove_ce_entries_read_only_file() {
+        let workdir = std::env::temp_dir().join("dayz_tool_read_only_ce");
+        let mission = workdir.join("mpmissions/dayzOffline.chernarusplus");
+        let _ = fs::remove_dir_all(&workdir);
+        fs::create_dir_all(&mission).unwrap();
+        let file_path = mission.join("cfgeconomycore.xml");
+        fs::write(&file_path, "<economycore>\n</economycore>").unwrap();
+
+        let mut permissions = fs::metadata(&file_path).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&file_path, permissions.clone()).unwrap();
+
+        let result = remove_ce_entries(
+            workdir.to_str().unwrap(),
+            "dayzOffline.chernarusplus",
+            "CF",
+            None,
+        );
+
+        match result {
+            Err(ModError::ReadOnlyFile(path, hint)) => {
+                assert_eq!(path, file_path.display().to_string());
+                assert!(hint.contains(&path));
+            }
+            other => panic!("Expected ReadOnlyFile error, got {:?}", other),
+        }
+
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        fs::set_permissions(&file_path, permissions).unwrap();
+        fs::remove_dir_all(&workdir).unwrap();
+    }
+
+    #[test]
+    fn test_order_by_load_order() {
+        let installed = vec![
+            "@Trader".to_string(),
+            "@CF".to_string(),
+            "@Expansion".to_string(),
+            "@BaseBuildingPlus".to_string(),
+        ];
+        let load_order = vec![
+            "@CF".to_string(),
+            "@Missing".to_string(),
+            "@Expansion".to_string(),
+        ];
+
+        assert_eq!(
+            order_by_load_order(&installed, &load_order),
+            vec!["@CF", "@Expansion", "@Trader", "@BaseBuildingPlus"]
+        );
+    }
+
+    #[test]
+    fn test_extract_types_with_bom() {
+        let dir = std::env::temp_dir().join("dayz_tool_bom_types");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file_path = dir.join("types.xml");
+        fs::write(
+            &file_path,
+            "\u{feff}<type name=\"Apple\">\n    <nominal>10</nominal>\n</type>\n<type name=\"Pear\">\n    <nominal>5</nominal>\n</type>\n",
+        )
+        .unwrap();
+
+        let types = extract_types(&file_path).unwrap();
+
+        assert_eq!(types.len(), 2);
+        assert_eq!(types[0].name, "Apple");
+        assert_eq!(types[0].nominal, Some(10));
+        assert_eq!(types[1].name, "Pear");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_extract_types_from_windows_1252() {
+        let dir = std::env::temp_dir().join("dayz_tool_cp1252_types");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let file_path = dir.join("types.xml");
+        let mut content = b"<?xml version=\"1.0\" encoding=\"windows-1252\"?>\n<types>\n".to_vec();
+        content.extend_from_slice(b"<!-- \x93Caf\xe9\x94 items -->\n");
+        content
+            .extend_from_slice(b"<type name=\"Caf\xe9Mug\">\n    <nominal>3</nominal>\n</type>\n");
+        content.extend_from_slice(b"</types>\n");
+        fs::write(&file_path, content).unwrap();
+
+        let types = extract_types(&file_path).unwrap();
+
+        assert_eq!(types.len(), 1);
+        assert_eq!(types[0].name, "Caf\u{e9}Mug");
+        assert_eq!(types[0].nominal, Some(3));
+        assert_eq!(
+            decode_windows_1252(b"\x93\x80\xe9\x94"),
+            "\u{201C}\u{20AC}\u{e9}\u{201D}"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_merge_custom_types_keeps_admin_types() {
+        let base = std::env::temp_dir().join("dayz_tool_merge_types");
+        let _ = fs::remove_dir_all(&base);
+        let workdir = base.join("workdir");
+        let source = base.join("types.xml");
+        let ce_types = workdir.join("mpmissions/dayzOffline.enoch/Test_ce/Test_types.xml");
+        fs::create_dir_all(&base).unwrap();
+        let type_xml = |name: &str, nominal: i32| {
+            format!(
+                "<type name=\"{}\">\n    <nominal>{}</nominal>\n</type>\n",
+                name, nominal
+            )
+        };
+        let workdir_path = workdir.to_str().unwrap();
+
+        fs::write(&source, type_xml("Apple", 10) + &type_xml("Pear", 5)).unwrap();
+        let types = extract_types(&source).unwrap();
+        save_extracted_data(
+            workdir_path,
+            "Test",
+            "dayzOffline.enoch",
+            types,
+            vec![],
+            vec![],
+            None,
+        )
+        .unwrap();
+
+        let mut edited = fs::read_to_string(&ce_types).unwrap();
+        edited = edited.replace("</types>", &(type_xml("AdminCrate", 1) + "</types>"));
+        fs::write(&ce_types, edited).unwrap();
+
+        fs::write(&source, type_xml("Apple", 10) + &type_xml("Pear", 8)).unwrap();
+        let types = merge_custom_types(
+            workdir_path,
+            "dayzOffline.enoch",
+            "Test",
+            None,
+            extract_types(&source).unwrap(),
+        );
+        save_extracted_data(
+            workdir_path,
+            "Test",
+            "dayzOffline.enoch",
+            types,
+            vec![],
+            vec![],
+            None,
+        )
+        .unwrap();
+
+        let saved = extract_types(&ce_types).unwrap();
+        let names: Vec<&str> = saved.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Apple", "Pear", "AdminCrate"]);
+        assert_eq!(saved[1].nominal, Some(8));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_build_steamcmd_command() {
+        let config = SteamCmdConfig {
+            path: "/opt/steamcmd/steamcmd.sh".to_string(),
+            login: "anonymous".to_string(),
+            command: "{steamcmd} +force_install_dir {workshop} +login {login} {items} +quit"
+                .to_string(),
+            keyring: None,
+        };
+        let ids = vec!["1559212036".to_string(), "1564026768".to_string()];
+
+        let command = build_steamcmd_command(&config, "/srv/workshop", &ids, None).unwrap();
+
+        assert_eq!(
+            command,
+            vec![
+                "/opt/steamcmd/steamcmd.sh",
+                "+force_install_dir",
+                "/srv/workshop",
+                "+login",
+                "anonymous",
+                "+workshop_download_item",
+                "221100",
+                "1559212036",
+                "+workshop_download_item",
+                "221100",
+                "1564026768",
+                "+quit",
+            ]
+        );
+
+        let invalid = build_steamcmd_command(&config, "/srv/workshop", &["12ab".to_string()], None);
+        assert_eq!(
+            invalid.unwrap_err(),
+            ModError::InvalidWorkshopId("12ab".to_string())
+        );
+    }
+
+    #[test]
+    fn test_steamcmd_runscript() {
+        let command: Vec<String> = [
+            "steamcmd",
+            "+login",
+            "server_admin",
+            "hunter 2",
+            "+workshop_download_item",
+            "221100",
+            "1559212036",
+            "+quit",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        let (program, script) = steamcmd_runscript(&command);
+
+        assert_eq!(program, ["steamcmd"]);
+        assert_eq!(
+            script,
+            "login server_admin \"hunter 2\"\nworkshop_download_item 221100 1559212036\nquit\n"
+        );
+    }
+
+    #[test]
+    fn test_build_steamcmd_command_with_password() {
+        let config = SteamCmdConfig {
+            path: "steamcmd".to_string(),
+            login: "server_admin".to_string(),
+            command:
+                "{steamcmd} +force_install_dir {workshop} +login {login} {password} {items} +quit"
+                    .to_string(),
+            keyring: Some("dayz-tool-cli".to_string()),
+        };
+        let ids = vec!["1559212036".to_string()];
+
+        let with_password =
+            build_steamcmd_command(&config, "/srv/workshop", &ids, Some("hunter2")).unwrap();
+        assert_eq!(with_password[3..6], ["+login", "server_admin", "hunter2"]);
+        assert_eq!(with_password[6], "+workshop_download_item");
+
+        let without_password =
+            build_steamcmd_command(&config, "/srv/workshop", &ids, None).unwrap();
+        assert_eq!(
+            without_password[3..6],
+            ["+login", "server_admin", "+workshop_download_item"]
+        );
+    }
+
+    #[test]
+    fn test_check_distinct_paths() {
+        let base = std::env::temp_dir().join("dayz_tool_distinct_paths");
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("workshop")).unwrap();
+        fs::create_dir_all(base.join("workdir")).unwrap();
+
+        let profile = |workshop: &Path, workdir: &Path| Profile {
+            workshop_path: workshop.to_str().unwrap().to_string(),
+            workdir_path: workdir.to_str().unwrap().to_string(),
+            ..Default::default()
+        };
+
+        assert!(
+            check_distinct_paths(&profile(&base.join("workshop"), &base.join("workdir"))).is_ok()
+        );
+        assert!(
+            check_distinct_paths(&profile(&base.join("workdir"), &base.join("workdir"))).is_err()
+        );
+        assert!(check_distinct_paths(&profile(&base, &base.join("workdir"))).is_err());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_copy_error_detects_disk_full() {
+        let target = Path::new("/srv/dayz/@CF");
+        let expected = ModError::DiskFull("/srv/dayz/@CF".to_string());
+
+        let no_space = if cfg!(windows) { 112 } else { 28 };
+        let error = std::io::Error::from_raw_os_error(no_space);
+        assert_eq!(copy_error(&error, target), expected);
+        assert_eq!(
+            expected.to_string(),
+            "Not enough disk space to copy to /srv/dayz/@CF. Free up space on the filesystem of /srv/dayz/@CF and try again"
+        );
+
+        let error = std::io::Error::new(std::io::ErrorKind::StorageFull, "no space left");
+        assert_eq!(copy_error(&error, target), expected);
+
+        let error = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert_eq!(copy_error(&error, target), ModError::CopyFileError);
+    }
+
+    #[test]
+    fn test_check_mod_branch() {
+        let base = std::env::temp_dir().join("dayz_tool_mod_branch");
+        let _ = fs::remove_dir_all(&base);
+        let mod_path = base.join("@Experimental");
+        fs::create_dir_all(&mod_path).unwrap();
+        fs::write(
+            mod_path.join("meta.cpp"),
+            "protocol = 1;\npublishedid = 1559212036;\nname = \"Experimental\";\nbranch = \"Experimental\";\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            check_mod_branch(Some("stable"), &mod_path),
+            Some("experimental".to_string())
+        );
+        assert_eq!(check_mod_branch(Some("experimental"), &mod_path), None);
+        assert_eq!(check_mod_branch(None, &mod_path), None);
+
+        let downloaded = base.join("steamapps/workshop/content/1024020/1559212036");
+        fs::create_dir_all(&downloaded).unwrap();
+        assert_eq!(
+            check_mod_branch(Some("stable"), &downloaded),
+            Some("experimental".to_string())
+        );
+        assert_eq!(
+            check_mod_branch(Some("stable"), &base.join("@Unknown")),
+            None
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_checksum_cache_shared_across_profiles() {
+        let base = std::env::temp_dir().join("dayz_tool_checksum_cache");
+        let _ = fs::remove_dir_all(&base);
+        let workshop_mod = base.join("!Workshop/@Shared");
+        let big_file = workshop_mod.join("addons/big.pbo");
+        let content = vec![b'a'; 2 * 1024 * 1024];
+        fs::create_dir_all(big_file.parent().unwrap()).unwrap();
+        fs::write(&big_file, &content).unwrap();
+        for server in ["chernarus", "livonia"] {
+            let installed = base.join(server).join("@Shared/addons");
+            fs::create_dir_all(&installed).unwrap();
+            fs::write(installed.join("big.pbo"), &content).unwrap();
+        }
+
+        let pool = ThreadPool::new(2);
+        assert!(
+            compare_mod_versions(&workshop_mod, &base.join("chernarus/@Shared"), &pool).unwrap()
+        );
+
+        let key = big_file.canonicalize().unwrap();
+        let cached = CHECKSUM_CACHE.lock().unwrap().entries[&key].clone();
+        assert_eq!(cached.hash, calculate_file_hash(&big_file).unwrap());
+
+        // Change the content but keep size and modification time, so only a cache hit
+        // still reports the shared mod as unchanged.
+        let modified = fs::metadata(&big_file).unwrap().modified().unwrap();
+        fs::write(&big_file, vec![b'b'; 2 * 1024 * 1024]).unwrap();
+        File::options()
+            .write(true)
+            .open(&big_file)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        assert!(compare_mod_versions(&workshop_mod, &base.join("livonia/@Shared"), &pool).unwrap());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_checksum_skips_unreadable_file() {
+        let mod_path = std::env::temp_dir().join("dayz_tool_checksum_unreadable");
+        let _ = fs::remove_dir_all(&mod_path);
+        fs::create_dir_all(mod_path.join("addons")).unwrap();
+        for i in 0..20 {
+            fs::write(mod_path.join(format!("addons/file{}.pbo", i)), "data").unwrap();
+        }
+
+        static CANCEL: AtomicBool = AtomicBool::new(false);
+        let pool = ThreadPool::new(2);
+        let progress = ProgressBar::new(20, 30, "Checking", Arc::new(THEME.clone()));
+
+        // The file disappears between listing and reading, like a locked file.
+        let files = collect_mod_files(&mod_path);
+        fs::remove_file(mod_path.join("addons/file3.pbo")).unwrap();
+        let checksums =
+            calculate_checksums(&mod_path, files, &pool, &progress, &CANCEL, false).unwrap();
+
+        assert_eq!(checksums.len(), 20);
+        let unreadable: Vec<_> = checksums
+            .iter()
+            .filter(|c| c.hash == UNREADABLE_HASH)
+            .map(|c| c.path.clone())
+            .collect();
+        assert_eq!(unreadable, vec![PathBuf::from("addons/file3.pbo")]);
+        assert_eq!(progress.position(), 20);
+
+        let files = collect_mod_files(&mod_path);
+        for i in 0..5 {
+            let _ = fs::remove_file(mod_path.join(format!("addons/file{}.pbo", i)));
+        }
+        let progress = ProgressBar::new(20, 30, "Checking", Arc::new(THEME.clone()));
+        let result = calculate_checksums(&mod_path, files, &pool, &progress, &CANCEL, false);
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+
+        fs::remove_dir_all(&mod_path).unwrap();
+    }
+
+    #[test]
+    fn test_checksum_calculation_cancelled() {
+        let mod_path = std::env::temp_dir().join("dayz_tool_checksum_cancel");
+        let _ = fs::remove_dir_all(&mod_path);
+        fs::create_dir_all(mod_path.join("addons")).unwrap();
+        for i in 0..20 {
+            fs::write(mod_path.join(format!("addons/file{}.pbo", i)), "data").unwrap();
+        }
+
+        static CANCEL: AtomicBool = AtomicBool::new(false);
+        let files = collect_mod_files(&mod_path);
+        let progress =
+            ProgressBar::new(files.len() as u64, 30, "Checking", Arc::new(THEME.clone()));
+        let pool = ThreadPool::new(1);
+
+        // Block the only worker, so the cancellation happens while the files are queued.
+        let (sender, receiver) = std::sync::mpsc::channel::<()>();
+        pool.execute(move || {
+            receiver.recv().unwrap();
+        });
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            CANCEL.store(true, Ordering::SeqCst);
+            sender.send(()).unwrap();
+        });
+
+        let result = calculate_checksums(&mod_path, files, &pool, &progress, &CANCEL, false);
+
+        assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::Interrupted);
+        assert_eq!(progress.position(), 0);
+
+        fs::remove_dir_all(&mod_path).unwrap();
+    }
+
+    #[test]
+    fn test_diff_checksums_compares_small_file_marker_by_size() {
+        let checksum = |size, hash: &str| FileChecksum {
+            size,
+            hash: hash.to_string(),
+        };
+        let old = BTreeMap::from([
+            (PathBuf::from("same.json"), checksum(10, SMALL_FILE_HASH)),
+            (PathBuf::from("grown.json"), checksum(10, SMALL_FILE_HASH)),
+        ]);
+        let new = BTreeMap::from([
+            (PathBuf::from("same.json"), checksum(10, "abc")),
+            (PathBuf::from("grown.json"), checksum(12, "def")),
+        ]);
+
+        assert_eq!(
+            diff_checksums(&old, &new).changed,
+            vec![PathBuf::from("grown.json")]
+        );
+    }
 }
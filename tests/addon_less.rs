@@ -0,0 +1,47 @@
+use std::{fs, process::Command};
+
+use serde_json::json;
+
+#[test]
+fn test_install_key_only_mod_logs_no_error() {
+    let base = std::env::temp_dir().join("dayz_tool_key_only_install");
+    let workshop = base.join("workshop");
+    let workdir = base.join("workdir");
+    let source = workshop.join("@KeysOnly");
+    let _ = fs::remove_dir_all(&base);
+
+    fs::create_dir_all(source.join("keys")).unwrap();
+    fs::write(source.join("keys/keysonly.bikey"), "key").unwrap();
+    fs::write(source.join("meta.cpp"), "name = \"KeysOnly\";").unwrap();
+    fs::create_dir_all(workdir.join("keys")).unwrap();
+
+    fs::create_dir_all(base.join(".dayz-tool")).unwrap();
+    let config = json!({
+        "profiles": [{
+            "name": "Chernarus",
+            "workdirPath": workdir,
+            "workshopPath": workshop,
+            "startParameters": "",
+            "installedMods": [],
+            "isActive": true
+        }]
+    });
+    fs::write(base.join(".dayz-tool/config.json"), config.to_string()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dayz-tool-cli"))
+        .args(["--interactive", "false", "mods", "install", "@KeysOnly"])
+        .env("HOME", &base)
+        .env("USERPROFILE", &base)
+        .env_remove("DAYZ_TOOL_CONFIG")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("DAYZ_TOOL_PROFILE")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let logs = [output.stdout, output.stderr].concat();
+    assert!(!String::from_utf8(logs).unwrap().contains("[ERROR]"));
+    assert!(workdir.join("keys/keysonly.bikey").exists());
+
+    fs::remove_dir_all(&base).unwrap();
+}
@@ -0,0 +1,159 @@
+use std::{
+    fs,
+    process::Command,
+    sync::{Mutex, MutexGuard},
+};
+
+use dayz_tool_cli::{
+    commands::{generate_admins, write_dnc},
+    set_dry_run,
+};
+use serde_json::json;
+
+/// Serializes the tests, the dry-run flag is global.
+static DRY_RUN_LOCK: Mutex<()> = Mutex::new(());
+
+/// Enables dry-run while holding `DRY_RUN_LOCK` and disables it again when dropped, also
+/// if the test panics. A lock poisoned by a failed test is still taken.
+struct DryRunGuard {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl DryRunGuard {
+    fn enable() -> Self {
+        let lock = DRY_RUN_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        set_dry_run(true);
+        DryRunGuard { _lock: lock }
+    }
+}
+
+impl Drop for DryRunGuard {
+    fn drop(&mut self) {
+        set_dry_run(false);
+    }
+}
+
+#[test]
+fn test_install_under_dry_run_changes_nothing() {
+    let base = std::env::temp_dir().join("dayz_tool_dry_run_install");
+    let workshop = base.join("workshop");
+    let workdir = base.join("workdir");
+    let mission = workdir.join("mpmissions/dayzOffline.chernarusplus");
+    let _ = fs::remove_dir_all(&base);
+
+    let source = workshop.join("@DryRun");
+    fs::create_dir_all(source.join("keys")).unwrap();
+    fs::create_dir_all(source.join("types")).unwrap();
+    fs::write(source.join("keys/dryrun.bikey"), "key").unwrap();
+    fs::write(
+        source.join("types/types.xml"),
+        "<types>\n<type name=\"Apple\">\n<nominal>10</nominal>\n</type>\n</types>\n",
+    )
+    .unwrap();
+    fs::create_dir_all(workdir.join("keys")).unwrap();
+    fs::create_dir_all(&mission).unwrap();
+    fs::write(
+        workdir.join("serverDZ.cfg"),
+        "template=\"dayzOffline.chernarusplus\";",
+    )
+    .unwrap();
+    let cfgeconomy = "<economycore>\n</economycore>";
+    fs::write(mission.join("cfgeconomycore.xml"), cfgeconomy).unwrap();
+
+    fs::create_dir_all(base.join(".dayz-tool")).unwrap();
+    let config = json!({
+        "profiles": [{
+            "name": "Chernarus",
+            "workdirPath": workdir,
+            "workshopPath": workshop,
+            "startParameters": "",
+            "installedMods": [],
+            "isActive": true
+        }]
+    })
+    .to_string();
+    let config_path = base.join(".dayz-tool/config.json");
+    fs::write(&config_path, &config).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dayz-tool-cli"))
+        .args([
+            "--interactive",
+            "false",
+            "--dry-run",
+            "mods",
+            "install",
+            "@DryRun",
+        ])
+        .env("HOME", &base)
+        .env("USERPROFILE", &base)
+        .env_remove("DAYZ_TOOL_CONFIG")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("DAYZ_TOOL_PROFILE")
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!workdir.join("@DryRun").exists());
+    assert_eq!(fs::read_dir(workdir.join("keys")).unwrap().count(), 0);
+    assert_eq!(fs::read_dir(&mission).unwrap().count(), 1);
+    assert_eq!(
+        fs::read_to_string(mission.join("cfgeconomycore.xml")).unwrap(),
+        cfgeconomy
+    );
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), config);
+
+    fs::remove_dir_all(&base).unwrap();
+}
+
+#[test]
+fn test_dnc_write_under_dry_run_reports_changes() {
+    let base = std::env::temp_dir().join("dayz_tool_dry_run_dnc");
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(&base).unwrap();
+    let cfg_path = base.join("serverDZ.cfg");
+    let content =
+        "hostname = \"Server\";\nserverTimeAcceleration = 4;\nserverNightTimeAcceleration = 1;\n";
+    fs::write(&cfg_path, content).unwrap();
+
+    let dry_run = DryRunGuard::enable();
+    let changes = write_dnc(&cfg_path, 1.5, 48.0).unwrap();
+    drop(dry_run);
+
+    assert_eq!(changes.len(), 2);
+    assert_eq!(
+        changes[0].before.as_deref(),
+        Some("serverTimeAcceleration = 4;")
+    );
+    assert_eq!(changes[0].after, "serverTimeAcceleration = 1.5;");
+    assert_eq!(
+        changes[1].before.as_deref(),
+        Some("serverNightTimeAcceleration = 1;")
+    );
+    assert_eq!(changes[1].after, "serverNightTimeAcceleration = 48;");
+    assert_eq!(fs::read_to_string(&cfg_path).unwrap(), content);
+
+    fs::remove_dir_all(&base).unwrap();
+}
+
+#[test]
+fn test_generate_admins_under_dry_run_writes_nothing() {
+    let base = std::env::temp_dir().join("dayz_tool_dry_run_admins");
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(&base).unwrap();
+    let file = base.join("admins.txt");
+    let output = base.join("admins_guid.txt");
+    fs::write(&file, "76561198039479171 //Karnes\n").unwrap();
+
+    let dry_run = DryRunGuard::enable();
+    let count = generate_admins(&file, &output).unwrap();
+    drop(dry_run);
+
+    assert_eq!(count, 1);
+    assert!(!output.exists());
+
+    fs::remove_dir_all(&base).unwrap();
+}
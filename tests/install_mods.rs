@@ -0,0 +1,77 @@
+use std::{fs, process::Command};
+
+use serde_json::{json, Value};
+
+#[test]
+fn test_install_without_ce_registration() {
+    let home = std::env::temp_dir().join("dayz_tool_install_no_ce_register");
+    let workshop = home.join("workshop");
+    let workdir = home.join("workdir");
+    let mission = workdir.join("mpmissions/dayzOffline.chernarusplus");
+    let _ = fs::remove_dir_all(&home);
+
+    let source = workshop.join("@Manual");
+    fs::create_dir_all(source.join("types")).unwrap();
+    fs::write(
+        source.join("types/types.xml"),
+        "<types>\n<type name=\"Apple\">\n<nominal>10</nominal>\n</type>\n</types>\n",
+    )
+    .unwrap();
+    fs::create_dir_all(workdir.join("keys")).unwrap();
+    fs::create_dir_all(&mission).unwrap();
+    fs::write(
+        workdir.join("serverDZ.cfg"),
+        "template=\"dayzOffline.chernarusplus\";",
+    )
+    .unwrap();
+    let cfgeconomy = "<economycore>\n</economycore>";
+    fs::write(mission.join("cfgeconomycore.xml"), cfgeconomy).unwrap();
+
+    fs::create_dir_all(home.join(".dayz-tool")).unwrap();
+    let config = json!({
+        "profiles": [{
+            "name": "Chernarus",
+            "workdirPath": workdir,
+            "workshopPath": workshop,
+            "startParameters": "",
+            "installedMods": [],
+            "isActive": true
+        }]
+    });
+    let config_path = home.join(".dayz-tool/config.json");
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dayz-tool-cli"))
+        .args([
+            "--interactive",
+            "false",
+            "mods",
+            "install",
+            "@Manual",
+            "--no-ce-register",
+        ])
+        .env("HOME", &home)
+        .env("USERPROFILE", &home)
+        .env_remove("DAYZ_TOOL_CONFIG")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("DAYZ_TOOL_PROFILE")
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(workdir.join("@Manual/types/types.xml").exists());
+    assert_eq!(
+        fs::read_to_string(mission.join("cfgeconomycore.xml")).unwrap(),
+        cfgeconomy
+    );
+
+    let config: Value = serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(config["profiles"][0]["installedMods"], json!(["@Manual"]));
+    assert_eq!(config["profiles"][0]["manualCeMods"], json!(["@Manual"]));
+
+    fs::remove_dir_all(&home).unwrap();
+}
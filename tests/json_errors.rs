@@ -0,0 +1,73 @@
+use std::{fs, process::Command};
+
+use serde_json::{json, Value};
+
+#[test]
+fn test_failing_command_prints_json_error() {
+    let home = std::env::temp_dir().join("dayz_tool_json_errors");
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(home.join(".dayz-tool")).unwrap();
+    let config = json!({
+        "profiles": [{
+            "name": "Inactive",
+            "workdirPath": "/srv/dayz",
+            "workshopPath": "/srv/!Workshop",
+            "startParameters": "",
+            "installedMods": [],
+            "isActive": false
+        }]
+    });
+    fs::write(home.join(".dayz-tool/config.json"), config.to_string()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dayz-tool-cli"))
+        .args(["--json-errors", "profile", "current"])
+        .env("HOME", &home)
+        .env("USERPROFILE", &home)
+        .env_remove("DAYZ_TOOL_CONFIG")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("DAYZ_TOOL_PROFILE")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let error: Value = serde_json::from_str(stderr.lines().last().unwrap()).unwrap();
+    assert_eq!(error["code"], 1);
+    assert_eq!(error["kind"], "NoActiveProfile");
+    assert_eq!(
+        error["message"],
+        "Failed to get the active profile: No active profile found"
+    );
+    assert_eq!(
+        error["context"]["configPath"],
+        home.join(".dayz-tool/config.json").to_str().unwrap()
+    );
+
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_missing_config_dir_prints_json_error() {
+    let cwd = std::env::temp_dir().join("dayz_tool_json_errors_no_config");
+    let _ = fs::remove_dir_all(&cwd);
+    fs::create_dir_all(&cwd).unwrap();
+
+    // Without HOME and with a deleted working directory, no config directory is found.
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(r#"rmdir "$PWD" && exec "$0" --json-errors profile current"#)
+        .arg(env!("CARGO_BIN_EXE_dayz-tool-cli"))
+        .current_dir(&cwd)
+        .env_remove("HOME")
+        .env_remove("USERPROFILE")
+        .env_remove("DAYZ_TOOL_CONFIG")
+        .env_remove("XDG_CONFIG_HOME")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let error: Value = serde_json::from_str(stderr.lines().last().unwrap()).unwrap();
+    assert_eq!(error["kind"], "NoConfigDir");
+}
@@ -0,0 +1,168 @@
+use std::{
+    fs,
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use serde_json::{json, Value};
+
+#[test]
+fn test_prompting_command_fails_non_interactively() {
+    let home = std::env::temp_dir().join("dayz_tool_non_interactive");
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(home.join(".dayz-tool")).unwrap();
+    let config = json!({
+        "profiles": [{
+            "name": "Chernarus",
+            "workdirPath": "/srv/dayz",
+            "workshopPath": "/srv/!Workshop",
+            "startParameters": "",
+            "installedMods": [],
+            "isActive": true
+        }, {
+            "name": "Livonia",
+            "workdirPath": "/srv/livonia",
+            "workshopPath": "/srv/!Workshop",
+            "startParameters": "",
+            "installedMods": [],
+            "isActive": false
+        }]
+    });
+    fs::write(home.join(".dayz-tool/config.json"), config.to_string()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dayz-tool-cli"))
+        .args(["--interactive", "false", "--json-errors", "profile", "use"])
+        .env("HOME", &home)
+        .env("USERPROFILE", &home)
+        .env_remove("DAYZ_TOOL_CONFIG")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("DAYZ_TOOL_PROFILE")
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let error: Value = serde_json::from_str(stderr.lines().last().unwrap()).unwrap();
+    assert_eq!(error["kind"], "NonInteractive");
+    assert_eq!(
+        error["message"],
+        "Failed to switch profile: input required but running non-interactively; provide the profile with DAYZ_TOOL_PROFILE"
+    );
+
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_clean_backups_yes_skips_the_confirmation() {
+    let home = std::env::temp_dir().join("dayz_tool_non_interactive_clean_backups");
+    let _ = fs::remove_dir_all(&home);
+    let workdir = home.join("server");
+    let snapshot = workdir.join(".dayz-backups/2020-01-01_00-00-00");
+    fs::create_dir_all(home.join(".dayz-tool")).unwrap();
+    fs::create_dir_all(snapshot.join("@CF")).unwrap();
+    fs::write(snapshot.join("manifest.json"), "{}").unwrap();
+    fs::File::options()
+        .write(true)
+        .open(snapshot.join("manifest.json"))
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(60 * 24 * 60 * 60))
+        .unwrap();
+    let config = json!({
+        "profiles": [{
+            "name": "Chernarus",
+            "workdirPath": workdir,
+            "workshopPath": "/srv/!Workshop",
+            "startParameters": "",
+            "installedMods": [],
+            "isActive": true
+        }]
+    });
+    fs::write(home.join(".dayz-tool/config.json"), config.to_string()).unwrap();
+
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_dayz-tool-cli"))
+            .args(["--interactive", "false", "--json-errors", "clean-backups"])
+            .args(args)
+            .env("HOME", &home)
+            .env("USERPROFILE", &home)
+            .env_remove("DAYZ_TOOL_CONFIG")
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("DAYZ_TOOL_PROFILE")
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let error: Value = serde_json::from_str(stderr.lines().last().unwrap()).unwrap();
+    assert_eq!(
+        error["message"],
+        "Failed to clean backups: input required but running non-interactively; provide --yes to confirm the removal"
+    );
+    assert!(snapshot.exists());
+
+    let output = run(&["--yes"]);
+    assert!(output.status.success());
+    assert!(!snapshot.exists());
+
+    fs::remove_dir_all(&home).unwrap();
+}
+
+#[test]
+fn test_uninstall_names_skip_the_selection() {
+    let home = std::env::temp_dir().join("dayz_tool_non_interactive_uninstall");
+    let _ = fs::remove_dir_all(&home);
+    let workdir = home.join("server");
+    fs::create_dir_all(home.join(".dayz-tool")).unwrap();
+    fs::create_dir_all(workdir.join("@CF/addons")).unwrap();
+    fs::create_dir_all(workdir.join("keys")).unwrap();
+    fs::create_dir_all(workdir.join("mpmissions/dayzOffline.chernarusplus")).unwrap();
+    let config = json!({
+        "profiles": [{
+            "name": "Chernarus",
+            "workdirPath": workdir,
+            "workshopPath": home.join("workshop"),
+            "startParameters": "",
+            "installedMods": ["@CF"],
+            "isActive": true
+        }]
+    });
+    let config_path = home.join(".dayz-tool/config.json");
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_dayz-tool-cli"))
+            .args([
+                "--interactive",
+                "false",
+                "--json-errors",
+                "mods",
+                "uninstall",
+            ])
+            .args(args)
+            .env("HOME", &home)
+            .env("USERPROFILE", &home)
+            .env_remove("DAYZ_TOOL_CONFIG")
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("DAYZ_TOOL_PROFILE")
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&[]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let error: Value = serde_json::from_str(stderr.lines().last().unwrap()).unwrap();
+    assert_eq!(
+        error["message"],
+        "Failed to uninstall mods: input required but running non-interactively; provide the mod names with `mods uninstall <name>...`"
+    );
+    assert!(workdir.join("@CF").exists());
+
+    let output = run(&["@CF"]);
+    assert!(output.status.success());
+    assert!(!workdir.join("@CF").exists());
+    let config: Value = serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(config["profiles"][0]["installedMods"], json!([]));
+
+    fs::remove_dir_all(&home).unwrap();
+}
@@ -0,0 +1,53 @@
+use std::{fs, path::Path, process::Command};
+
+use serde_json::json;
+
+fn current_profile(home: &Path, profile_override: &str) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_dayz-tool-cli"))
+        .args(["profile", "current"])
+        .env("HOME", home)
+        .env("USERPROFILE", home)
+        .env_remove("DAYZ_TOOL_CONFIG")
+        .env_remove("XDG_CONFIG_HOME")
+        .env("DAYZ_TOOL_PROFILE", profile_override)
+        .output()
+        .unwrap();
+
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap().trim().to_string(),
+    )
+}
+
+#[test]
+fn test_env_override_selects_named_profile() {
+    let home = std::env::temp_dir().join("dayz_tool_profile_override");
+    let _ = fs::remove_dir_all(&home);
+    fs::create_dir_all(home.join(".dayz-tool")).unwrap();
+    let profile = |name: &str, is_active: bool| {
+        json!({
+            "name": name,
+            "workdirPath": "/srv/dayz",
+            "workshopPath": "/srv/!Workshop",
+            "startParameters": "",
+            "installedMods": [],
+            "isActive": is_active
+        })
+    };
+    let config = json!({ "profiles": [profile("Chernarus", true), profile("Livonia", false)] });
+    let config_path = home.join(".dayz-tool/config.json");
+    fs::write(&config_path, config.to_string()).unwrap();
+
+    assert_eq!(current_profile(&home, ""), (true, "Chernarus".to_string()));
+    assert_eq!(
+        current_profile(&home, "Livonia"),
+        (true, "Livonia".to_string())
+    );
+    assert_eq!(current_profile(&home, "Sakhal"), (false, String::new()));
+
+    let stored: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&config_path).unwrap()).unwrap();
+    assert_eq!(stored, config);
+
+    fs::remove_dir_all(&home).unwrap();
+}
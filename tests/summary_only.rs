@@ -0,0 +1,64 @@
+use std::{fs, path::Path, process::Command};
+
+use serde_json::json;
+
+fn update_mods(home: &Path, summary_only: bool) -> String {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_dayz-tool-cli"));
+    command
+        .args(["mods", "update"])
+        .env("HOME", home)
+        .env("USERPROFILE", home)
+        .env_remove("DAYZ_TOOL_CONFIG")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("DAYZ_TOOL_PROFILE");
+    if summary_only {
+        command.arg("--summary-only");
+    }
+    let output = command.output().unwrap();
+    assert!(output.status.success());
+
+    format!(
+        "{}{}",
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap()
+    )
+}
+
+#[test]
+fn test_summary_only_hides_per_mod_logs() {
+    let home = std::env::temp_dir().join("dayz_tool_summary_only");
+    let workdir = home.join("workdir");
+    let workshop = home.join("workshop");
+    let _ = fs::remove_dir_all(&home);
+
+    for dir in [&workdir, &workshop] {
+        fs::create_dir_all(dir.join("@Current/addons")).unwrap();
+        fs::write(dir.join("@Current/addons/current.pbo"), "pbo").unwrap();
+    }
+    fs::create_dir_all(home.join(".dayz-tool")).unwrap();
+    let config = json!({
+        "profiles": [{
+            "name": "Chernarus",
+            "workdirPath": workdir,
+            "workshopPath": workshop,
+            "startParameters": "",
+            "installedMods": ["@Current"],
+            "isActive": true
+        }]
+    });
+    fs::write(home.join(".dayz-tool/config.json"), config.to_string()).unwrap();
+
+    let summary =
+        "Update finished: 0 updated, 1 up to date, 0 missing from workshop, 0 excluded, 0 failed";
+
+    let output = update_mods(&home, false);
+    assert!(output.contains("Mod @Current is up to date, skipping"));
+    assert!(output.contains(summary));
+
+    let output = update_mods(&home, true);
+    assert!(!output.contains("Mod @Current is up to date, skipping"));
+    assert!(!output.contains("Starting mod updates"));
+    assert!(output.contains(summary));
+
+    fs::remove_dir_all(&home).unwrap();
+}